@@ -193,7 +193,7 @@ pub fn SVG(
 	});
 
 	let mut used_font = use_signal(|| HashSet::new());
-	let mut add_font = move |v: (FontRef, FontWeight)| {
+	let mut add_font = move |v: (FontRef, FontProperties)| {
 		used_font.write().insert(v);
 	};
 
@@ -218,7 +218,7 @@ pub fn SVG(
 fn Shaper(
 	shape: ReadOnlySignal<Shape>,
 	parent_transform: Transform2<f32>,
-	add_font: EventHandler<(FontRef, FontWeight)>,
+	add_font: EventHandler<(FontRef, FontProperties)>,
 ) -> Element {
 	match shape() {
 		Shape::Group(dessin::shapes::Group {
@@ -241,55 +241,130 @@ fn Shaper(
 			stroke,
 			shape,
 		} => {
-			let fill = match fill {
-				Some(Fill::Solid { color }) => format!(
+			let gradient = match &fill {
+				Some(fill @ (Fill::LinearGradient { .. } | Fill::RadialGradient { .. })) => {
+					static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+					let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+
+					Some((format!("gradient{id}"), parent_transform * fill.clone()))
+				}
+				_ => None,
+			};
+
+			let fill = match (fill, &gradient) {
+				(Some(Fill::Solid { color }), _) => format!(
 					"rgb({} {} {} / {:.3})",
 					(color.red * 255.) as u32,
 					(color.green * 255.) as u32,
 					(color.blue * 255.) as u32,
 					color.alpha
 				),
-				None => "none".to_string(),
+				(Some(_), Some((id, _))) => format!("url(#{id})"),
+				_ => "none".to_string(),
 			};
 
-			let (stroke, stroke_width, stroke_dasharray) = match stroke {
-				Some(Stroke::Dashed {
-					color,
-					width,
-					on,
-					off,
-				}) => (
+			let (
+				stroke,
+				stroke_width,
+				stroke_linecap,
+				stroke_linejoin,
+				stroke_miterlimit,
+				stroke_dasharray,
+				stroke_dashoffset,
+			) = match stroke {
+				Some(stroke) => (
 					Some(format!(
 						"rgb({} {} {} / {:.3})",
-						(color.red * 255.) as u32,
-						(color.green * 255.) as u32,
-						(color.blue * 255.) as u32,
-						color.alpha
+						(stroke.color.red * 255.) as u32,
+						(stroke.color.green * 255.) as u32,
+						(stroke.color.blue * 255.) as u32,
+						stroke.color.alpha
 					)),
-					Some(width),
-					Some(format!("{on},{off}")),
+					Some(stroke.width),
+					Some(match stroke.cap {
+						LineCap::Butt => "butt",
+						LineCap::Round => "round",
+						LineCap::Square => "square",
+					}),
+					Some(match stroke.join {
+						LineJoin::Miter => "miter",
+						LineJoin::Round => "round",
+						LineJoin::Bevel => "bevel",
+					}),
+					Some(stroke.miter_limit),
+					stroke.is_dashed().then(|| {
+						stroke
+							.dash
+							.iter()
+							.map(f32::to_string)
+							.collect::<Vec<_>>()
+							.join(",")
+					}),
+					stroke.is_dashed().then_some(stroke.dash_offset),
 				),
-				Some(Stroke::Solid { color, width }) => (
-					Some(format!(
-						"rgb({} {} {} / {:.3})",
-						(color.red * 255.) as u32,
-						(color.green * 255.) as u32,
-						(color.blue * 255.) as u32,
-						color.alpha
-					)),
-					Some(width),
-					None,
-				),
-				None => (None, None, None),
+				None => (None, None, None, None, None, None, None),
 			};
 
 			rsx! {
+				if let Some((id, fill)) = gradient {
+					Gradient { id, fill }
+				}
 				g {
 					fill,
 					stroke,
 					stroke_width,
+					stroke_linecap,
+					stroke_linejoin,
+					stroke_miterlimit,
 					stroke_dasharray,
+					stroke_dashoffset,
+
+					Shaper { parent_transform, shape: *shape, add_font }
+				}
+			}
+		}
+		Shape::Clip { clip, shape } => {
+			static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+			let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+
+			rsx! {
+				defs {
+					clipPath { id: "clip{id}",
+						path {
+							d: write_curve(clip.position(&parent_transform)),
+							clip_rule: fill_rule(clip.fill_rule),
+						}
+					}
+				}
+				g { clip_path: "url(#clip{id})",
+					Shaper { parent_transform, shape: *shape, add_font }
+				}
+			}
+		}
+		Shape::Mask { mask, kind, shape } => {
+			static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+			let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
 
+			let bb = mask.global_bounding_box(&parent_transform).straigthen();
+			let mask_type = match kind {
+				MaskKind::Luminance => "luminance",
+				MaskKind::Alpha => "alpha",
+			};
+
+			rsx! {
+				defs {
+					mask {
+						id: "mask{id}",
+						"maskUnits": "userSpaceOnUse",
+						x: bb.left(),
+						y: bb.bottom(),
+						width: bb.width(),
+						height: bb.height(),
+						style: "mask-type:{mask_type}",
+						Shaper { parent_transform, shape: *mask, add_font }
+					}
+				}
+				g { mask: "url(#mask{id})",
 					Shaper { parent_transform, shape: *shape, add_font }
 				}
 			}
@@ -338,17 +413,19 @@ fn Shaper(
 
 			let text = text.position(&parent_transform);
 
-			let weight = match text.font_weight {
-				FontWeight::Bold | FontWeight::BoldItalic => "bold",
-				_ => "normal",
-			};
-			let text_style = match text.font_weight {
-				FontWeight::Italic | FontWeight::BoldItalic => "italic",
-				_ => "normal",
-			};
+			let properties = text.font_properties();
+			let weight = text.font_weight.0;
+			let style = dessin_svg::css_font_style(text.font_style);
+			let stretch = dessin_svg::css_font_stretch(text.font_stretch);
+			let letter_spacing =
+				(text.letter_spacing != 0.).then(|| format!("{}px", text.letter_spacing));
+			let word_spacing =
+				(text.word_spacing != 0.).then(|| format!("{}px", text.word_spacing));
+			let decoration = (!text.decoration.is_none())
+				.then(|| dessin_svg::css_text_decoration(text.decoration));
 			let align = match text.align {
 				TextAlign::Center => "middle",
-				TextAlign::Left => "start",
+				TextAlign::Left | TextAlign::Justify => "start",
 				TextAlign::Right => "end",
 			};
 
@@ -361,12 +438,16 @@ fn Shaper(
 
 			rsx! {
 				text {
-					onmounted: move |_| add_font((font_ref.clone(), text.font_weight)),
+					onmounted: move |_| add_font((font_ref.clone(), properties)),
 					font_family: "{font_ref2}",
 					text_anchor: "{align}",
 					font_size: "{text.font_size}px",
 					font_weight: "{weight}",
-					"text-style": "{text_style}",
+					font_style: "{style}",
+					font_stretch: "{stretch}",
+					letter_spacing,
+					word_spacing,
+					text_decoration: decoration,
 					transform: "translate({x} {y}) rotate({r})",
 					if let Some(curve) = text.on_curve {
 						path { id: "{id}", d: write_curve(curve) }
@@ -378,7 +459,10 @@ fn Shaper(
 			}
 		}
 		Shape::Curve(curve) => rsx! {
-			path { d: write_curve(curve.position(&parent_transform)) }
+			path {
+				d: write_curve(curve.position(&parent_transform)),
+				fill_rule: fill_rule(curve.fill_rule),
+			}
 		},
 		Shape::Dynamic {
 			local_transform,
@@ -393,47 +477,167 @@ fn Shaper(
 	}
 }
 
+#[component]
+fn Gradient(id: String, fill: Fill) -> Element {
+	let (stops, spread, transform) = match &fill {
+		Fill::Solid { .. } => return rsx! {},
+		Fill::LinearGradient {
+			stops,
+			spread,
+			transform,
+			..
+		}
+		| Fill::RadialGradient {
+			stops,
+			spread,
+			transform,
+			..
+		} => (stops.clone(), *spread, *transform),
+	};
+
+	let spread = match spread {
+		GradientSpread::Pad => "pad",
+		GradientSpread::Reflect => "reflect",
+		GradientSpread::Repeat => "repeat",
+	};
+	let m = transform.matrix();
+	let gradient_transform = format!(
+		"matrix({} {} {} {} {} {})",
+		m[(0, 0)],
+		m[(1, 0)],
+		m[(0, 1)],
+		m[(1, 1)],
+		m[(0, 2)],
+		m[(1, 2)],
+	);
+
+	let stops = stops.into_iter().map(|GradientStop { offset, color }| {
+		let stop_color = format!(
+			"rgb({} {} {})",
+			(color.red * 255.) as u32,
+			(color.green * 255.) as u32,
+			(color.blue * 255.) as u32,
+		);
+		rsx! {
+			stop {
+				offset,
+				stop_color,
+				stop_opacity: color.alpha,
+			}
+		}
+	});
+
+	match fill {
+		Fill::Solid { .. } => rsx! {},
+		Fill::LinearGradient { start, end, .. } => rsx! {
+			defs {
+				linearGradient {
+					id,
+					x1: start.x,
+					y1: start.y,
+					x2: end.x,
+					y2: end.y,
+					"gradientUnits": "userSpaceOnUse",
+					"spreadMethod": spread,
+					"gradientTransform": gradient_transform,
+					{stops}
+				}
+			}
+		},
+		Fill::RadialGradient {
+			center,
+			radius,
+			focal,
+			..
+		} => rsx! {
+			defs {
+				radialGradient {
+					id,
+					cx: center.x,
+					cy: center.y,
+					r: radius,
+					fx: focal.x,
+					fy: focal.y,
+					"gradientUnits": "userSpaceOnUse",
+					"spreadMethod": spread,
+					"gradientTransform": gradient_transform,
+					{stops}
+				}
+			}
+		},
+	}
+}
+
+fn fill_rule(fill_rule: FillRule) -> &'static str {
+	match fill_rule {
+		FillRule::NonZero => "nonzero",
+		FillRule::EvenOdd => "evenodd",
+	}
+}
+
 fn write_curve(curve: CurvePosition) -> String {
 	let mut acc = String::new();
-	let mut is_first = true;
-
-	for keypoint in &curve.keypoints {
-		match keypoint {
-			KeypointPosition::Point(p) => {
-				acc.push_str(&format!(
-					"{} {} {} ",
-					if is_first { "M" } else { "L" },
-					p.x,
-					p.y
-				));
-			}
-			KeypointPosition::Bezier(b) => {
-				if let Some(v) = b.start {
+
+	for subpath in curve.iter_subpaths() {
+		let mut is_first = true;
+
+		for keypoint in &subpath.keypoints {
+			match keypoint {
+				KeypointPosition::Point(p) => {
 					acc.push_str(&format!(
 						"{} {} {} ",
 						if is_first { "M" } else { "L" },
-						v.x,
-						v.y
+						p.x,
+						p.y
 					));
 				}
+				KeypointPosition::Bezier(Bezier { start, .. })
+				| KeypointPosition::Quadratic(QuadraticBezier { start, .. })
+				| KeypointPosition::ArcTo(EllipticalArc { start, .. }) => {
+					if let Some(v) = start {
+						acc.push_str(&format!(
+							"{} {} {} ",
+							if is_first { "M" } else { "L" },
+							v.x,
+							v.y
+						));
+					}
 
-				acc.push_str(&format!(
-					"C {start_ctrl_x} {start_ctrl_y} {end_ctrl_x} {end_ctrl_y} {end_x} {end_y} ",
-					start_ctrl_x = b.start_control.x,
-					start_ctrl_y = b.start_control.y,
-					end_ctrl_x = b.end_control.x,
-					end_ctrl_y = b.end_control.y,
-					end_x = b.end.x,
-					end_y = b.end.y,
-				));
+					match keypoint {
+						KeypointPosition::Bezier(b) => acc.push_str(&format!(
+							"C {start_ctrl_x} {start_ctrl_y} {end_ctrl_x} {end_ctrl_y} {end_x} {end_y} ",
+							start_ctrl_x = b.start_control.x,
+							start_ctrl_y = b.start_control.y,
+							end_ctrl_x = b.end_control.x,
+							end_ctrl_y = b.end_control.y,
+							end_x = b.end.x,
+							end_y = b.end.y,
+						)),
+						KeypointPosition::Quadratic(q) => acc.push_str(&format!(
+							"Q {} {} {} {} ",
+							q.control.x, q.control.y, q.end.x, q.end.y,
+						)),
+						KeypointPosition::ArcTo(a) => acc.push_str(&format!(
+							"A {} {} {} {} {} {} {} ",
+							a.radii.x,
+							a.radii.y,
+							a.rotation.to_degrees(),
+							a.large_arc as u8,
+							a.sweep as u8,
+							a.end.x,
+							a.end.y,
+						)),
+						KeypointPosition::Point(_) => unreachable!(),
+					}
+				}
 			}
-		}
 
-		is_first = false;
-	}
+			is_first = false;
+		}
 
-	if curve.closed {
-		acc.push_str(&format!("Z"));
+		if subpath.closed {
+			acc.push_str("Z ");
+		}
 	}
 
 	acc
@@ -3,20 +3,26 @@
 use ::image::{DynamicImage, RgbaImage};
 use dessin::{
 	export::{Export, Exporter},
+	font::FontRef,
+	palette::Srgba,
 	prelude::*,
 };
 use nalgebra::{Point2, Transform2, Translation2, Vector2};
 use raqote::{
-	DrawOptions, DrawTarget, LineCap, LineJoin, PathBuilder, Point, SolidSource, Source,
-	StrokeStyle,
+	Color, DrawOptions, DrawTarget, Gradient, GradientStop, Image, Mask, Path, PathBuilder, Point,
+	SolidSource, Source, Spread, StrokeStyle, Winding,
+};
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	fmt,
 };
-use std::fmt;
 
 #[derive(Debug)]
 pub enum ImageError {
 	WriteError(fmt::Error),
 	CurveHasNoStartingPoint(CurvePosition),
 	FontLoadingError(font_kit::error::FontLoadingError),
+	FontError(dessin::font::FontError),
 	ImageError,
 }
 impl fmt::Display for ImageError {
@@ -36,9 +42,19 @@ pub struct ImageOptions {
 	pub canvas: Option<(f32, f32)>,
 }
 
+enum ClipLayer {
+	/// Clip pushed on the buffer
+	Curve,
+	/// Shapes are drawn on a new buffer, masked when drawn back on `parent`
+	Mask { parent: Box<DrawTarget>, mask: Mask },
+}
+
 pub struct ImageExporter {
 	buffer: DrawTarget,
 	style: Vec<StylePosition>,
+	clips: Vec<ClipLayer>,
+	/// Fonts loaded for drawing, by font and weight
+	fonts: HashMap<(FontRef, FontProperties), font_kit::font::Font>,
 }
 
 impl ImageExporter {
@@ -46,6 +62,8 @@ impl ImageExporter {
 		ImageExporter {
 			buffer: DrawTarget::new(width as i32, height as i32),
 			style: vec![],
+			clips: vec![],
+			fonts: HashMap::new(),
 		}
 	}
 
@@ -60,14 +78,12 @@ impl ImageExporter {
 		};
 
 		for style in self.style.iter().rev() {
-			match (acc.fill, style.fill) {
-				(None, Some(s)) => acc.fill = Some(s),
-				_ => {}
+			if let (None, Some(s)) = (&acc.fill, &style.fill) {
+				acc.fill = Some(s.clone());
 			}
 
-			match (acc.stroke, style.stroke) {
-				(None, Some(s)) => acc.stroke = Some(s),
-				_ => {}
+			if let (None, Some(s)) = (&acc.stroke, &style.stroke) {
+				acc.stroke = Some(s.clone());
 			}
 
 			if acc.fill.is_some() && acc.fill.is_some() {
@@ -79,6 +95,172 @@ impl ImageExporter {
 	}
 }
 
+fn curve_path(curve: &CurvePosition) -> Result<Path, ImageError> {
+	let mut path = PathBuilder::new();
+
+	// Quadratic beziers and arcs are drawn as cubic beziers
+	for subpath in curve.to_cubics().iter_subpaths() {
+		for (idx, k) in subpath.keypoints.iter().enumerate() {
+			let is_first = idx == 0;
+
+			match k {
+				KeypointPosition::Point(p) if is_first => path.move_to(p.x, p.y),
+				KeypointPosition::Point(p) => path.line_to(p.x, p.y),
+				KeypointPosition::Bezier(b) => {
+					match (is_first, b.start) {
+						(true, None) => {
+							return Err(ImageError::CurveHasNoStartingPoint(curve.clone()))
+						}
+						(true, Some(s)) => path.move_to(s.x, s.y),
+						(false, None) => {}
+						(false, Some(s)) => path.line_to(s.x, s.y),
+					}
+
+					path.cubic_to(
+						b.start_control.x,
+						b.start_control.y,
+						b.end_control.x,
+						b.end_control.y,
+						b.end.x,
+						b.end.y,
+					);
+				}
+				KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+					unreachable!("converted to cubics")
+				}
+			}
+		}
+
+		if subpath.closed {
+			path.close()
+		}
+	}
+
+	let mut path = path.finish();
+	path.winding = match curve.fill_rule {
+		FillRule::NonZero => Winding::NonZero,
+		FillRule::EvenOdd => Winding::EvenOdd,
+	};
+
+	Ok(path)
+}
+
+/// Coverage of each pixel of a rendered mask
+fn mask_coverage(mask: &DrawTarget, kind: MaskKind) -> Mask {
+	let data = mask
+		.get_data()
+		.iter()
+		.map(|p| match kind {
+			// Pixels are premultiplied, with red and blue swapped
+			MaskKind::Luminance => {
+				let (r, g, b) = (p & 0xff, (p >> 8) & 0xff, (p >> 16) & 0xff);
+				(0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as u8
+			}
+			MaskKind::Alpha => (p >> 24) as u8,
+		})
+		.collect();
+
+	Mask {
+		width: mask.width(),
+		height: mask.height(),
+		data,
+	}
+}
+
+/// Raqote color, with red and blue swapped as the buffer is read as RGBA
+fn gradient_color(color: Srgba) -> Color {
+	let color = color.into_format::<u8, u8>();
+	Color::new(color.alpha, color.blue, color.green, color.red)
+}
+
+fn fill_source(fill: &Fill) -> Source<'static> {
+	if let Some(color) = fill.degenerate_color() {
+		return fill_source(&Fill::Solid { color });
+	}
+
+	let (stops, spread, transform) = match fill {
+		Fill::Solid { color } => {
+			let (r, g, b, a) = (
+				color.into_format::<u8, f32>().red,
+				color.into_format::<u8, f32>().green,
+				color.into_format::<u8, f32>().blue,
+				color.into_format::<u8, u8>().alpha,
+			);
+			return Source::Solid(SolidSource { r: b, g, b: r, a });
+		}
+		Fill::LinearGradient {
+			stops,
+			spread,
+			transform,
+			..
+		}
+		| Fill::RadialGradient {
+			stops,
+			spread,
+			transform,
+			..
+		} => (stops, spread, transform),
+	};
+
+	let gradient = Gradient {
+		stops: stops
+			.iter()
+			.map(|s| GradientStop {
+				position: s.offset,
+				color: gradient_color(s.color),
+			})
+			.collect(),
+	};
+	let spread = match spread {
+		GradientSpread::Pad => Spread::Pad,
+		GradientSpread::Reflect => Spread::Reflect,
+		GradientSpread::Repeat => Spread::Repeat,
+	};
+
+	// Raqote's transforms go from user space to gradient space
+	let inverse = transform.try_inverse().unwrap_or_else(Transform2::identity);
+	let m = inverse.matrix();
+	let inverse = raqote::Transform::new(
+		m[(0, 0)],
+		m[(1, 0)],
+		m[(0, 1)],
+		m[(1, 1)],
+		m[(0, 2)],
+		m[(1, 2)],
+	);
+
+	match fill {
+		Fill::Solid { .. } => unreachable!(),
+		Fill::LinearGradient { start, end, .. } => {
+			match Source::new_linear_gradient(
+				gradient,
+				Point::new(start.x, start.y),
+				Point::new(end.x, end.y),
+				spread,
+			) {
+				Source::LinearGradient(gradient, spread, t) => {
+					Source::LinearGradient(gradient, spread, inverse.then(&t))
+				}
+				source => source,
+			}
+		}
+		Fill::RadialGradient {
+			center,
+			radius,
+			focal,
+			..
+		} => Source::TwoCircleRadialGradient(
+			gradient,
+			spread,
+			Point::new(focal.x, focal.y),
+			0.,
+			Point::new(center.x, center.y),
+			*radius,
+			inverse,
+		),
+	}
+}
+
 impl Exporter for ImageExporter {
 	type Error = ImageError;
 
@@ -94,6 +276,69 @@ impl Exporter for ImageExporter {
 		Ok(())
 	}
 
+	fn start_clip(&mut self, clip: ClipPosition) -> Result<(), Self::Error> {
+		match clip {
+			ClipPosition::Curve(curve) => {
+				self.buffer.push_clip(&curve_path(&curve)?);
+				self.clips.push(ClipLayer::Curve);
+			}
+			ClipPosition::Mask {
+				mask,
+				kind,
+				parent_transform,
+			} => {
+				let (width, height) = (self.buffer.width(), self.buffer.height());
+
+				let mut mask_exporter = ImageExporter::new(width as u32, height as u32);
+				mask.write_into_exporter(
+					&mut mask_exporter,
+					&parent_transform,
+					StylePosition {
+						fill: None,
+						stroke: None,
+					},
+				)?;
+				let mask = mask_coverage(&mask_exporter.finalize(), kind);
+
+				let parent = std::mem::replace(&mut self.buffer, DrawTarget::new(width, height));
+				self.clips.push(ClipLayer::Mask {
+					parent: Box::new(parent),
+					mask,
+				});
+			}
+		}
+
+		Ok(())
+	}
+
+	fn end_clip(&mut self) -> Result<(), Self::Error> {
+		match self.clips.pop() {
+			Some(ClipLayer::Curve) => self.buffer.pop_clip(),
+			Some(ClipLayer::Mask { parent, mask }) => {
+				let masked = std::mem::replace(&mut self.buffer, *parent);
+				let image = Image {
+					width: masked.width(),
+					height: masked.height(),
+					data: masked.get_data(),
+				};
+				self.buffer.mask(
+					&Source::Image(
+						image,
+						raqote::ExtendMode::Pad,
+						raqote::FilterMode::Nearest,
+						raqote::Transform::identity(),
+					),
+					0,
+					0,
+					&mask,
+				);
+			}
+			None => {}
+		}
+
+		Ok(())
+	}
+
 	fn export_image(
 		&mut self,
 		ImagePosition {
@@ -134,151 +379,100 @@ impl Exporter for ImageExporter {
 	}
 
 	fn export_curve(&mut self, curve: CurvePosition, _: StylePosition) -> Result<(), Self::Error> {
-		let mut path = PathBuilder::new();
+		let path = curve_path(&curve)?;
 
-		for (idx, k) in curve.keypoints.iter().enumerate() {
-			let is_first = idx == 0;
-
-			match k {
-				KeypointPosition::Point(p) if is_first => path.move_to(p.x, p.y),
-				KeypointPosition::Point(p) => path.line_to(p.x, p.y),
-				KeypointPosition::Bezier(b) => {
-					match (is_first, b.start) {
-						(true, None) => return Err(ImageError::CurveHasNoStartingPoint(curve)),
-						(true, Some(s)) => path.move_to(s.x, s.y),
-						(false, None) => {}
-						(false, Some(s)) => path.line_to(s.x, s.y),
-					}
-
-					path.cubic_to(
-						b.start_control.x,
-						b.start_control.y,
-						b.end_control.x,
-						b.end_control.y,
-						b.end.x,
-						b.end.y,
-					);
-				}
-			}
-		}
+		let style = self.style();
 
-		if curve.closed {
-			path.close()
+		if let Some(fill) = &style.fill {
+			self.buffer
+				.fill(&path, &fill_source(fill), &DrawOptions::new())
 		}
 
-		let path = path.finish();
-
-		let style = self.style();
-
-		if let Some(Fill::Solid { color }) = style.fill {
+		if let Some(stroke) = style.stroke {
 			let (r, g, b, a) = (
-				color.into_format::<u8, f32>().red,
-				color.into_format::<u8, f32>().green,
-				color.into_format::<u8, f32>().blue,
-				color.into_format::<u8, u8>().alpha,
+				stroke.color.into_format::<u8, f32>().red,
+				stroke.color.into_format::<u8, f32>().green,
+				stroke.color.into_format::<u8, f32>().blue,
+				stroke.color.into_format::<u8, u8>().alpha,
 			);
-			self.buffer.fill(
+			self.buffer.stroke(
 				&path,
 				&Source::Solid(SolidSource { r: b, g, b: r, a }),
-				&DrawOptions::new(),
-			)
-		}
-
-		match style.stroke {
-			Some(Stroke::Solid { color, width }) => {
-				let (r, g, b, a) = (
-					color.into_format::<u8, f32>().red,
-					color.into_format::<u8, f32>().green,
-					color.into_format::<u8, f32>().blue,
-					color.into_format::<u8, u8>().alpha,
-				);
-				self.buffer.stroke(
-					&path,
-					&Source::Solid(SolidSource { r: b, g, b: r, a }),
-					&StrokeStyle {
-						cap: LineCap::Butt,
-						join: LineJoin::Miter,
-						width,
-						miter_limit: 2.,
-						dash_array: vec![],
-						dash_offset: 0.,
+				&StrokeStyle {
+					cap: match stroke.cap {
+						LineCap::Butt => raqote::LineCap::Butt,
+						LineCap::Round => raqote::LineCap::Round,
+						LineCap::Square => raqote::LineCap::Square,
 					},
-					&DrawOptions::new(),
-				);
-			}
-			Some(Stroke::Dashed {
-				color,
-				width,
-				on,
-				off,
-			}) => {
-				let (r, g, b, a) = (
-					color.into_format::<u8, f32>().red,
-					color.into_format::<u8, f32>().green,
-					color.into_format::<u8, f32>().blue,
-					color.into_format::<u8, u8>().alpha,
-				);
-				self.buffer.stroke(
-					&path,
-					&Source::Solid(SolidSource { r: b, g, b: r, a }),
-					&StrokeStyle {
-						cap: LineCap::Butt,
-						join: LineJoin::Miter,
-						width,
-						miter_limit: 2.,
-						dash_array: vec![on, off],
-						dash_offset: 0.,
+					join: match stroke.join {
+						LineJoin::Miter => raqote::LineJoin::Miter,
+						LineJoin::Round => raqote::LineJoin::Round,
+						LineJoin::Bevel => raqote::LineJoin::Bevel,
 					},
-					&DrawOptions::new(),
-				);
-			}
-			None => {}
+					width: stroke.width,
+					miter_limit: stroke.miter_limit,
+					dash_array: if stroke.is_dashed() {
+						stroke.dash
+					} else {
+						vec![]
+					},
+					dash_offset: stroke.dash_offset,
+				},
+				&DrawOptions::new(),
+			);
 		}
 
 		Ok(())
 	}
 
-	fn export_text(
-		&mut self,
-		TextPosition {
-			text,
-			align: _,
-			font_weight,
-			on_curve: _,
-			font_size,
-			reference_start,
-			direction: _,
-			font,
-		}: TextPosition,
-		_: StylePosition,
-	) -> Result<(), Self::Error> {
-		let fg = dessin::font::get_or_default(font.as_ref());
-		let font = fg.get(font_weight).as_bytes();
-
-		//dt.set_transform(&Transform::create_translation(50.0, 0.0));
-		// dt.set_transform(&Transform::rotation(euclid::Angle::degrees(15.0)));
-
-		let color = match self.style().fill {
-			Some(Fill::Solid { color }) => color,
+	fn export_text(&mut self, text: TextPosition, _: StylePosition) -> Result<(), Self::Error> {
+		let source = match &self.style().fill {
+			Some(fill) => fill_source(fill),
 			None => return Ok(()),
 		};
-		let (r, g, b, a) = (
-			color.into_format::<u8, f32>().red, //-----------------------------------------------------------------------------
-			color.into_format::<u8, f32>().green, //before : color.rgba();
-			color.into_format::<u8, f32>().blue, //rgba() modification should be better
-			color.into_format::<u8, u8>().alpha, //-----------------------------------------------------------------------------
-		);
 
-		let font = font_kit::loader::Loader::from_bytes(std::sync::Arc::new(font.to_vec()), 0)
-			.map_err(|e| ImageError::FontLoadingError(e))?;
-		self.buffer.draw_text(
-			&font,
-			font_size,
-			text,
-			Point::new(reference_start.x, reference_start.y),
-			&Source::Solid(SolidSource { r: b, g, b: r, a }),
-			&DrawOptions::new(),
-		);
+		// Glyphs are placed as shaped by dessin, to match the bounding box of the text
+		for run in text.runs() {
+			let properties = dessin::font::properties(Some(&run.font), text.font_properties())
+				.map_err(ImageError::FontError)?;
+			let font = match self.fonts.entry((run.font, properties)) {
+				Entry::Occupied(font) => font.into_mut(),
+				Entry::Vacant(entry) => {
+					let face = dessin::font::try_face(Some(&entry.key().0), properties)
+						.map_err(ImageError::FontError)?;
+					let font = font_kit::loader::Loader::from_bytes(
+						std::sync::Arc::new(face.as_bytes().to_vec()),
+						face.index(),
+					)
+					.map_err(ImageError::FontLoadingError)?;
+					entry.insert(font)
+				}
+			};
+
+			let (ids, positions): (Vec<_>, Vec<_>) = run
+				.glyphs
+				.into_iter()
+				.map(|glyph| {
+					(
+						glyph.id as u32,
+						Point::new(glyph.position.x, glyph.position.y),
+					)
+				})
+				.unzip();
+			self.buffer.draw_glyphs(
+				font,
+				text.font_size,
+				&ids,
+				&positions,
+				&source,
+				&DrawOptions::new(),
+			);
+		}
+
+		for line in text.decorations() {
+			self.buffer
+				.fill(&curve_path(&line)?, &source, &DrawOptions::new());
+		}
 
 		Ok(())
 	}
@@ -317,8 +511,8 @@ impl ToImage for Shape {
 				&mut exporter,
 				&transform,
 				StylePosition {
-					fill: *fill,
-					stroke: *stroke,
+					fill: fill.clone(),
+					stroke: stroke.clone(),
 				},
 			)? //Needed to be complete
 		} else {
@@ -0,0 +1,266 @@
+use dessin::{palette::Srgba, prelude::*};
+use nalgebra::{Point2, Vector2};
+use printpdf::{DictItem, ExternalStream, ExternalXObject, Mm};
+use std::collections::BTreeMap;
+
+fn name(n: &str) -> DictItem {
+	DictItem::Name(n.as_bytes().to_vec())
+}
+
+fn reals<I: IntoIterator<Item = f32>>(values: I) -> DictItem {
+	DictItem::Array(values.into_iter().map(DictItem::Real).collect())
+}
+
+fn dict<const N: usize>(entries: [(&str, DictItem); N]) -> DictItem {
+	DictItem::Dict {
+		map: entries
+			.into_iter()
+			.map(|(k, v)| (k.to_string(), v))
+			.collect(),
+	}
+}
+
+fn rgb(color: Srgba) -> [f32; 3] {
+	[color.red, color.green, color.blue]
+}
+
+/// Function mapping `[0, 1]` to the colors of the stops
+fn stops_function(stops: &[GradientStop]) -> DictItem {
+	let interpolate = |c0: Srgba, c1: Srgba| {
+		dict([
+			("FunctionType", DictItem::Int(2)),
+			("Domain", reals([0., 1.])),
+			("C0", reals(rgb(c0))),
+			("C1", reals(rgb(c1))),
+			("N", DictItem::Real(1.)),
+		])
+	};
+
+	let mut stops = stops
+		.iter()
+		.map(|s| GradientStop {
+			offset: s.offset.clamp(0., 1.),
+			color: s.color,
+		})
+		.collect::<Vec<_>>();
+	match (stops.first().copied(), stops.last().copied()) {
+		(Some(first), Some(last)) => {
+			if first.offset > 0. {
+				stops.insert(
+					0,
+					GradientStop {
+						offset: 0.,
+						..first
+					},
+				);
+			}
+			if last.offset < 1. {
+				stops.push(GradientStop { offset: 1., ..last });
+			}
+		}
+		_ => return interpolate(Srgba::default(), Srgba::default()),
+	}
+
+	if stops.len() == 1 {
+		return interpolate(stops[0].color, stops[0].color);
+	}
+
+	let segments = stops.windows(2).collect::<Vec<_>>();
+
+	dict([
+		("FunctionType", DictItem::Int(3)),
+		("Domain", reals([0., 1.])),
+		(
+			"Functions",
+			DictItem::Array(
+				segments
+					.iter()
+					.map(|s| interpolate(s[0].color, s[1].color))
+					.collect(),
+			),
+		),
+		(
+			"Bounds",
+			reals(segments.iter().skip(1).map(|s| s[0].offset)),
+		),
+		("Encode", reals(segments.iter().flat_map(|_| [0., 1.]))),
+	])
+}
+
+/// Function mapping `[from, to]` to the colors of the stops, following the spread
+fn spread_function(stops: &[GradientStop], spread: GradientSpread, from: i32, to: i32) -> DictItem {
+	let function = stops_function(stops);
+	if spread == GradientSpread::Pad || to - from <= 1 {
+		return function;
+	}
+
+	dict([
+		("FunctionType", DictItem::Int(3)),
+		("Domain", reals([from as f32, to as f32])),
+		(
+			"Functions",
+			DictItem::Array((from..to).map(|_| function.clone()).collect()),
+		),
+		("Bounds", reals((from + 1..to).map(|v| v as f32))),
+		(
+			"Encode",
+			reals((from..to).flat_map(|period| {
+				if spread == GradientSpread::Reflect && period.rem_euclid(2) == 1 {
+					[1., 0.]
+				} else {
+					[0., 1.]
+				}
+			})),
+		),
+	])
+}
+
+/// Form XObject painting a gradient over the whole clipping area.
+///
+/// `points` are the points, in user space, that must be covered by the gradient.
+pub(crate) fn gradient_xobject(fill: &Fill, points: &[Point2<f32>]) -> Option<ExternalXObject> {
+	let transform = match fill {
+		Fill::Solid { .. } => return None,
+		Fill::LinearGradient { transform, .. } | Fill::RadialGradient { transform, .. } => {
+			transform
+		}
+	};
+	let inverse = transform.try_inverse()?;
+	let points = points.iter().map(|p| inverse * p).collect::<Vec<_>>();
+
+	let shading = match fill {
+		_ if fill.degenerate_color().is_some() => None,
+		Fill::Solid { .. } => unreachable!(),
+		Fill::LinearGradient {
+			start,
+			end,
+			stops,
+			spread,
+			..
+		} => {
+			let direction = end - start;
+			let t = |p: &Point2<f32>| (p - start).dot(&direction) / direction.norm_squared();
+			let (from, to) = match spread {
+				GradientSpread::Pad => (0, 1),
+				_ => (
+					points.iter().map(t).fold(0_f32, f32::min).floor() as i32,
+					points.iter().map(t).fold(1_f32, f32::max).ceil() as i32,
+				),
+			};
+			let (a, b) = (
+				start + direction * from as f32,
+				start + direction * to as f32,
+			);
+
+			Some(dict([
+				("ShadingType", DictItem::Int(2)),
+				("ColorSpace", name("DeviceRGB")),
+				("Coords", reals([a.x, a.y, b.x, b.y])),
+				("Domain", reals([from as f32, to as f32])),
+				("Function", spread_function(stops, *spread, from, to)),
+				("Extend", DictItem::Array(vec![DictItem::Bool(true); 2])),
+			]))
+		}
+		Fill::RadialGradient {
+			center,
+			radius,
+			focal,
+			stops,
+			spread,
+			..
+		} => {
+			let to = match spread {
+				GradientSpread::Pad => 1,
+				_ => points
+					.iter()
+					.map(|p| (p - focal).norm() / radius)
+					.fold(1_f32, f32::max)
+					.ceil() as i32,
+			};
+			let outer: Point2<f32> = focal + (center - focal) * to as f32;
+
+			Some(dict([
+				("ShadingType", DictItem::Int(3)),
+				("ColorSpace", name("DeviceRGB")),
+				(
+					"Coords",
+					reals([focal.x, focal.y, 0., outer.x, outer.y, radius * to as f32]),
+				),
+				("Domain", reals([0., to as f32])),
+				("Function", spread_function(stops, *spread, 0, to)),
+				("Extend", DictItem::Array(vec![DictItem::Bool(true); 2])),
+			]))
+		}
+	};
+
+	// Gradient space is in mm, PDF user space is in pt
+	let mm_to_pt = Mm(1.).into_pt().0;
+	let m = transform.matrix() * mm_to_pt;
+	let bounds = points
+		.iter()
+		.fold(
+			None,
+			|acc: Option<(Point2<f32>, Point2<f32>)>, p| match acc {
+				None => Some((*p, *p)),
+				Some((min, max)) => Some((min.inf(p), max.sup(p))),
+			},
+		)
+		.unwrap_or((Point2::origin(), Point2::origin()));
+	let margin = Vector2::new(1., 1.);
+
+	let mut map = BTreeMap::new();
+	map.insert("Type".to_string(), name("XObject"));
+	map.insert("Subtype".to_string(), name("Form"));
+	map.insert(
+		"BBox".to_string(),
+		reals([
+			bounds.0.x - margin.x,
+			bounds.0.y - margin.y,
+			bounds.1.x + margin.x,
+			bounds.1.y + margin.y,
+		]),
+	);
+	map.insert(
+		"Matrix".to_string(),
+		reals([
+			m[(0, 0)],
+			m[(1, 0)],
+			m[(0, 1)],
+			m[(1, 1)],
+			m[(0, 2)],
+			m[(1, 2)],
+		]),
+	);
+	let content = match (shading, fill.degenerate_color()) {
+		(Some(shading), _) => {
+			map.insert(
+				"Resources".to_string(),
+				dict([("Shading", dict([("Sh0", shading)]))]),
+			);
+			"/Sh0 sh".to_string()
+		}
+		// Without extent, the last stop paints the whole area
+		(None, color) => {
+			let [r, g, b] = rgb(color.unwrap_or_default());
+			let (min, max) = (bounds.0 - margin, bounds.1 + margin);
+			format!(
+				"{r} {g} {b} rg {} {} {} {} re f",
+				min.x,
+				min.y,
+				max.x - min.x,
+				max.y - min.y
+			)
+		}
+	};
+
+	Some(ExternalXObject {
+		stream: ExternalStream {
+			dict: map,
+			content: content.into_bytes(),
+			compress: false,
+		},
+		width: None,
+		height: None,
+		dpi: None,
+	})
+}
@@ -1,16 +1,18 @@
+mod gradient;
+
 use dessin::{
 	export::{Export, Exporter},
 	font::FontRef,
 	prelude::*,
 };
-use nalgebra::Translation2;
+use nalgebra::{Point2, Translation2};
 use printpdf::{
-	Color, FontId, Layer, LayerIntent, LayerInternalId, LayerSubtype, Line, LinePoint, Mm, Op,
-	PaintMode, ParsedFont, PdfDocument, PdfPage, PdfSaveOptions, Point, Polygon, PolygonRing, Px,
-	RawImage, Rgb, TextItem, TextMatrix, TextRenderingMode, WindingOrder, XObjectRotation,
-	XObjectTransform,
+	Color, CurTransMat, FontId, Layer, LayerIntent, LayerInternalId, LayerSubtype, Line,
+	LineCapStyle, LineDashPattern, LineJoinStyle, LinePoint, Mm, Op, PaintMode, ParsedFont,
+	PdfDocument, PdfPage, PdfSaveOptions, Point, Polygon, PolygonRing, Pt, Px, RawImage, Rgb,
+	TextMatrix, TextRenderingMode, WindingOrder, XObjectRotation, XObjectTransform,
 };
-use std::{collections::HashMap, convert::identity, fmt};
+use std::{collections::HashMap, fmt};
 
 #[derive(Debug, thiserror::Error)]
 pub enum PDFError {
@@ -25,17 +27,26 @@ pub enum PDFError {
 	#[error("Orphelin layer")]
 	OrphelinLayer,
 	#[error("Can't parse font `{0} {1:?}`")]
-	CantParseFont(FontRef, FontWeight),
+	CantParseFont(FontRef, FontProperties),
 	#[error("Internal error: No layer started")]
 	NoLayerStarted,
+	#[error("{0}")]
+	FontError(#[from] font::FontError),
+	#[error("PDF masks can only be a single closed curve filled with an opaque color, white for luminance masks")]
+	UnsupportedMask,
+	#[error("PDF dash patterns have at most 3 dashes and 3 gaps: {0:?}")]
+	UnsupportedDashPattern(Vec<f32>),
 }
 
-type PDFFontHolder = HashMap<(FontRef, FontWeight), FontId>;
+/// Fonts added to the document, by the properties of their face
+type PDFFontHolder = HashMap<(FontRef, FontProperties), FontId>;
 
 #[derive(Default)]
 pub struct PDFOptions {
 	pub size: Option<(f32, f32)>,
 	pub used_font: PDFFontHolder,
+	/// Draw texts as the outlines of their glyphs, without embedding fonts
+	pub outline_text: bool,
 }
 
 pub struct PDFExporter<'a> {
@@ -57,6 +68,237 @@ impl<'a> PDFExporter<'a> {
 	pub fn new(doc: &'a mut PdfDocument) -> Self {
 		PDFExporter::new_with_font(doc, HashMap::default())
 	}
+
+	/// Start drawing with a dashed stroke, returning the scale of the space to draw in
+	///
+	/// Dashed strokes are drawn in a space [`DASH_SCALE`] times finer, that must be left
+	/// with [`Op::RestoreGraphicsState`]. Solid strokes are drawn as is, with a scale of 1.
+	fn start_dashed(&mut self, stroke: &Option<Stroke>) -> Result<f32, PDFError> {
+		let Some(stroke) = stroke.as_ref().filter(|s| s.is_dashed()) else {
+			return Ok(1.);
+		};
+
+		self.content.extend([
+			Op::SaveGraphicsState,
+			Op::SetTransformationMatrix {
+				matrix: CurTransMat::Scale(1. / DASH_SCALE, 1. / DASH_SCALE),
+			},
+			Op::SetOutlineThickness {
+				pt: Pt(Mm(stroke.width).into_pt().0 * DASH_SCALE),
+			},
+			Op::SetLineDashPattern {
+				dash: dash_pattern(stroke, DASH_SCALE)?,
+			},
+		]);
+		Ok(DASH_SCALE)
+	}
+
+	/// Leave the space started by [`PDFExporter::start_dashed`]
+	fn end_dashed(&mut self, scale: f32) {
+		if scale != 1. {
+			self.content.push(Op::RestoreGraphicsState);
+		}
+	}
+
+	/// Font added to the document, adding it on first use
+	fn font_id(&mut self, font: FontRef, properties: FontProperties) -> Result<FontId, PDFError> {
+		let properties = font::properties(Some(&font), properties)?;
+		let key = (font, properties);
+		if let Some(font_id) = self.used_font.get(&key) {
+			return Ok(font_id.clone());
+		}
+
+		let face = font::try_face(Some(&key.0), properties)?;
+		let font_id = self.doc.add_font(
+			&ParsedFont::from_bytes(face.as_bytes(), face.index() as usize, &mut vec![])
+				.ok_or_else(|| PDFError::CantParseFont(key.0.clone(), properties))?,
+		);
+		self.used_font.insert(key, font_id.clone());
+		Ok(font_id)
+	}
+}
+
+/// Collects the curves of a mask, that can only be expressed as a clip
+///
+/// PDF soft masks can't be written here, so only masks made of a single closed curve
+/// filled with an opaque color, white for luminance masks, are exported.
+struct MaskCollector {
+	kind: MaskKind,
+	curves: Vec<CurvePosition>,
+}
+impl MaskCollector {
+	/// Whether `fill` fully shows what is under it
+	fn is_visible(&self, fill: &Fill) -> bool {
+		const EPSILON: f32 = 1e-3;
+		let Fill::Solid { color } = fill else {
+			return false;
+		};
+		let color = color.into_format::<f32, f32>();
+		let opaque = color.alpha >= 1. - EPSILON;
+
+		match self.kind {
+			MaskKind::Alpha => opaque,
+			MaskKind::Luminance => {
+				opaque
+					&& [color.red, color.green, color.blue]
+						.iter()
+						.all(|c| *c >= 1. - EPSILON)
+			}
+		}
+	}
+}
+impl Exporter for MaskCollector {
+	type Error = PDFError;
+
+	const CAN_EXPORT_ELLIPSE: bool = false;
+
+	fn start_style(&mut self, _: StylePosition) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn end_style(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn start_clip(&mut self, _: ClipPosition) -> Result<(), Self::Error> {
+		Err(PDFError::UnsupportedMask)
+	}
+
+	fn export_image(&mut self, _: ImagePosition) -> Result<(), Self::Error> {
+		Err(PDFError::UnsupportedMask)
+	}
+
+	fn export_curve(
+		&mut self,
+		curve: CurvePosition,
+		StylePosition { fill, stroke }: StylePosition,
+	) -> Result<(), Self::Error> {
+		match (fill, stroke) {
+			// Invisible curves don't show anything
+			(None, None) => Ok(()),
+			(Some(fill), None) if self.is_visible(&fill) => {
+				self.curves.push(curve);
+				Ok(())
+			}
+			_ => Err(PDFError::UnsupportedMask),
+		}
+	}
+
+	fn export_text(&mut self, _: TextPosition, _: StylePosition) -> Result<(), Self::Error> {
+		Err(PDFError::UnsupportedMask)
+	}
+}
+
+/// PDF dash lengths are integers, so dashed strokes are drawn in a space this many times finer
+const DASH_SCALE: f32 = 1000.;
+
+/// Dash pattern of `stroke`, in a space `scale` times finer than points
+///
+/// PDF dash patterns have at most 3 dashes and 3 gaps.
+fn dash_pattern(stroke: &Stroke, scale: f32) -> Result<LineDashPattern, PDFError> {
+	if !stroke.is_dashed() {
+		return Ok(LineDashPattern::default());
+	}
+	if stroke.dash.len() > 6 {
+		return Err(PDFError::UnsupportedDashPattern(stroke.dash.clone()));
+	}
+
+	let length = |v: f32| (Mm(v).into_pt().0 * scale).round() as i64;
+	let mut dash = stroke.dash.iter().map(|v| Some(length(*v)));
+	Ok(LineDashPattern {
+		offset: length(stroke.dash_offset),
+		dash_1: dash.next().flatten(),
+		gap_1: dash.next().flatten(),
+		dash_2: dash.next().flatten(),
+		gap_2: dash.next().flatten(),
+		dash_3: dash.next().flatten(),
+		gap_3: dash.next().flatten(),
+	})
+}
+
+/// Rings drawn in a space `scale` times finer
+fn scale_rings(rings: Vec<PolygonRing>, scale: f32) -> Vec<PolygonRing> {
+	rings
+		.into_iter()
+		.map(|PolygonRing { points }| PolygonRing {
+			points: points
+				.into_iter()
+				.map(|LinePoint { p, bezier }| LinePoint {
+					p: Point {
+						x: Pt(p.x.0 * scale),
+						y: Pt(p.y.0 * scale),
+					},
+					bezier,
+				})
+				.collect(),
+		})
+		.collect()
+}
+
+fn winding_order(fill_rule: FillRule) -> WindingOrder {
+	match fill_rule {
+		FillRule::NonZero => WindingOrder::NonZero,
+		FillRule::EvenOdd => WindingOrder::EvenOdd,
+	}
+}
+
+/// One ring per subpath
+fn polygon_rings(curve: &CurvePosition) -> Vec<PolygonRing> {
+	curve
+		.iter_subpaths()
+		.map(|subpath| PolygonRing {
+			points: line_points(subpath),
+		})
+		.collect()
+}
+
+fn line_points(curve: &CurvePosition) -> Vec<LinePoint> {
+	// Quadratic beziers and arcs are drawn as cubic beziers
+	curve
+		.to_cubics()
+		.keypoints
+		.iter()
+		.flat_map(|v| match v {
+			KeypointPosition::Point(p) => [
+				Some(LinePoint {
+					p: Point::new(Mm(p.x), Mm(p.y)),
+					bezier: false,
+				}),
+				None,
+				None,
+				None,
+			]
+			.into_iter(),
+			KeypointPosition::Bezier(Bezier {
+				start,
+				start_control,
+				end_control,
+				end,
+			}) => [
+				start.map(|v| LinePoint {
+					p: Point::new(Mm(v.x), Mm(v.y)),
+					bezier: false,
+				}),
+				Some(LinePoint {
+					p: Point::new(Mm(start_control.x), Mm(start_control.y)),
+					bezier: true,
+				}),
+				Some(LinePoint {
+					p: Point::new(Mm(end_control.x), Mm(end_control.y)),
+					bezier: true,
+				}),
+				Some(LinePoint {
+					p: Point::new(Mm(end.x), Mm(end.y)),
+					bezier: true,
+				}),
+			]
+			.into_iter(),
+			KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+				unreachable!("converted to cubics")
+			}
+		})
+		.flatten()
+		.collect()
 }
 
 impl Exporter for PDFExporter<'_> {
@@ -75,16 +317,17 @@ impl Exporter for PDFExporter<'_> {
 			usage: LayerSubtype::Artwork,
 		});
 
-		self.content.push(Op::BeginLayer { layer_id });
+		self.content.push(Op::BeginLayer {
+			layer_id: layer_id.clone(),
+		});
+		self.layers.push(layer_id);
 
-		if let Some(fill) = fill {
-			let (r, g, b) = match fill {
-				Fill::Solid { color } => (
-					color.into_format::<f32, f32>().red,
-					color.into_format::<f32, f32>().green,
-					color.into_format::<f32, f32>().blue,
-				),
-			};
+		if let Some(Fill::Solid { color }) = fill {
+			let (r, g, b) = (
+				color.into_format::<f32, f32>().red,
+				color.into_format::<f32, f32>().green,
+				color.into_format::<f32, f32>().blue,
+			);
 
 			self.content.push(Op::SetFillColor {
 				col: Color::Rgb(Rgb {
@@ -97,55 +340,37 @@ impl Exporter for PDFExporter<'_> {
 		}
 
 		if let Some(stroke) = stroke {
-			let ((r, g, b), w) = match stroke {
-				Stroke::Solid { color, width } => (
-					(
-						color.into_format::<f32, f32>().red,
-						color.into_format::<f32, f32>().green,
-						color.into_format::<f32, f32>().blue,
-					),
-					width,
-				),
-				Stroke::Dashed {
-					color,
-					width,
-					on: _,
-					off: _,
-				} => {
-					eprintln!("TODO: LineDashPattern");
-
-					// self.content.push(printpdf::LineDashPattern {
-					// 	offset: 0,
-					// 	dash_1: Some(on as i64),
-					// 	gap_1: Some(off as i64),
-					// 	dash_2: None,
-					// 	gap_2: None,
-					// 	dash_3: None,
-					// 	gap_3: None,
-					// });
-
-					(
-						(
-							color.into_format::<f32, f32>().red,
-							color.into_format::<f32, f32>().green,
-							color.into_format::<f32, f32>().blue,
-						),
-						width,
-					)
-				}
-			};
+			let color = stroke.color.into_format::<f32, f32>();
 
 			self.content.extend([
 				Op::SetOutlineColor {
 					col: Color::Rgb(Rgb {
-						r,
-						g,
-						b,
+						r: color.red,
+						g: color.green,
+						b: color.blue,
 						icc_profile: None,
 					}),
 				},
 				Op::SetOutlineThickness {
-					pt: printpdf::Mm(w).into_pt(),
+					pt: Mm(stroke.width).into_pt(),
+				},
+				Op::SetLineCapStyle {
+					cap: match stroke.cap {
+						LineCap::Butt => LineCapStyle::Butt,
+						LineCap::Round => LineCapStyle::Round,
+						LineCap::Square => LineCapStyle::ProjectingSquare,
+					},
+				},
+				Op::SetLineJoinStyle {
+					join: match stroke.join {
+						LineJoin::Miter => LineJoinStyle::Miter,
+						LineJoin::Round => LineJoinStyle::Round,
+						LineJoin::Bevel => LineJoinStyle::Bevel,
+					},
+				},
+				// The miter limit is a ratio, not a length
+				Op::SetMiterLimit {
+					limit: Pt(stroke.miter_limit),
 				},
 			]);
 		}
@@ -160,6 +385,63 @@ impl Exporter for PDFExporter<'_> {
 		Ok(())
 	}
 
+	fn start_clip(&mut self, clip: ClipPosition) -> Result<(), Self::Error> {
+		let (rings, winding_order) = match clip {
+			ClipPosition::Curve(curve) => (polygon_rings(&curve), winding_order(curve.fill_rule)),
+			ClipPosition::Mask {
+				mask,
+				kind,
+				parent_transform,
+			} => {
+				let mut collector = MaskCollector {
+					kind,
+					curves: vec![],
+				};
+				mask.write_into_exporter(
+					&mut collector,
+					&parent_transform,
+					StylePosition {
+						fill: None,
+						stroke: None,
+					},
+				)?;
+
+				match collector.curves.as_slice() {
+					// Nothing is visible through an empty mask
+					[] => (
+						vec![PolygonRing {
+							points: vec![LinePoint {
+								p: Point::new(Mm(0.), Mm(0.)),
+								bezier: false,
+							}],
+						}],
+						WindingOrder::NonZero,
+					),
+					[curve] => (polygon_rings(curve), winding_order(curve.fill_rule)),
+					_ => return Err(PDFError::UnsupportedMask),
+				}
+			}
+		};
+
+		self.content.extend([
+			Op::SaveGraphicsState,
+			Op::DrawPolygon {
+				polygon: Polygon {
+					mode: PaintMode::Clip,
+					rings,
+					winding_order,
+				},
+			},
+		]);
+
+		Ok(())
+	}
+
+	fn end_clip(&mut self) -> Result<(), Self::Error> {
+		self.content.push(Op::RestoreGraphicsState);
+		Ok(())
+	}
+
 	fn export_image(
 		&mut self,
 		ImagePosition {
@@ -213,50 +495,78 @@ impl Exporter for PDFExporter<'_> {
 		curve: CurvePosition,
 		StylePosition { fill, stroke }: StylePosition,
 	) -> Result<(), Self::Error> {
-		let points = curve
-			.keypoints
-			.into_iter()
+		let user_points = curve
+			.to_cubics()
+			.iter_subpaths()
+			.flat_map(|subpath| subpath.keypoints.iter())
 			.flat_map(|v| match v {
-				KeypointPosition::Point(p) => [
-					Some(LinePoint {
-						p: Point::new(Mm(p.x), Mm(p.y)),
-						bezier: false,
-					}),
-					None,
-					None,
-					None,
-				]
-				.into_iter(),
-				KeypointPosition::Bezier(Bezier {
-					start,
-					start_control,
-					end_control,
-					end,
-				}) => [
-					start.map(|v| LinePoint {
-						p: Point::new(Mm(v.x), Mm(v.y)),
-						bezier: false,
-					}),
-					Some(LinePoint {
-						p: Point::new(Mm(start_control.x), Mm(start_control.y)),
-						bezier: true,
-					}),
-					Some(LinePoint {
-						p: Point::new(Mm(end_control.x), Mm(end_control.y)),
-						bezier: true,
-					}),
-					Some(LinePoint {
-						p: Point::new(Mm(end.x), Mm(end.y)),
-						bezier: true,
-					}),
-				]
-				.into_iter(),
+				KeypointPosition::Point(p) => vec![*p],
+				KeypointPosition::Bezier(b) => b
+					.start
+					.into_iter()
+					.chain([b.start_control, b.end_control, b.end])
+					.collect(),
+				KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+					unreachable!("converted to cubics")
+				}
 			})
-			.filter_map(identity)
-			.collect::<Vec<_>>();
+			.collect::<Vec<Point2<f32>>>();
 
-		self.content.push(if curve.closed {
-			Op::DrawPolygon {
+		let rings = polygon_rings(&curve);
+		let winding_order = winding_order(curve.fill_rule);
+
+		// Gradients are painted as a shading, clipped by the curve
+		let shading = fill
+			.as_ref()
+			.and_then(|fill| gradient::gradient_xobject(fill, &user_points));
+		let fill = if let Some(shading) = shading {
+			let id = self.doc.add_xobject(&shading);
+
+			self.content.extend([
+				Op::SaveGraphicsState,
+				Op::DrawPolygon {
+					polygon: Polygon {
+						mode: PaintMode::Clip,
+						rings: rings.clone(),
+						winding_order,
+					},
+				},
+				Op::UseXobject {
+					id,
+					transform: XObjectTransform::default(),
+				},
+				Op::RestoreGraphicsState,
+			]);
+
+			if stroke.is_none() {
+				return Ok(());
+			}
+
+			None
+		} else {
+			fill
+		};
+
+		// Dashed strokes are drawn apart from the fill, in a finer space
+		let dashed = stroke.as_ref().is_some_and(Stroke::is_dashed);
+		let fill = match fill {
+			Some(_) if dashed && curve.closed => {
+				self.content.push(Op::DrawPolygon {
+					polygon: Polygon {
+						mode: PaintMode::Fill,
+						rings: rings.clone(),
+						winding_order,
+					},
+				});
+				None
+			}
+			fill => fill,
+		};
+		let scale = self.start_dashed(&stroke)?;
+		let rings = scale_rings(rings, scale);
+
+		if curve.closed {
+			self.content.push(Op::DrawPolygon {
 				polygon: Polygon {
 					mode: match (fill, stroke) {
 						(Some(_), Some(_)) => PaintMode::FillStroke,
@@ -264,84 +574,131 @@ impl Exporter for PDFExporter<'_> {
 						(None, Some(_)) => PaintMode::Stroke,
 						(None, None) => PaintMode::Clip,
 					},
-					rings: vec![PolygonRing { points }],
-					winding_order: WindingOrder::NonZero,
+					rings,
+					winding_order,
 				},
-			}
+			});
 		} else {
-			Op::DrawLine {
-				line: Line {
-					points,
-					is_closed: false,
-				},
-			}
-		});
+			self.content.extend(
+				rings
+					.into_iter()
+					.map(|PolygonRing { points }| Op::DrawLine {
+						line: Line {
+							points,
+							is_closed: false,
+						},
+					}),
+			);
+		}
+		self.end_dashed(scale);
 
 		Ok(())
 	}
 
 	fn export_text(
 		&mut self,
-		TextPosition {
-			text,
-			align: _,
-			font_weight,
-			on_curve: _,
+		text: TextPosition,
+		StylePosition { fill, stroke }: StylePosition,
+	) -> Result<(), Self::Error> {
+		let properties = text.font_properties();
+		let TextPosition {
 			font_size,
-			reference_start,
+			letter_spacing,
+			word_spacing,
 			direction,
 			font,
-		}: TextPosition,
-		StylePosition { fill, stroke }: StylePosition,
-	) -> Result<(), Self::Error> {
-		let font = font.clone().unwrap_or(FontRef::default());
-
-		let key = (font.clone(), font_weight);
-		if !self.used_font.contains_key(&key) {
-			match font::get(&font).get(font_weight) {
-				dessin::font::Font::OTF(b) | dessin::font::Font::TTF(b) => {
-					let font_id = self.doc.add_font(
-						&ParsedFont::from_bytes(&b, 0, &mut vec![])
-							.ok_or_else(|| PDFError::CantParseFont(font.clone(), font_weight))?,
-					);
-
-					self.used_font.insert(key.clone(), font_id);
-				}
-			}
-		}
-
-		let font = self.used_font[&key].clone();
+			..
+		} = text;
+		let font = self.font_id(font.clone().unwrap_or(FontRef::default()), properties)?;
 
 		let rotation = direction.y.atan2(direction.x).to_degrees();
+		let scale = self.start_dashed(&stroke)?;
+		let pt = |v: f32| Pt(Mm(v).into_pt().0 * scale);
+		let mode = match (&fill, &stroke) {
+			(Some(_), Some(_)) => TextRenderingMode::FillStroke,
+			(Some(_), None) => TextRenderingMode::Fill,
+			(None, Some(_)) => TextRenderingMode::Stroke,
+			(None, None) => TextRenderingMode::Clip,
+		};
 
 		self.content.extend([
-			Op::SetLineHeight {
-				lh: Mm(font_size).into_pt(),
-			},
-			Op::SetWordSpacing {
-				pt: Mm(font_size).into_pt(),
-			},
-			Op::SetTextRenderingMode {
-				mode: match (fill, stroke) {
-					(Some(_), Some(_)) => TextRenderingMode::FillStroke,
-					(Some(_), None) => TextRenderingMode::Fill,
-					(None, Some(_)) => TextRenderingMode::Stroke,
-					(None, None) => TextRenderingMode::Clip,
-				},
+			Op::StartTextSection,
+			Op::SetFontSize {
+				size: pt(font_size),
+				font: font.clone(),
 			},
-			Op::SetTextMatrix {
-				matrix: TextMatrix::TranslateRotate(
-					Mm(reference_start.x).into_pt(),
-					Mm(reference_start.y).into_pt(),
-					rotation,
-				),
+			Op::SetLineHeight { lh: pt(font_size) },
+			// Glyphs are placed one by one, spacings only keep the text state faithful
+			Op::SetCharacterSpacing {
+				multiplier: pt(letter_spacing).0,
 			},
-			Op::WriteText {
-				items: vec![TextItem::Text(text.to_string())],
-				font,
+			Op::SetWordSpacing {
+				pt: pt(word_spacing),
 			},
+			Op::SetTextRenderingMode { mode },
 		]);
 
+		// Each glyph is placed where dessin shaped it, kerning and marks included,
+		// and drawn by the font, or the fallback, of its run
+		for run in text.runs() {
+			let face = dessin::font::face(Some(&run.font), properties);
+			let font = self.font_id(run.font, properties)?;
+			self.content.push(Op::SetFontSize {
+				size: pt(font_size),
+				font: font.clone(),
+			});
+
+			for glyph in run.glyphs {
+				self.content.extend([
+					Op::SetTextMatrix {
+						matrix: TextMatrix::TranslateRotate(
+							pt(glyph.position.x),
+							pt(glyph.position.y),
+							rotation,
+						),
+					},
+					Op::WriteCodepoints {
+						font: font.clone(),
+						cp: vec![(glyph.id, glyph.character)],
+					},
+				]);
+
+				// A glyph is extracted as a single character, the others of a ligature
+				// follow it as invisible glyphs
+				let hidden = glyph
+					.text
+					.chars()
+					.skip(1)
+					.filter_map(|c| Some((face.glyph_id(c)?, c)))
+					.collect::<Vec<_>>();
+				if !hidden.is_empty() {
+					self.content.extend([
+						Op::SetTextRenderingMode {
+							mode: TextRenderingMode::Invisible,
+						},
+						Op::WriteCodepoints {
+							font: font.clone(),
+							cp: hidden,
+						},
+						Op::SetTextRenderingMode { mode },
+					]);
+				}
+			}
+		}
+
+		self.content.push(Op::EndTextSection);
+		self.end_dashed(scale);
+
+		for line in text.decorations() {
+			self.export_curve(
+				line,
+				StylePosition {
+					fill: fill.clone(),
+					stroke: stroke.clone(),
+				},
+			)?;
+		}
+
 		Ok(())
 	}
 }
@@ -355,6 +712,16 @@ pub fn write_to_pdf_with_options(
 		let bb = shape.local_bounding_box();
 		(bb.width(), bb.height())
 	});
+	let outlined;
+	let shape = if options.outline_text {
+		let mut shape = shape.clone();
+		shape.outline_texts();
+		outlined = shape;
+		&outlined
+	} else {
+		shape
+	};
+
 	let mut exporter = PDFExporter::new_with_font(doc, options.used_font);
 	let translation = Translation2::new(width / 2., height / 2.);
 	let parent_transform = nalgebra::convert(translation);
@@ -364,8 +731,8 @@ pub fn write_to_pdf_with_options(
 			&mut exporter,
 			&parent_transform,
 			StylePosition {
-				fill: *fill,
-				stroke: *stroke,
+				fill: fill.clone(),
+				stroke: stroke.clone(),
 			},
 		)?
 	} else {
@@ -0,0 +1,1673 @@
+//! Import of SVG documents as a [`Shape`].
+//!
+//! Groups, transforms, basic shapes, paths, texts and embedded images are imported,
+//! with their fill and stroke given as attributes, in a `style` attribute or in a `<style>` sheet.
+//! Fills can be gradients, and elements can be clipped by a `<clipPath>` or masked by a `<mask>`.
+//! Everything else is listed in [`SVGImport::unsupported`].
+//!
+//! The `viewBox` of the document is fitted into its `width` and `height` following its `preserveAspectRatio`,
+//! so that coordinates are in the units of its size. Content overflowing the viewport is not clipped.
+//! Documents without a size keep the coordinates of their `viewBox`: the imported dessin is flipped upside up,
+//! so that exporting it with [`to_string`][crate::to_string] gives back the same coordinates.
+use dessin::{
+	font::{self, FontRef},
+	palette::Srgba,
+	prelude::*,
+};
+use nalgebra::{Matrix3, Point2, Scale2, Transform2, Translation2, Vector2};
+use roxmltree::{Document, Node, NodeId};
+use simplecss::{AttributeOperator, DeclarationTokenizer, PseudoClass, StyleSheet};
+use std::{collections::HashMap, fmt, mem, str::FromStr};
+
+/// Presentation properties understood by the import, all inherited by children
+const PROPERTIES: &[&str] = &[
+	"color",
+	"fill",
+	"fill-opacity",
+	"fill-rule",
+	"stroke",
+	"stroke-width",
+	"stroke-opacity",
+	"stroke-linecap",
+	"stroke-linejoin",
+	"stroke-miterlimit",
+	"stroke-dasharray",
+	"stroke-dashoffset",
+	"font-size",
+	"font-family",
+	"font-weight",
+	"font-style",
+	"font-stretch",
+	"text-anchor",
+	"clip-rule",
+	"stop-color",
+	"stop-opacity",
+];
+
+/// Attributes changing the rendering of an element, that can't be imported
+const UNSUPPORTED_ATTRIBUTES: &[&str] = &["filter", "marker-start", "marker-mid", "marker-end"];
+
+#[derive(Debug)]
+pub enum SVGImportError {
+	XmlError(roxmltree::Error),
+	/// The root element is not an `<svg>`
+	NotAnSVG,
+}
+impl fmt::Display for SVGImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{self:?}")
+	}
+}
+impl From<roxmltree::Error> for SVGImportError {
+	fn from(value: roxmltree::Error) -> Self {
+		SVGImportError::XmlError(value)
+	}
+}
+impl std::error::Error for SVGImportError {}
+
+/// Part of the document left out of the import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+	/// Name of the element
+	pub element: String,
+	/// Name of the attribute, when only this attribute was left out
+	pub attribute: Option<String>,
+	/// Line of the element in the document, starting at 1
+	pub line: u32,
+}
+
+/// Result of [`from_str`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SVGImport {
+	/// Imported dessin
+	pub shape: Shape,
+	/// Elements and attributes left out, in document order
+	pub unsupported: Vec<Unsupported>,
+}
+
+/// Import an SVG document
+///
+/// ```
+/// let import = dessin_svg::from_str(
+///     r#"<svg xmlns="http://www.w3.org/2000/svg">
+///         <style>.red { fill: red }</style>
+///         <rect class="red" x="10" y="10" width="20" height="10" rx="2"/>
+///         <circle cx="5" cy="5" r="5" stroke="blue"/>
+///         <foreignObject/>
+///     </svg>"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(import.unsupported.len(), 1);
+/// assert_eq!(import.unsupported[0].element, "foreignObject");
+/// ```
+pub fn from_str(svg: &str) -> Result<SVGImport, SVGImportError> {
+	let document = Document::parse_with_options(
+		svg,
+		roxmltree::ParsingOptions {
+			allow_dtd: true,
+			..Default::default()
+		},
+	)?;
+
+	let root = document.root_element();
+	if !root.has_tag_name("svg") {
+		return Err(SVGImportError::NotAnSVG);
+	}
+
+	let mut stylesheet = StyleSheet::new();
+	for style in document.descendants().filter(|v| v.has_tag_name("style")) {
+		if let Some(text) = style.text() {
+			stylesheet.parse_more(text);
+		}
+	}
+
+	let mut importer = Importer {
+		document: &document,
+		stylesheet,
+		unsupported: vec![],
+		used: vec![],
+		in_clip_path: false,
+	};
+
+	let properties = importer.properties(root, &Properties::default());
+	let mut shape = Shape::Group(Group {
+		local_transform: Transform2::default(),
+		shapes: importer.children(root, &properties),
+		metadata: vec![],
+	});
+	// Without a size, the view box is the viewport
+	if root.has_attribute("width") || root.has_attribute("height") {
+		shape.transform(importer.viewport(root, &properties));
+	}
+	shape.transform(nalgebra::convert(Scale2::new(1., -1.)));
+
+	Ok(SVGImport {
+		shape,
+		unsupported: importer.unsupported,
+	})
+}
+
+/// Presentation properties of an element, inherited from its parents
+#[derive(Clone)]
+struct Properties<'a> {
+	values: HashMap<&'a str, &'a str>,
+	/// Product of the opacities of the element and its parents
+	opacity: f32,
+}
+impl Default for Properties<'_> {
+	fn default() -> Self {
+		Properties {
+			values: HashMap::new(),
+			opacity: 1.,
+		}
+	}
+}
+impl Properties<'_> {
+	fn get(&self, name: &str) -> Option<&str> {
+		self.values.get(name).copied()
+	}
+
+	fn number(&self, name: &str) -> Option<f32> {
+		self.get(name)
+			.and_then(|v| svgtypes::Number::from_str(v).ok())
+			.map(|v| v.0 as f32)
+	}
+
+	fn font_size(&self) -> f32 {
+		self.get("font-size")
+			.and_then(|v| svgtypes::Length::from_str(v).ok())
+			.and_then(|v| length(v, 16.))
+			.unwrap_or(16.)
+	}
+
+	fn length(&self, name: &str) -> Option<f32> {
+		self.get(name)
+			.and_then(|v| svgtypes::Length::from_str(v).ok())
+			.and_then(|v| length(v, self.font_size()))
+	}
+}
+
+/// Length in user units, `None` for percentages
+fn length(length: svgtypes::Length, font_size: f32) -> Option<f32> {
+	use svgtypes::LengthUnit;
+
+	let n = length.number as f32;
+	Some(match length.unit {
+		LengthUnit::None | LengthUnit::Px => n,
+		LengthUnit::Em => n * font_size,
+		LengthUnit::Ex => n * font_size / 2.,
+		LengthUnit::In => n * 96.,
+		LengthUnit::Cm => n * 96. / 2.54,
+		LengthUnit::Mm => n * 96. / 25.4,
+		LengthUnit::Pt => n * 4. / 3.,
+		LengthUnit::Pc => n * 16.,
+		LengthUnit::Percent => return None,
+	})
+}
+
+/// Fraction of a gradient or clip length, `None` when it is not a number or a percentage
+fn fraction(value: &str) -> Option<f32> {
+	match svgtypes::Length::from_str(value).ok()? {
+		svgtypes::Length {
+			number,
+			unit: svgtypes::LengthUnit::Percent,
+		} => Some(number as f32 / 100.),
+		length => self::length(length, 16.),
+	}
+}
+
+/// Transform from the bounding box units of `shape` to its user space, `None` when it has no area
+fn bounding_box_units(shape: &Shape) -> Option<Transform2<f32>> {
+	let bb = shape.local_bounding_box().straigthen();
+	let (width, height) = (bb.width(), bb.height());
+	if width <= 0. || height <= 0. {
+		return None;
+	}
+
+	let min = Translation2::new(bb.left().min(bb.right()), bb.top().min(bb.bottom()));
+	Some(
+		nalgebra::convert::<_, Transform2<f32>>(min)
+			* nalgebra::convert::<_, Transform2<f32>>(Scale2::new(width, height)),
+	)
+}
+
+/// Curve covering the same area as `shape`, `None` when it has other shapes than curves and texts
+fn area(shape: Shape) -> Option<Curve> {
+	match shape {
+		Shape::Curve(curve) => Some(curve),
+		Shape::Ellipse(ellipse) => Some(ellipse.into()),
+		Shape::Text(text) => area(text.to_outlines()),
+		Shape::Style { shape, .. } => area(*shape),
+		Shape::Group(Group {
+			local_transform,
+			shapes,
+			..
+		}) => {
+			let curves = shapes.into_iter().map(area).collect::<Option<Vec<_>>>()?;
+			// A single curve is kept as is, several are merged
+			let mut curve = curves.into_iter().reduce(|a, b| a.union(&b))?;
+			curve.transform(local_transform);
+			Some(curve)
+		}
+		_ => None,
+	}
+}
+
+/// Element of the document, as seen by the style sheet
+struct Element<'a, 'input>(Node<'a, 'input>);
+impl simplecss::Element for Element<'_, '_> {
+	fn parent_element(&self) -> Option<Self> {
+		self.0.parent_element().map(Element)
+	}
+
+	fn prev_sibling_element(&self) -> Option<Self> {
+		self.0.prev_sibling_element().map(Element)
+	}
+
+	fn has_local_name(&self, name: &str) -> bool {
+		self.0.tag_name().name() == name
+	}
+
+	fn attribute_matches(&self, local_name: &str, operator: AttributeOperator<'_>) -> bool {
+		self.0
+			.attribute(local_name)
+			.is_some_and(|v| operator.matches(v))
+	}
+
+	fn pseudo_class_matches(&self, class: PseudoClass<'_>) -> bool {
+		match class {
+			PseudoClass::FirstChild => self.0.prev_sibling_element().is_none(),
+			_ => false,
+		}
+	}
+}
+
+struct Importer<'a, 'input> {
+	document: &'a Document<'input>,
+	stylesheet: StyleSheet<'a>,
+	unsupported: Vec<Unsupported>,
+	/// Elements referenced by the `<use>`, `<clipPath>` and `<mask>` being imported, to stop on cycles
+	used: Vec<NodeId>,
+	/// Whether the children of a `<clipPath>` are being imported
+	in_clip_path: bool,
+}
+impl<'a, 'input> Importer<'a, 'input> {
+	fn report(&mut self, node: Node, attribute: Option<&str>) {
+		let line = self.document.text_pos_at(node.range().start).row;
+		self.unsupported.push(Unsupported {
+			element: node.tag_name().name().to_string(),
+			attribute: attribute.map(str::to_string),
+			line,
+		});
+	}
+
+	fn by_id(&self, id: &str) -> Option<Node<'a, 'input>> {
+		self.document
+			.descendants()
+			.find(|v| v.attribute("id") == Some(id))
+	}
+
+	/// Element referenced by a `url(#id)`
+	fn by_url(&self, url: &str) -> Option<Node<'a, 'input>> {
+		self.by_id(svgtypes::FuncIRI::from_str(url).ok()?.0)
+	}
+
+	/// Value of a property that is not inherited: attribute, style sheet, then `style` attribute
+	fn declared(&self, node: Node<'a, 'input>, name: &str) -> Option<&'a str> {
+		let in_style = node.attribute("style").and_then(|style| {
+			DeclarationTokenizer::from(style)
+				.filter(|v| v.name == name)
+				.last()
+				.map(|v| v.value)
+		});
+		let in_stylesheet = self
+			.stylesheet
+			.rules
+			.iter()
+			.filter(|rule| rule.selector.matches(&Element(node)))
+			.flat_map(|rule| &rule.declarations)
+			.rev()
+			.find(|v| v.name == name)
+			.map(|v| v.value);
+
+		in_style
+			.or(in_stylesheet)
+			.or_else(|| node.attribute(name))
+			.map(str::trim)
+	}
+
+	/// Properties of `node`, inherited from all its ancestors
+	fn inherited(&self, node: Node<'a, 'input>) -> Properties<'a> {
+		let mut ancestors = node
+			.ancestors()
+			.filter(Node::is_element)
+			.collect::<Vec<_>>();
+		ancestors.reverse();
+		ancestors
+			.into_iter()
+			.fold(Properties::default(), |parent, node| {
+				self.properties(node, &parent)
+			})
+	}
+
+	fn properties(&self, node: Node<'a, 'input>, parent: &Properties<'a>) -> Properties<'a> {
+		// Later declarations override earlier ones: attributes, style sheet, then `style` attribute
+		let mut declared = HashMap::new();
+		for attribute in node.attributes() {
+			declared.insert(attribute.name(), attribute.value());
+		}
+		for rule in &self.stylesheet.rules {
+			if rule.selector.matches(&Element(node)) {
+				for declaration in &rule.declarations {
+					declared.insert(declaration.name, declaration.value);
+				}
+			}
+		}
+		if let Some(style) = node.attribute("style") {
+			for declaration in DeclarationTokenizer::from(style) {
+				declared.insert(declaration.name, declaration.value);
+			}
+		}
+
+		let mut properties = parent.clone();
+		for (name, value) in declared {
+			let value = value.trim();
+			if value == "inherit" {
+				continue;
+			}
+
+			if name == "opacity" {
+				if let Ok(opacity) = svgtypes::Number::from_str(value) {
+					properties.opacity *= opacity.0 as f32;
+				}
+			} else if PROPERTIES.contains(&name) {
+				properties.values.insert(name, value);
+			}
+		}
+		properties
+	}
+
+	fn children(&mut self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Vec<Shape> {
+		node.children()
+			.filter(|v| v.is_element())
+			.filter_map(|child| self.element(child, properties))
+			.collect()
+	}
+
+	fn element(&mut self, node: Node<'a, 'input>, parent: &Properties<'a>) -> Option<Shape> {
+		let properties = self.properties(node, parent);
+		if self.is_hidden(node) {
+			return None;
+		}
+
+		for attribute in UNSUPPORTED_ATTRIBUTES {
+			if node.has_attribute(*attribute) {
+				self.report(node, Some(attribute));
+			}
+		}
+
+		let mut shape = match node.tag_name().name() {
+			"g" | "a" | "switch" => self.group(node, &properties),
+			"svg" => {
+				let mut group = self.group(node, &properties);
+				group.transform(self.viewport(node, &properties));
+				group
+			}
+			"use" => self.use_element(node, &properties)?,
+			"rect" => self.styled(node, &properties, self.rect(node, &properties)?),
+			"circle" => {
+				let r = self.coordinate(node, &properties, "r");
+				let ellipse = self.ellipse(node, &properties, r, r)?;
+				self.styled(node, &properties, ellipse)
+			}
+			"ellipse" => {
+				let rx = self.coordinate(node, &properties, "rx");
+				let ry = self.coordinate(node, &properties, "ry");
+				let ellipse = self.ellipse(node, &properties, rx, ry)?;
+				self.styled(node, &properties, ellipse)
+			}
+			"line" => {
+				let curve = dessin!(Curve(
+					then = self.point(node, &properties, "x1", "y1"),
+					then = self.point(node, &properties, "x2", "y2"),
+				));
+				self.styled(node, &properties, curve.into())
+			}
+			"polyline" | "polygon" => {
+				let mut curve = Curve::default();
+				for (x, y) in svgtypes::PointsParser::from(node.attribute("points")?) {
+					curve.then(Point2::new(x as f32, y as f32));
+				}
+				curve.close(node.has_tag_name("polygon"));
+				self.styled(node, &properties, curve.into())
+			}
+			"path" => {
+				let curve = match Curve::from_svg_path_data(node.attribute("d")?) {
+					Ok(v) => v,
+					Err(_) => {
+						self.report(node, Some("d"));
+						return None;
+					}
+				};
+				self.styled(node, &properties, curve.into())
+			}
+			"text" => {
+				let text = self.text(node, &properties)?;
+				self.styled(node, &properties, text)
+			}
+			"image" => self.image(node, &properties)?,
+			"defs" | "style" | "title" | "desc" | "metadata" | "linearGradient"
+			| "radialGradient" | "clipPath" | "mask" => return None,
+			_ => {
+				self.report(node, None);
+				return None;
+			}
+		};
+
+		// Clips and masks are in the user space of the element, before its transform
+		if let Some(url) = self.declared(node, "clip-path").filter(|v| *v != "none") {
+			match self.clip_path(url, &shape) {
+				Some(Some(clip)) => {
+					shape = Shape::Clip {
+						clip,
+						shape: Box::new(shape),
+					}
+				}
+				// Nothing is visible through an empty clip
+				Some(None) => return None,
+				None => self.report(node, Some("clip-path")),
+			}
+		}
+		if let Some(url) = self.declared(node, "mask").filter(|v| *v != "none") {
+			match self.mask(url, &shape) {
+				Some(Some((mask, kind))) => {
+					shape = Shape::Mask {
+						mask: Box::new(mask),
+						kind,
+						shape: Box::new(shape),
+					}
+				}
+				// Nothing is visible through an empty mask
+				Some(None) => return None,
+				None => self.report(node, Some("mask")),
+			}
+		}
+
+		if node.has_attribute("transform") {
+			let transform = self.transform(node);
+			shape.transform(transform);
+		}
+
+		Some(shape)
+	}
+
+	fn is_hidden(&self, node: Node<'a, 'input>) -> bool {
+		self.declared(node, "display") == Some("none")
+			|| matches!(
+				self.declared(node, "visibility"),
+				Some("hidden" | "collapse")
+			)
+	}
+
+	/// Transform from the `viewBox` of an `<svg>` to its viewport, placed at its `x` and `y`
+	fn viewport(&self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Transform2<f32> {
+		use svgtypes::Align;
+
+		// The position of the document itself is ignored
+		let position: Transform2<f32> = match node.parent_element() {
+			Some(_) => nalgebra::convert(Translation2::new(
+				self.coordinate(node, properties, "x"),
+				self.coordinate(node, properties, "y"),
+			)),
+			None => Transform2::identity(),
+		};
+		let Some(view_box) = node
+			.attribute("viewBox")
+			.and_then(|v| svgtypes::ViewBox::from_str(v).ok())
+			.filter(|v| v.w > 0. && v.h > 0.)
+		else {
+			return position;
+		};
+		let (x, y, w, h) = (
+			view_box.x as f32,
+			view_box.y as f32,
+			view_box.w as f32,
+			view_box.h as f32,
+		);
+
+		// A missing or relative size is the size of the view box
+		let size = |name: &str, default: f32| {
+			node.attribute(name)
+				.and_then(|v| svgtypes::Length::from_str(v).ok())
+				.and_then(|v| length(v, properties.font_size()))
+				.filter(|v| *v > 0.)
+				.unwrap_or(default)
+		};
+		let (width, height) = (size("width", w), size("height", h));
+
+		let aspect_ratio = node
+			.attribute("preserveAspectRatio")
+			.and_then(|v| svgtypes::AspectRatio::from_str(v).ok())
+			.unwrap_or_default();
+		let (scale_x, scale_y) = match aspect_ratio.align {
+			Align::None => (width / w, height / h),
+			_ if aspect_ratio.slice => {
+				let scale = (width / w).max(height / h);
+				(scale, scale)
+			}
+			_ => {
+				let scale = (width / w).min(height / h);
+				(scale, scale)
+			}
+		};
+		let (align_x, align_y) = match aspect_ratio.align {
+			Align::None | Align::XMinYMin => (0., 0.),
+			Align::XMidYMin => (0.5, 0.),
+			Align::XMaxYMin => (1., 0.),
+			Align::XMinYMid => (0., 0.5),
+			Align::XMidYMid => (0.5, 0.5),
+			Align::XMaxYMid => (1., 0.5),
+			Align::XMinYMax => (0., 1.),
+			Align::XMidYMax => (0.5, 1.),
+			Align::XMaxYMax => (1., 1.),
+		};
+
+		let translation = Translation2::new(
+			(width - w * scale_x) * align_x - x * scale_x,
+			(height - h * scale_y) * align_y - y * scale_y,
+		);
+		position
+			* nalgebra::convert::<_, Transform2<f32>>(translation)
+			* nalgebra::convert::<_, Transform2<f32>>(Scale2::new(scale_x, scale_y))
+	}
+
+	/// Curve of the `<clipPath>` at `url`, clipping `shape`
+	///
+	/// `Some(None)` when the clip is empty, `None` when it can't be imported.
+	fn clip_path(&mut self, url: &str, shape: &Shape) -> Option<Option<Curve>> {
+		let clip_path = self
+			.by_url(url)
+			.filter(|v| v.has_tag_name("clipPath") && !self.used.contains(&v.id()))?;
+		let units = match clip_path.attribute("clipPathUnits") {
+			Some("objectBoundingBox") => bounding_box_units(shape)?,
+			_ => Transform2::identity(),
+		};
+
+		let properties = self.inherited(clip_path);
+		self.used.push(clip_path.id());
+		let in_clip_path = mem::replace(&mut self.in_clip_path, true);
+		let shapes = self.children(clip_path, &properties);
+		self.in_clip_path = in_clip_path;
+		self.used.pop();
+
+		if shapes.is_empty() {
+			return Some(None);
+		}
+		let mut clip = area(Shape::Group(Group {
+			local_transform: units * self.transform(clip_path),
+			shapes,
+			metadata: vec![],
+		}))?;
+		clip.closed();
+		Some(Some(clip))
+	}
+
+	/// Content and kind of the `<mask>` at `url`, masking `shape`
+	///
+	/// `Some(None)` when the mask is empty, `None` when it can't be imported.
+	fn mask(&mut self, url: &str, shape: &Shape) -> Option<Option<(Shape, MaskKind)>> {
+		let mask = self
+			.by_url(url)
+			.filter(|v| v.has_tag_name("mask") && !self.used.contains(&v.id()))?;
+		let units = match mask.attribute("maskContentUnits") {
+			Some("objectBoundingBox") => bounding_box_units(shape)?,
+			_ => Transform2::identity(),
+		};
+		let kind = match self.declared(mask, "mask-type") {
+			Some("alpha") => MaskKind::Alpha,
+			_ => MaskKind::Luminance,
+		};
+
+		let properties = self.inherited(mask);
+		self.used.push(mask.id());
+		let shapes = self.children(mask, &properties);
+		self.used.pop();
+
+		if shapes.is_empty() {
+			return Some(None);
+		}
+		Some(Some((
+			Shape::Group(Group {
+				local_transform: units,
+				shapes,
+				metadata: vec![],
+			}),
+			kind,
+		)))
+	}
+
+	/// Transform of the `transform` attribute, or `gradientTransform` for gradients
+	fn transform(&mut self, node: Node<'a, 'input>) -> Transform2<f32> {
+		let name = match node.tag_name().name() {
+			"linearGradient" | "radialGradient" => "gradientTransform",
+			_ => "transform",
+		};
+		let Some(transform) = node.attribute(name) else {
+			return Transform2::identity();
+		};
+
+		match svgtypes::Transform::from_str(transform) {
+			Ok(svgtypes::Transform { a, b, c, d, e, f }) => {
+				Transform2::from_matrix_unchecked(Matrix3::new(
+					a as f32, c as f32, e as f32, b as f32, d as f32, f as f32, 0., 0., 1.,
+				))
+			}
+			Err(_) => {
+				self.report(node, Some(name));
+				Transform2::identity()
+			}
+		}
+	}
+
+	fn coordinate(&self, node: Node<'a, 'input>, properties: &Properties<'a>, name: &str) -> f32 {
+		let Some(value) = node.attribute(name) else {
+			return 0.;
+		};
+
+		svgtypes::Length::from_str(value)
+			.ok()
+			.and_then(|v| length(v, properties.font_size()))
+			.unwrap_or(0.)
+	}
+
+	fn point(
+		&self,
+		node: Node<'a, 'input>,
+		properties: &Properties<'a>,
+		x: &str,
+		y: &str,
+	) -> Point2<f32> {
+		Point2::new(
+			self.coordinate(node, properties, x),
+			self.coordinate(node, properties, y),
+		)
+	}
+
+	fn group(&mut self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Shape {
+		Shape::Group(Group {
+			local_transform: Transform2::default(),
+			shapes: self.children(node, properties),
+			metadata: node
+				.attribute("id")
+				.map(|id| vec![("id".to_string(), id.to_string())])
+				.unwrap_or_default(),
+		})
+	}
+
+	fn use_element(
+		&mut self,
+		node: Node<'a, 'input>,
+		properties: &Properties<'a>,
+	) -> Option<Shape> {
+		let href = node
+			.attribute(("http://www.w3.org/1999/xlink", "href"))
+			.or_else(|| node.attribute("href"))?;
+		let used = href.strip_prefix('#').and_then(|id| self.by_id(id));
+		let Some(used) = used.filter(|v| !self.used.contains(&v.id())) else {
+			self.report(node, Some("href"));
+			return None;
+		};
+
+		self.used.push(used.id());
+		let shape = self.element(used, properties);
+		self.used.pop();
+
+		let mut shape = Shape::Group(Group {
+			local_transform: Transform2::default(),
+			shapes: shape.into_iter().collect(),
+			metadata: vec![],
+		});
+		shape.transform(nalgebra::convert(Translation2::new(
+			self.coordinate(node, properties, "x"),
+			self.coordinate(node, properties, "y"),
+		)));
+		Some(shape)
+	}
+
+	fn rect(&self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Option<Shape> {
+		let position = self.point(node, properties, "x", "y");
+		let (x, y) = (position.x, position.y);
+		let width = self.coordinate(node, properties, "width");
+		let height = self.coordinate(node, properties, "height");
+		if width <= 0. || height <= 0. {
+			return None;
+		}
+
+		// A missing radius is the same as the other one
+		let (rx, ry) = match (node.attribute("rx"), node.attribute("ry")) {
+			(None, None) => (0., 0.),
+			(Some(_), None) => {
+				let r = self.coordinate(node, properties, "rx");
+				(r, r)
+			}
+			(None, Some(_)) => {
+				let r = self.coordinate(node, properties, "ry");
+				(r, r)
+			}
+			(Some(_), Some(_)) => (
+				self.coordinate(node, properties, "rx"),
+				self.coordinate(node, properties, "ry"),
+			),
+		};
+		let (rx, ry) = (rx.clamp(0., width / 2.), ry.clamp(0., height / 2.));
+
+		let mut curve = Curve::default();
+		curve.closed();
+		if rx == 0. || ry == 0. {
+			curve.extend([
+				Point2::new(x, y).into(),
+				Point2::new(x + width, y).into(),
+				Point2::new(x + width, y + height).into(),
+				Point2::new(x, y + height).into(),
+			]);
+		} else {
+			let corner =
+				|x, y| EllipticalArc::new(Vector2::new(rx, ry), 0., false, true, Point2::new(x, y));
+			curve.extend([
+				Point2::new(x + rx, y).into(),
+				Point2::new(x + width - rx, y).into(),
+				corner(x + width, y + ry).into(),
+				Point2::new(x + width, y + height - ry).into(),
+				corner(x + width - rx, y + height).into(),
+				Point2::new(x + rx, y + height).into(),
+				corner(x, y + height - ry).into(),
+				Point2::new(x, y + ry).into(),
+				corner(x + rx, y).into(),
+			]);
+		}
+
+		Some(curve.into())
+	}
+
+	fn ellipse(
+		&self,
+		node: Node<'a, 'input>,
+		properties: &Properties<'a>,
+		rx: f32,
+		ry: f32,
+	) -> Option<Shape> {
+		if rx <= 0. || ry <= 0. {
+			return None;
+		}
+
+		let center = self.point(node, properties, "cx", "cy");
+		Some(dessin!(
+			Ellipse(
+				semi_major_axis = rx,
+				semi_minor_axis = ry,
+				translate = Translation2::new(center.x, center.y),
+			) > ()
+		))
+	}
+
+	fn text(&mut self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Option<Shape> {
+		for span in node.descendants().filter(|v| v.has_tag_name("tspan")) {
+			for attribute in ["x", "y", "dx", "dy", "rotate"] {
+				if span.has_attribute(attribute) {
+					self.report(span, Some(attribute));
+				}
+			}
+		}
+
+		let text = node
+			.descendants()
+			.filter(|v| v.is_text())
+			.filter_map(|v| v.text())
+			.collect::<String>();
+		let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+		if text.is_empty() {
+			return None;
+		}
+
+		let align = match properties.get("text-anchor") {
+			Some("middle") => TextAlign::Center,
+			Some("end") => TextAlign::Right,
+			_ => TextAlign::Left,
+		};
+		// Relative weights are taken relative to a regular parent
+		let font_weight = match properties.get("font-weight") {
+			Some("bold" | "bolder") => FontWeight::BOLD,
+			Some("lighter") => FontWeight::THIN,
+			Some(weight) => weight
+				.parse::<f32>()
+				.map(|v| FontWeight(v.clamp(1., 1000.) as u16))
+				.unwrap_or_default(),
+			None => FontWeight::REGULAR,
+		};
+		let font_style = match properties.get("font-style") {
+			Some("italic") => FontStyle::Italic,
+			Some(style) if style.starts_with("oblique") => FontStyle::Oblique,
+			_ => FontStyle::Normal,
+		};
+		let font_stretch = match properties.get("font-stretch") {
+			Some("ultra-condensed") => FontStretch::UltraCondensed,
+			Some("extra-condensed") => FontStretch::ExtraCondensed,
+			Some("condensed" | "narrower") => FontStretch::Condensed,
+			Some("semi-condensed") => FontStretch::SemiCondensed,
+			Some("semi-expanded") => FontStretch::SemiExpanded,
+			Some("expanded" | "wider") => FontStretch::Expanded,
+			Some("extra-expanded") => FontStretch::ExtraExpanded,
+			Some("ultra-expanded") => FontStretch::UltraExpanded,
+			// Percentages go to the closest keyword
+			Some(stretch) => match stretch.strip_suffix('%').map(str::parse::<f32>) {
+				Some(Ok(percentage)) => [
+					FontStretch::UltraCondensed,
+					FontStretch::ExtraCondensed,
+					FontStretch::Condensed,
+					FontStretch::SemiCondensed,
+					FontStretch::Normal,
+					FontStretch::SemiExpanded,
+					FontStretch::Expanded,
+					FontStretch::ExtraExpanded,
+					FontStretch::UltraExpanded,
+				]
+				.into_iter()
+				.min_by(|a, b| {
+					(a.percentage() - percentage)
+						.abs()
+						.total_cmp(&(b.percentage() - percentage).abs())
+				})
+				.unwrap_or_default(),
+				_ => FontStretch::Normal,
+			},
+			None => FontStretch::Normal,
+		};
+
+		// Only fonts already registered can be exported
+		let font = properties.get("font-family").and_then(|families| {
+			let names = font::font_names();
+			let found = families
+				.split(',')
+				.map(|v| v.trim().trim_matches(['"', '\'']))
+				.find(|family| names.iter().any(|name| name.as_str() == *family))
+				.map(FontRef::from);
+			if found.is_none() {
+				self.report(node, Some("font-family"));
+			}
+			found
+		});
+
+		let position = self.point(node, properties, "x", "y");
+		let mut text = Text {
+			text,
+			align,
+			vertical_align: TextVerticalAlign::Baseline,
+			font_weight,
+			font_style,
+			font_stretch,
+			font_size: properties.font_size(),
+			font,
+			..Default::default()
+		};
+		text.translate(Translation2::new(position.x, position.y));
+
+		Some(text.into())
+	}
+
+	fn image(&mut self, node: Node<'a, 'input>, properties: &Properties<'a>) -> Option<Shape> {
+		let href = node
+			.attribute(("http://www.w3.org/1999/xlink", "href"))
+			.or_else(|| node.attribute("href"))?;
+
+		// Only images embedded in the document can be imported
+		let image = href
+			.strip_prefix("data:")
+			.and_then(|v| v.split_once(";base64,"))
+			.and_then(|(_, data)| {
+				let data = data.split_whitespace().collect::<String>();
+				data_encoding::BASE64.decode(data.as_bytes()).ok()
+			})
+			.and_then(|bytes| ::image::load_from_memory(&bytes).ok());
+		let Some(image) = image else {
+			self.report(node, Some("href"));
+			return None;
+		};
+
+		let position = self.point(node, properties, "x", "y");
+		let (x, y) = (position.x, position.y);
+		let (width, height) = match (node.attribute("width"), node.attribute("height")) {
+			(Some(_), Some(_)) => (
+				self.coordinate(node, properties, "width"),
+				self.coordinate(node, properties, "height"),
+			),
+			_ => (image.width() as f32, image.height() as f32),
+		};
+
+		Some(dessin!(
+			Image(
+				{ image },
+				scale = [width, height],
+				translate = [x + width / 2., y + height / 2.],
+			) > ()
+		))
+	}
+
+	/// Attribute of a gradient, or of the gradients it references
+	fn gradient_attribute(&self, gradient: Node<'a, 'input>, name: &str) -> Option<&'a str> {
+		self.gradient_chain(gradient)
+			.into_iter()
+			.find_map(|v| v.attribute(name))
+	}
+
+	/// Gradient and the gradients it references with `href`, in order
+	fn gradient_chain(&self, gradient: Node<'a, 'input>) -> Vec<Node<'a, 'input>> {
+		let mut chain = vec![gradient];
+		while let Some(next) = chain
+			.last()
+			.and_then(|v| {
+				v.attribute(("http://www.w3.org/1999/xlink", "href"))
+					.or_else(|| v.attribute("href"))
+			})
+			.and_then(|href| href.strip_prefix('#'))
+			.and_then(|id| self.by_id(id))
+			.filter(|v| v.has_tag_name("linearGradient") || v.has_tag_name("radialGradient"))
+		{
+			if chain.contains(&next) {
+				break;
+			}
+			chain.push(next);
+		}
+		chain
+	}
+
+	/// Fill of a `<linearGradient>` or `<radialGradient>` painting `shape`, `None` when nothing is painted
+	fn gradient(
+		&mut self,
+		gradient: Node<'a, 'input>,
+		shape: &Shape,
+		opacity: f32,
+	) -> Option<Fill> {
+		// Stops come from the first gradient having some
+		let mut stops = vec![];
+		if let Some(with_stops) = self
+			.gradient_chain(gradient)
+			.into_iter()
+			.find(|v| v.children().any(|v| v.has_tag_name("stop")))
+		{
+			for stop in with_stops.children().filter(|v| v.has_tag_name("stop")) {
+				let properties = self.properties(stop, &Properties::default());
+				let color = match properties.get("stop-color") {
+					Some("currentColor") => properties.get("color"),
+					color => color,
+				}
+				.and_then(|v| svgtypes::Color::from_str(v).ok())
+				.unwrap_or_else(svgtypes::Color::black);
+				let alpha = color.alpha as f32 / 255.
+					* properties.number("stop-opacity").unwrap_or(1.)
+					* opacity;
+
+				// Offsets never go back
+				let offset = stop
+					.attribute("offset")
+					.and_then(fraction)
+					.unwrap_or(0.)
+					.clamp(0., 1.)
+					.max(stops.last().map_or(0., |v: &GradientStop| v.offset));
+				stops.push(GradientStop {
+					offset,
+					color: Srgba::new(
+						color.red as f32 / 255.,
+						color.green as f32 / 255.,
+						color.blue as f32 / 255.,
+						alpha,
+					),
+				});
+			}
+		}
+		match stops.as_slice() {
+			[] => return None,
+			[stop] => return Some(stop.color.into()),
+			_ => {}
+		}
+
+		let units = match self.gradient_attribute(gradient, "gradientUnits") {
+			Some("userSpaceOnUse") => Transform2::identity(),
+			// Shapes without area aren't painted by gradients in their bounding box
+			_ => bounding_box_units(shape)?,
+		};
+		let transform = units
+			* self
+				.gradient_chain(gradient)
+				.into_iter()
+				.find(|v| v.has_attribute("gradientTransform"))
+				.map(|v| self.transform(v))
+				.unwrap_or_else(Transform2::identity);
+		let spread = match self.gradient_attribute(gradient, "spreadMethod") {
+			Some("reflect") => GradientSpread::Reflect,
+			Some("repeat") => GradientSpread::Repeat,
+			_ => GradientSpread::Pad,
+		};
+		let coordinate = |name: &str, default: f32| {
+			self.gradient_attribute(gradient, name)
+				.and_then(fraction)
+				.unwrap_or(default)
+		};
+
+		Some(if gradient.has_tag_name("linearGradient") {
+			Fill::LinearGradient {
+				start: Point2::new(coordinate("x1", 0.), coordinate("y1", 0.)),
+				end: Point2::new(coordinate("x2", 1.), coordinate("y2", 0.)),
+				stops,
+				spread,
+				transform,
+			}
+		} else {
+			let center = Point2::new(coordinate("cx", 0.5), coordinate("cy", 0.5));
+			Fill::RadialGradient {
+				center,
+				radius: coordinate("r", 0.5),
+				focal: Point2::new(coordinate("fx", center.x), coordinate("fy", center.y)),
+				stops,
+				spread,
+				transform,
+			}
+		})
+	}
+
+	/// Color of the paint property `name`, `None` when nothing is painted
+	fn color(
+		&mut self,
+		node: Node<'a, 'input>,
+		properties: &Properties<'a>,
+		name: &str,
+		default: &str,
+	) -> Option<Srgba> {
+		let paint = properties.get(name).unwrap_or(default);
+		let current_color = || {
+			properties
+				.get("color")
+				.and_then(|v| svgtypes::Color::from_str(v).ok())
+				.unwrap_or_else(svgtypes::Color::black)
+		};
+
+		let color = match svgtypes::Paint::from_str(paint) {
+			Ok(svgtypes::Paint::None) => None,
+			Ok(svgtypes::Paint::Color(color)) => Some(color),
+			Ok(svgtypes::Paint::CurrentColor) => Some(current_color()),
+			Ok(svgtypes::Paint::FuncIRI(_, fallback)) => {
+				self.report(node, Some(name));
+				match fallback {
+					Some(svgtypes::PaintFallback::Color(color)) => Some(color),
+					Some(svgtypes::PaintFallback::CurrentColor) => Some(current_color()),
+					Some(svgtypes::PaintFallback::None) | None => None,
+				}
+			}
+			_ => {
+				self.report(node, Some(name));
+				None
+			}
+		}?;
+
+		Some(Srgba::new(
+			color.red as f32 / 255.,
+			color.green as f32 / 255.,
+			color.blue as f32 / 255.,
+			color.alpha as f32 / 255. * properties.opacity,
+		))
+	}
+
+	/// Wrap a leaf `shape` in its fill and stroke
+	fn styled(
+		&mut self,
+		node: Node<'a, 'input>,
+		properties: &Properties<'a>,
+		mut shape: Shape,
+	) -> Shape {
+		let fill_opacity = properties.number("fill-opacity").unwrap_or(1.);
+		let gradient = properties
+			.get("fill")
+			.and_then(|v| svgtypes::Paint::from_str(v).ok())
+			.and_then(|paint| match paint {
+				svgtypes::Paint::FuncIRI(id, _) => self.by_id(id),
+				_ => None,
+			})
+			.filter(|v| v.has_tag_name("linearGradient") || v.has_tag_name("radialGradient"));
+		let fill = match gradient {
+			Some(gradient) => self.gradient(gradient, &shape, fill_opacity * properties.opacity),
+			None => self
+				.color(node, properties, "fill", "black")
+				.map(|mut color| {
+					color.alpha *= fill_opacity;
+					color.into()
+				}),
+		};
+
+		let stroke = self
+			.color(node, properties, "stroke", "none")
+			.map(|mut color| {
+				color.alpha *= properties.number("stroke-opacity").unwrap_or(1.);
+
+				let mut stroke =
+					Stroke::new_solid(color, properties.length("stroke-width").unwrap_or(1.))
+						.with_cap(match properties.get("stroke-linecap") {
+							Some("round") => LineCap::Round,
+							Some("square") => LineCap::Square,
+							_ => LineCap::Butt,
+						})
+						.with_join(match properties.get("stroke-linejoin") {
+							Some("round") => LineJoin::Round,
+							Some("bevel") => LineJoin::Bevel,
+							_ => LineJoin::Miter,
+						});
+				if let Some(miter_limit) = properties.number("stroke-miterlimit") {
+					stroke = stroke.with_miter_limit(miter_limit);
+				}
+				if let Some(dash) = properties.get("stroke-dasharray").filter(|v| *v != "none") {
+					let dash = svgtypes::LengthListParser::from(dash)
+						.filter_map(Result::ok)
+						.filter_map(|v| length(v, properties.font_size()))
+						.collect::<Vec<_>>();
+					stroke = stroke
+						.with_dash(dash, properties.length("stroke-dashoffset").unwrap_or(0.));
+				}
+				stroke
+			});
+
+		let fill_rule = match self.in_clip_path {
+			true => properties.get("clip-rule"),
+			false => properties.get("fill-rule"),
+		};
+		if let (Shape::Curve(curve), Some("evenodd")) = (&mut shape, fill_rule) {
+			curve.fill_rule = FillRule::EvenOdd;
+		}
+
+		Shape::Style {
+			fill,
+			stroke,
+			shape: Box::new(shape),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn import(svg: &str) -> SVGImport {
+		from_str(&format!(
+			r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">{svg}</svg>"#
+		))
+		.unwrap()
+	}
+
+	fn shapes(import: &SVGImport) -> &[Shape] {
+		match &import.shape {
+			Shape::Group(Group { shapes, .. }) => shapes,
+			_ => panic!("the import is a group"),
+		}
+	}
+
+	/// Top left and bottom right corners of the import, in dessin coordinates
+	fn corners(import: &SVGImport) -> (Point2<f32>, Point2<f32>) {
+		let bb = import.shape.local_bounding_box().straigthen();
+		(bb.top_left(), bb.bottom_right())
+	}
+
+	fn assert_close(a: Point2<f32>, b: Point2<f32>) {
+		assert!((a - b).magnitude() < 10e-4, "{a} != {b}");
+	}
+
+	fn fill(shape: &Shape) -> &Fill {
+		match shape {
+			Shape::Style {
+				fill: Some(fill), ..
+			} => fill,
+			_ => panic!("{shape:?} is not filled"),
+		}
+	}
+
+	#[test]
+	fn elements() {
+		let import = import(
+			r#"<rect x="10" y="10" width="20" height="10"/>
+			<rect width="20" height="10" rx="2"/>
+			<circle r="5"/>
+			<ellipse rx="5" ry="2"/>
+			<line x2="10" y2="10" stroke="black"/>
+			<polyline points="0,0 10,0 10,10" stroke="black"/>
+			<polygon points="0,0 10,0 10,10"/>
+			<path d="M 0 0 L 10 0 Z"/>
+			<rect width="0" height="10"/>"#,
+		);
+		assert!(import.unsupported.is_empty());
+
+		let inner = shapes(&import)
+			.iter()
+			.map(|v| match v {
+				Shape::Style { shape, .. } => shape.as_ref(),
+				_ => panic!("{v:?} is not styled"),
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(inner.len(), 8);
+		assert!(matches!(inner[0], Shape::Curve(v) if v.closed));
+		assert!(matches!(inner[2], Shape::Ellipse(_)));
+		assert!(matches!(inner[3], Shape::Ellipse(_)));
+		assert!(matches!(inner[5], Shape::Curve(v) if !v.closed));
+		assert!(matches!(inner[6], Shape::Curve(v) if v.closed));
+
+		let bb = inner[0].local_bounding_box().straigthen();
+		assert_close(
+			bb.bottom_right() - bb.top_left().coords,
+			Point2::new(20., -10.),
+		);
+		let bb = inner[3].local_bounding_box().straigthen();
+		assert_close(bb.top_left(), Point2::new(-5., 2.));
+		assert_close(bb.bottom_right(), Point2::new(5., -2.));
+
+		// Only the line and the polyline are stroked
+		let stroked = shapes(&import)
+			.iter()
+			.map(|v| {
+				matches!(
+					v,
+					Shape::Style {
+						stroke: Some(_),
+						..
+					}
+				)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			stroked,
+			[false, false, false, false, true, true, false, false]
+		);
+	}
+
+	#[test]
+	fn transforms() {
+		// The dessin is flipped upside up
+		let flipped = import(r#"<rect x="10" y="10" width="20" height="10"/>"#);
+		assert_eq!(
+			corners(&flipped),
+			(Point2::new(10., -10.), Point2::new(30., -20.))
+		);
+
+		let transformed = import(
+			r#"<g transform="translate(10 20)">
+				<rect width="20" height="10" transform="scale(2) rotate(90)"/>
+			</g>"#,
+		);
+		let (top_left, bottom_right) = corners(&transformed);
+		assert_close(top_left, Point2::new(-10., -20.));
+		assert_close(bottom_right, Point2::new(10., -60.));
+
+		let invalid = import(r#"<rect width="20" height="10" transform="skew(nope)"/>"#);
+		assert_eq!(
+			invalid.unsupported[0].attribute.as_deref(),
+			Some("transform")
+		);
+	}
+
+	#[test]
+	fn css() {
+		let import = import(
+			r#"<style>.blue { fill: blue } #green { fill: lime }</style>
+			<g fill="red" fill-opacity="0.5">
+				<rect width="1" height="1"/>
+				<rect class="blue" width="1" height="1"/>
+				<rect id="green" class="blue" width="1" height="1"/>
+				<rect class="blue" style="fill: white; opacity: 0.5" width="1" height="1"/>
+				<rect fill="currentColor" color="black" width="1" height="1"/>
+				<rect style="display: none" width="1" height="1"/>
+				<rect visibility="hidden" width="1" height="1"/>
+			</g>"#,
+		);
+		let Shape::Group(Group { shapes, .. }) = &shapes(&import)[0] else {
+			panic!("<g> is a group");
+		};
+
+		let fills = shapes.iter().map(fill).collect::<Vec<_>>();
+		assert_eq!(
+			fills,
+			[
+				&Srgba::new(1., 0., 0., 0.5).into(),
+				&Srgba::new(0., 0., 1., 0.5).into(),
+				&Srgba::new(0., 1., 0., 0.5).into(),
+				&Srgba::new(1., 1., 1., 0.25).into(),
+				&Srgba::new(0., 0., 0., 0.5).into(),
+			]
+		);
+	}
+
+	#[test]
+	fn use_element() {
+		let import = import(
+			r##"<defs><rect id="r" width="10" height="10"/></defs>
+			<use href="#r" x="5" y="5"/>
+			<use xlink:href="#r" x="20"/>
+			<use href="#missing"/>
+			<g id="cycle"><use href="#cycle"/></g>"##,
+		);
+
+		// Placed at their position, in SVG coordinates
+		let bb = shapes(&import)[0].local_bounding_box().straigthen();
+		assert_close(bb.bottom_left(), Point2::new(5., 5.));
+		assert_close(bb.top_right(), Point2::new(15., 15.));
+		let bb = shapes(&import)[1].local_bounding_box().straigthen();
+		assert_close(bb.bottom_left(), Point2::new(20., 0.));
+
+		let reported = import
+			.unsupported
+			.iter()
+			.map(|v| (v.element.as_str(), v.attribute.as_deref(), v.line))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			reported,
+			[("use", Some("href"), 4), ("use", Some("href"), 5)]
+		);
+	}
+
+	#[test]
+	fn text() {
+		let import = import(
+			r#"<text x="10" y="20" font-size="12" font-weight="bold" font-style="italic" text-anchor="middle">
+				Hello   <tspan>world</tspan>
+			</text>
+			<text font-family="Missing, serif">a</text>
+			<text> </text>"#,
+		);
+
+		let Shape::Style { shape, .. } = &shapes(&import)[0] else {
+			panic!("texts are styled");
+		};
+		let Shape::Text(text) = shape.as_ref() else {
+			panic!("{shape:?} is not a text");
+		};
+		assert_eq!(text.text, "Hello world");
+		assert_eq!(text.font_size, 12.);
+		assert_eq!(text.font_weight, FontWeight::BOLD);
+		assert_eq!(text.font_style, FontStyle::Italic);
+		assert_eq!(text.align, TextAlign::Center);
+		assert_close(
+			text.local_transform * Point2::origin(),
+			Point2::new(10., 20.),
+		);
+
+		// Unknown families fall back to the default font, empty texts are dropped
+		assert_eq!(shapes(&import).len(), 2);
+		assert_eq!(
+			import.unsupported[0].attribute.as_deref(),
+			Some("font-family")
+		);
+	}
+
+	#[test]
+	fn image() {
+		let mut png = vec![];
+		::image::DynamicImage::new_rgb8(4, 2)
+			.write_to(
+				&mut std::io::Cursor::new(&mut png),
+				::image::ImageFormat::Png,
+			)
+			.unwrap();
+		let data = data_encoding::BASE64.encode(&png);
+
+		let import = import(&format!(
+			r#"<image x="10" y="10" href="data:image/png;base64,{data}"/>
+			<image href="image.png"/>"#
+		));
+
+		let Shape::Image(image) = &shapes(&import)[0] else {
+			panic!("embedded images are imported");
+		};
+		assert_eq!((image.image.width(), image.image.height()), (4, 2));
+		assert_eq!(
+			corners(&import),
+			(Point2::new(10., -10.), Point2::new(14., -12.))
+		);
+
+		// External images can't be imported
+		assert_eq!(shapes(&import).len(), 1);
+		assert_eq!(import.unsupported[0].attribute.as_deref(), Some("href"));
+	}
+
+	#[test]
+	fn unsupported() {
+		let import = import(
+			r#"<foreignObject/>
+			<rect width="1" height="1" filter="url(#f)" marker-end="url(#m)"/>
+			<rect width="1" height="1" fill="url(#pattern)" stroke="url(#gradient) red"/>
+			<pattern id="pattern"/>
+			<linearGradient id="gradient"/>"#,
+		);
+
+		let reported = import
+			.unsupported
+			.iter()
+			.map(|v| (v.element.as_str(), v.attribute.as_deref(), v.line))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			reported,
+			[
+				("foreignObject", None, 1),
+				("rect", Some("filter"), 2),
+				("rect", Some("marker-end"), 2),
+				("rect", Some("fill"), 3),
+				("rect", Some("stroke"), 3),
+				("pattern", None, 4),
+			]
+		);
+
+		// Paints fall back to their fallback color
+		let Shape::Style { fill, stroke, .. } = &shapes(&import)[1] else {
+			panic!("rects are styled");
+		};
+		assert_eq!(fill, &None);
+		assert!(stroke.is_some());
+	}
+
+	#[test]
+	fn gradients() {
+		let import = import(
+			r##"<linearGradient id="linear" x2="0" y2="1" spreadMethod="reflect">
+				<stop offset="0" stop-color="red"/>
+				<stop offset="50%" style="stop-color: blue; stop-opacity: 0.5"/>
+				<stop offset="0.2" stop-color="lime"/>
+			</linearGradient>
+			<radialGradient id="radial" href="#linear" gradientUnits="userSpaceOnUse" r="10" fx="2"/>
+			<linearGradient id="solid"><stop stop-color="blue"/></linearGradient>
+			<linearGradient id="empty"/>
+			<rect x="10" y="10" width="20" height="10" fill="url(#linear)"/>
+			<rect width="20" height="10" fill="url(#radial)" opacity="0.5"/>
+			<rect width="20" height="10" fill="url(#solid)"/>
+			<rect width="20" height="10" fill="url(#empty)"/>"##,
+		);
+		assert!(import.unsupported.is_empty());
+
+		let Fill::LinearGradient {
+			start,
+			end,
+			stops,
+			spread,
+			transform,
+		} = fill(&shapes(&import)[0])
+		else {
+			panic!("linear gradient");
+		};
+		assert_eq!(spread, &GradientSpread::Reflect);
+		// From the bounding box of the rect
+		assert_close(transform * start, Point2::new(10., 10.));
+		assert_close(transform * end, Point2::new(10., 20.));
+		assert_eq!(
+			stops,
+			&[
+				GradientStop {
+					offset: 0.,
+					color: Srgba::new(1., 0., 0., 1.),
+				},
+				GradientStop {
+					offset: 0.5,
+					color: Srgba::new(0., 0., 1., 0.5),
+				},
+				// Offsets never go back
+				GradientStop {
+					offset: 0.5,
+					color: Srgba::new(0., 1., 0., 1.),
+				},
+			]
+		);
+
+		let Fill::RadialGradient {
+			center,
+			radius,
+			focal,
+			stops,
+			spread,
+			transform,
+		} = fill(&shapes(&import)[1])
+		else {
+			panic!("radial gradient");
+		};
+		assert_eq!(transform, &Transform2::identity());
+		assert_eq!(spread, &GradientSpread::Reflect);
+		assert_eq!(radius, &10.);
+		assert_close(*center, Point2::new(0.5, 0.5));
+		assert_close(*focal, Point2::new(2., 0.5));
+		// Stops come from the referenced gradient, with the opacity of the element
+		assert_eq!(stops[0].color, Srgba::new(1., 0., 0., 0.5));
+
+		assert_eq!(
+			fill(&shapes(&import)[2]),
+			&Srgba::new(0., 0., 1., 1.).into()
+		);
+		assert!(matches!(
+			&shapes(&import)[3],
+			Shape::Style { fill: None, .. }
+		));
+	}
+
+	#[test]
+	fn clip_path() {
+		let import = import(
+			r##"<clipPath id="clip">
+				<rect width="10" height="10"/>
+				<circle cx="10" cy="10" r="5"/>
+			</clipPath>
+			<clipPath id="box" clipPathUnits="objectBoundingBox">
+				<rect width="0.5" height="1"/>
+			</clipPath>
+			<clipPath id="empty"/>
+			<rect width="100" height="100" clip-path="url(#clip)"/>
+			<rect x="10" width="20" height="10" style="clip-path: url(#box)"/>
+			<rect width="100" height="100" clip-path="url(#empty)"/>
+			<rect width="100" height="100" clip-path="url(#missing)"/>"##,
+		);
+		assert_eq!(shapes(&import).len(), 3);
+		assert_eq!(
+			import.unsupported[0].attribute.as_deref(),
+			Some("clip-path")
+		);
+
+		let Shape::Clip { clip, shape } = &shapes(&import)[0] else {
+			panic!("clipped rect");
+		};
+		assert!(matches!(shape.as_ref(), Shape::Style { .. }));
+		let bb = clip.local_bounding_box().straigthen();
+		assert_close(bb.bottom_left(), Point2::new(0., 0.));
+		assert_close(bb.top_right(), Point2::new(15., 15.));
+
+		let Shape::Clip { clip, .. } = &shapes(&import)[1] else {
+			panic!("clipped rect");
+		};
+		let bb = clip.local_bounding_box().straigthen();
+		assert_close(bb.bottom_left(), Point2::new(10., 0.));
+		assert_close(bb.top_right(), Point2::new(20., 10.));
+
+		// A clipped rect is only hit inside the clip, in dessin coordinates
+		assert_eq!(import.shape.hit_test(Point2::new(5., -5.)).len(), 2);
+		assert_eq!(import.shape.hit_test(Point2::new(50., -50.)).len(), 1);
+	}
+
+	#[test]
+	fn mask() {
+		let import = import(
+			r##"<mask id="mask"><rect width="10" height="10" fill="white"/></mask>
+			<mask id="alpha" style="mask-type: alpha" maskContentUnits="objectBoundingBox">
+				<rect width="0.5" height="0.5"/>
+			</mask>
+			<mask id="empty"/>
+			<rect width="100" height="100" mask="url(#mask)"/>
+			<rect width="20" height="20" mask="url(#alpha)"/>
+			<rect width="100" height="100" mask="url(#empty)"/>"##,
+		);
+		assert!(import.unsupported.is_empty());
+		assert_eq!(shapes(&import).len(), 2);
+
+		let Shape::Mask { mask, kind, .. } = &shapes(&import)[0] else {
+			panic!("masked rect");
+		};
+		assert_eq!(kind, &MaskKind::Luminance);
+		assert_eq!(fill(&mask_child(mask)), &Srgba::new(1., 1., 1., 1.).into());
+
+		let Shape::Mask { mask, kind, .. } = &shapes(&import)[1] else {
+			panic!("masked rect");
+		};
+		assert_eq!(kind, &MaskKind::Alpha);
+		let bb = mask.local_bounding_box().straigthen();
+		assert_close(bb.top_left(), Point2::new(0., 10.));
+		assert_close(bb.bottom_right(), Point2::new(10., 0.));
+	}
+
+	fn mask_child(mask: &Shape) -> Shape {
+		match mask {
+			Shape::Group(Group { shapes, .. }) => shapes[0].clone(),
+			_ => panic!("masks are groups"),
+		}
+	}
+
+	#[test]
+	fn view_box() {
+		let view_box = |attributes: &str| {
+			let import = from_str(&format!(
+				r#"<svg xmlns="http://www.w3.org/2000/svg" {attributes}>
+					<rect width="10" height="10"/>
+				</svg>"#
+			))
+			.unwrap();
+			corners(&import)
+		};
+
+		// Centered and scaled to fit
+		assert_eq!(
+			view_box(r#"width="200" height="100" viewBox="0 0 10 10""#),
+			(Point2::new(50., 0.), Point2::new(150., -100.))
+		);
+		assert_eq!(
+			view_box(
+				r#"width="200" height="100" viewBox="0 0 10 10" preserveAspectRatio="xMaxYMin slice""#
+			),
+			(Point2::new(0., 0.), Point2::new(200., -200.))
+		);
+		assert_eq!(
+			view_box(r#"width="200" height="100" viewBox="5 0 10 10" preserveAspectRatio="none""#),
+			(Point2::new(-100., 0.), Point2::new(100., -100.))
+		);
+		// Without a size, coordinates are the ones of the view box
+		assert_eq!(
+			view_box(r#"viewBox="0 0 100 100""#),
+			(Point2::new(0., 0.), Point2::new(10., -10.))
+		);
+
+		// Nested documents are placed at their position
+		let import = import(
+			r#"<svg x="10" y="10" width="20" height="20" viewBox="0 0 10 10">
+				<rect width="10" height="10"/>
+			</svg>"#,
+		);
+		assert_eq!(
+			corners(&import),
+			(Point2::new(10., -10.), Point2::new(30., -30.))
+		);
+	}
+}
@@ -12,10 +12,14 @@ use std::{
 	sync::{atomic::AtomicU32, LazyLock},
 };
 
+mod import;
+pub use import::*;
+
 #[derive(Debug)]
 pub enum SVGError {
 	WriteError(fmt::Error),
 	CurveHasNoStartingPoint(CurvePosition),
+	FontError(font::FontError),
 }
 impl fmt::Display for SVGError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -27,6 +31,11 @@ impl From<fmt::Error> for SVGError {
 		SVGError::WriteError(value)
 	}
 }
+impl From<font::FontError> for SVGError {
+	fn from(value: font::FontError) -> Self {
+		SVGError::FontError(value)
+	}
+}
 impl std::error::Error for SVGError {}
 
 #[derive(Default, Clone, Copy, PartialEq)]
@@ -51,59 +60,151 @@ pub enum ViewPort {
 pub struct SVGOptions {
 	pub viewport: ViewPort,
 	pub skip_svg_tag: bool,
+	/// Draw texts as the outlines of their glyphs, without embedding fonts
+	pub outline_text: bool,
 }
 
 pub struct SVGExporter {
 	acc: String,
-	used_font: HashSet<(FontRef, FontWeight)>,
+	used_font: HashSet<(FontRef, FontProperties)>,
 }
 
 impl SVGExporter {
 	pub fn new() -> Self {
 		let acc = String::new();
-		let used_font: HashSet<(FontRef, FontWeight)> = HashSet::default();
+		let used_font: HashSet<(FontRef, FontProperties)> = HashSet::default();
 
 		SVGExporter { acc, used_font }
 	}
 
-	fn write_style(&mut self, style: StylePosition) -> Result<(), SVGError> {
-		match style.fill {
-			Some(Fill::Solid { color }) => write!(
+	fn write_gradient(&mut self, fill: &Fill) -> Result<Option<u32>, SVGError> {
+		static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+
+		let (stops, spread, transform) = match fill {
+			Fill::Solid { .. } => return Ok(None),
+			Fill::LinearGradient {
+				stops,
+				spread,
+				transform,
+				..
+			}
+			| Fill::RadialGradient {
+				stops,
+				spread,
+				transform,
+				..
+			} => (stops, spread, transform),
+		};
+
+		let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+
+		let spread = match spread {
+			GradientSpread::Pad => "pad",
+			GradientSpread::Reflect => "reflect",
+			GradientSpread::Repeat => "repeat",
+		};
+		let m = transform.matrix();
+
+		write!(self.acc, "<defs>")?;
+		match fill {
+			Fill::Solid { .. } => unreachable!(),
+			Fill::LinearGradient { start, end, .. } => write!(
 				self.acc,
-				"fill='rgb({} {} {} / {:.3})' ",
+				"<linearGradient id='gradient{id}' x1='{}' y1='{}' x2='{}' y2='{}' ",
+				start.x, start.y, end.x, end.y
+			)?,
+			Fill::RadialGradient {
+				center,
+				radius,
+				focal,
+				..
+			} => write!(
+				self.acc,
+				"<radialGradient id='gradient{id}' cx='{}' cy='{}' r='{radius}' fx='{}' fy='{}' ",
+				center.x, center.y, focal.x, focal.y
+			)?,
+		}
+		write!(
+			self.acc,
+			"gradientUnits='userSpaceOnUse' spreadMethod='{spread}' gradientTransform='matrix({} {} {} {} {} {})'>",
+			m[(0, 0)],
+			m[(1, 0)],
+			m[(0, 1)],
+			m[(1, 1)],
+			m[(0, 2)],
+			m[(1, 2)],
+		)?;
+
+		for GradientStop { offset, color } in stops {
+			write!(
+				self.acc,
+				"<stop offset='{offset}' stop-color='rgb({} {} {})' stop-opacity='{:.3}'/>",
 				(color.red * 255.) as u32,
 				(color.green * 255.) as u32,
 				(color.blue * 255.) as u32,
 				color.alpha
-			)?, // pass [0;1] number to [0;255] for a working CSS code (not needed for alpha)
+			)?;
+		}
 
-			None => write!(self.acc, "fill='none' ")?,
+		match fill {
+			Fill::LinearGradient { .. } => write!(self.acc, "</linearGradient></defs>")?,
+			_ => write!(self.acc, "</radialGradient></defs>")?,
 		}
 
-		match style.stroke {
-			Some(Stroke::Dashed {
-				color,
-				width,
-				on,
-				off,
-			}) => write!(
+		Ok(Some(id))
+	}
+
+	fn write_style(&mut self, style: StylePosition, gradient: Option<u32>) -> Result<(), SVGError> {
+		match style.fill {
+			Some(Fill::LinearGradient { .. } | Fill::RadialGradient { .. }) => {
+				if let Some(id) = gradient {
+					write!(self.acc, "fill='url(#gradient{id})' ")?
+				}
+			}
+			Some(Fill::Solid { color }) => write!(
 				self.acc,
-				"stroke='rgb({} {} {} / {:.3})' stroke-width='{width}' stroke-dasharray='{on},{off}' ",
+				"fill='rgb({} {} {} / {:.3})' ",
 				(color.red * 255.) as u32,
 				(color.green * 255.) as u32,
 				(color.blue * 255.) as u32,
 				color.alpha
-			)?,
-			Some(Stroke::Solid { color, width }) => write!(
+			)?, // pass [0;1] number to [0;255] for a working CSS code (not needed for alpha)
+
+			None => write!(self.acc, "fill='none' ")?,
+		}
+
+		if let Some(stroke) = style.stroke {
+			let color = stroke.color;
+			write!(
 				self.acc,
-				"stroke='rgb({} {} {} / {:.3})' stroke-width='{width}' ",
+				"stroke='rgb({} {} {} / {:.3})' stroke-width='{}' ",
 				(color.red * 255.) as u32,
 				(color.green * 255.) as u32,
 				(color.blue * 255.) as u32,
-				color.alpha
-			)?,
+				color.alpha,
+				stroke.width,
+			)?;
 
-			None => {}
+			match stroke.cap {
+				LineCap::Butt => {}
+				LineCap::Round => write!(self.acc, "stroke-linecap='round' ")?,
+				LineCap::Square => write!(self.acc, "stroke-linecap='square' ")?,
+			}
+			match stroke.join {
+				LineJoin::Miter => {}
+				LineJoin::Round => write!(self.acc, "stroke-linejoin='round' ")?,
+				LineJoin::Bevel => write!(self.acc, "stroke-linejoin='bevel' ")?,
+			}
+			if stroke.miter_limit != Stroke::DEFAULT_MITER_LIMIT {
+				write!(self.acc, "stroke-miterlimit='{}' ", stroke.miter_limit)?;
+			}
+			if stroke.is_dashed() {
+				let dash = stroke.dash.iter().map(f32::to_string).collect::<Vec<_>>();
+				write!(self.acc, "stroke-dasharray='{}' ", dash.join(","))?;
+				if stroke.dash_offset != 0. {
+					write!(self.acc, "stroke-dashoffset='{}' ", stroke.dash_offset)?;
+				}
+			}
 		}
 
 		Ok(())
@@ -111,51 +212,74 @@ impl SVGExporter {
 
 	#[allow(unused)]
 	fn write_curve(&mut self, curve: CurvePosition) -> Result<(), SVGError> {
-		let mut has_start = false;
-
-		for keypoint in &curve.keypoints {
-			match keypoint {
-				KeypointPosition::Point(p) => {
-					if has_start {
-						write!(self.acc, "L ")?;
-					} else {
-						write!(self.acc, "M ")?;
-						has_start = true;
-					}
-					write!(self.acc, "{} {} ", p.x, p.y)?;
-				}
-				KeypointPosition::Bezier(b) => {
-					if has_start {
-						if let Some(v) = b.start {
-							write!(self.acc, "L {} {} ", v.x, v.y)?;
-						}
-					} else {
-						if let Some(v) = b.start {
-							write!(self.acc, "M {} {} ", v.x, v.y)?;
-							has_start = true;
+		for subpath in curve.iter_subpaths() {
+			let mut has_start = false;
+
+			for keypoint in &subpath.keypoints {
+				match keypoint {
+					KeypointPosition::Point(p) => {
+						if has_start {
+							write!(self.acc, "L ")?;
 						} else {
-							return Err(SVGError::CurveHasNoStartingPoint(curve));
+							write!(self.acc, "M ")?;
+							has_start = true;
 						}
+						write!(self.acc, "{} {} ", p.x, p.y)?;
 					}
+					KeypointPosition::Bezier(Bezier { start, .. })
+					| KeypointPosition::Quadratic(QuadraticBezier { start, .. })
+					| KeypointPosition::ArcTo(EllipticalArc { start, .. }) => {
+						if has_start {
+							if let Some(v) = start {
+								write!(self.acc, "L {} {} ", v.x, v.y)?;
+							}
+						} else {
+							if let Some(v) = start {
+								write!(self.acc, "M {} {} ", v.x, v.y)?;
+								has_start = true;
+							} else {
+								return Err(SVGError::CurveHasNoStartingPoint(curve.clone()));
+							}
+						}
 
-					write!(
-							self.acc,
-							"C {start_ctrl_x} {start_ctrl_y} {end_ctrl_x} {end_ctrl_y} {end_x} {end_y} ",
-							start_ctrl_x = b.start_control.x,
-							start_ctrl_y = b.start_control.y,
-							end_ctrl_x = b.end_control.x,
-							end_ctrl_y = b.end_control.y,
-							end_x = b.end.x,
-							end_y = b.end.y,
-						)?;
+						match keypoint {
+							KeypointPosition::Bezier(b) => write!(
+								self.acc,
+								"C {start_ctrl_x} {start_ctrl_y} {end_ctrl_x} {end_ctrl_y} {end_x} {end_y} ",
+								start_ctrl_x = b.start_control.x,
+								start_ctrl_y = b.start_control.y,
+								end_ctrl_x = b.end_control.x,
+								end_ctrl_y = b.end_control.y,
+								end_x = b.end.x,
+								end_y = b.end.y,
+							)?,
+							KeypointPosition::Quadratic(q) => write!(
+								self.acc,
+								"Q {} {} {} {} ",
+								q.control.x, q.control.y, q.end.x, q.end.y,
+							)?,
+							KeypointPosition::ArcTo(a) => write!(
+								self.acc,
+								"A {} {} {} {} {} {} {} ",
+								a.radii.x,
+								a.radii.y,
+								a.rotation.to_degrees(),
+								a.large_arc as u8,
+								a.sweep as u8,
+								a.end.x,
+								a.end.y,
+							)?,
+							KeypointPosition::Point(_) => unreachable!(),
+						}
+					}
 				}
-			}
 
-			has_start = true;
-		}
+				has_start = true;
+			}
 
-		if curve.closed {
-			write!(self.acc, "Z",)?;
+			if subpath.closed {
+				write!(self.acc, "Z",)?;
+			}
 		}
 
 		Ok(())
@@ -169,26 +293,31 @@ impl SVGExporter {
 		let return_fonts = self
 			.used_font
 			.into_iter()
-			.map(move |(font_ref, font_weight)| {
+			.map(move |(font_ref, properties)| {
 				let font_group = font::get(&font_ref);
-				let (mime, bytes) = match font_group.get(font_weight) {
-					dessin::font::Font::OTF(bytes) => ("font/otf", bytes),
-					dessin::font::Font::TTF(bytes) => ("font/ttf", bytes),
-				};
 				let font_name = &*font_ref;
 
-				let styles = match font_weight {
-					FontWeight::Regular => "font-weight:normal;font-style:normal;",
-					FontWeight::Bold => "font-weight:bold;font-style:normal;",
-					FontWeight::Italic => "font-weight:normal;font-style:italic;",
-					FontWeight::BoldItalic => "font-weight:bold;font-style:italic;",
+				let styles = format!(
+					"font-weight:{};font-style:{};font-stretch:{};",
+					properties.weight.0,
+					css_font_style(properties.style),
+					css_font_stretch(properties.stretch),
+				);
+
+				let src = match font_group.get(properties) {
+					// creates a base 64 ending font using previous imports
+					dessin::font::Font::OTF(bytes) => format!(
+						r#"url("data:font/otf;base64,{}")"#,
+						data_encoding::BASE64.encode(bytes)
+					),
+					dessin::font::Font::TTF(bytes) => format!(
+						r#"url("data:font/ttf;base64,{}")"#,
+						data_encoding::BASE64.encode(bytes)
+					),
+					// Left to the viewer, which looks for it on its system
+					dessin::font::Font::ByName(name) => format!(r#"local("{name}")"#),
 				};
-
-				// creates a base 64 ending font using previous imports
-				let encoded_font_bytes = data_encoding::BASE64.encode(&bytes);
-				format!(
-					r#"@font-face{{font-family:{font_name};src:url("data:{mime};base64,{encoded_font_bytes}");{styles}}}"#
-				)
+				format!(r#"@font-face{{font-family:{font_name};src:{src};{styles}}}"#)
 			})
 			.collect::<String>();
 
@@ -206,8 +335,13 @@ impl Exporter for SVGExporter {
 	const CAN_EXPORT_ELLIPSE: bool = true;
 
 	fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> {
+		let gradient = match &style.fill {
+			Some(fill) => self.write_gradient(fill)?,
+			None => None,
+		};
+
 		write!(self.acc, "<g ")?;
-		self.write_style(style)?;
+		self.write_style(style, gradient)?;
 		write!(self.acc, ">")?;
 
 		Ok(())
@@ -218,6 +352,66 @@ impl Exporter for SVGExporter {
 		Ok(())
 	}
 
+	fn start_clip(&mut self, clip: ClipPosition) -> Result<(), Self::Error> {
+		match clip {
+			ClipPosition::Curve(curve) => {
+				static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+				let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+
+				let clip_rule = match curve.fill_rule {
+					FillRule::NonZero => "nonzero",
+					FillRule::EvenOdd => "evenodd",
+				};
+
+				write!(self.acc, r#"<defs><clipPath id="clip{id}"><path d=""#)?;
+				self.write_curve(curve)?;
+				write!(
+					self.acc,
+					r#"" clip-rule="{clip_rule}"/></clipPath></defs><g clip-path="url(#clip{id})">"#
+				)?;
+			}
+			ClipPosition::Mask {
+				mask,
+				kind,
+				parent_transform,
+			} => {
+				static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
+				let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+
+				let bb = mask.global_bounding_box(&parent_transform).straigthen();
+				let mask_type = match kind {
+					MaskKind::Luminance => "luminance",
+					MaskKind::Alpha => "alpha",
+				};
+
+				write!(
+					self.acc,
+					r#"<defs><mask id="mask{id}" maskUnits="userSpaceOnUse" x="{x}" y="{y}" width="{width}" height="{height}" style="mask-type:{mask_type}">"#,
+					x = bb.left(),
+					y = bb.bottom(),
+					width = bb.width(),
+					height = bb.height(),
+				)?;
+				mask.write_into_exporter(
+					self,
+					&parent_transform,
+					StylePosition {
+						fill: None,
+						stroke: None,
+					},
+				)?;
+				write!(self.acc, r#"</mask></defs><g mask="url(#mask{id})">"#)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn end_clip(&mut self) -> Result<(), Self::Error> {
+		write!(self.acc, "</g>")?;
+		Ok(())
+	}
+
 	fn start_block(&mut self, _metadata: &[(String, String)]) -> Result<(), Self::Error> {
 		if !_metadata.is_empty() {
 			write!(self.acc, "<g ")?;
@@ -308,9 +502,14 @@ impl Exporter for SVGExporter {
 	}
 
 	fn export_curve(&mut self, curve: CurvePosition, _: StylePosition) -> Result<(), Self::Error> {
+		let fill_rule = curve.fill_rule;
+
 		write!(self.acc, r#"<path d=""#)?;
 		self.write_curve(curve)?;
-		write!(self.acc, r#""/>"#)?;
+		match fill_rule {
+			FillRule::NonZero => write!(self.acc, r#""/>"#)?,
+			FillRule::EvenOdd => write!(self.acc, r#"" fill-rule="evenodd"/>"#)?,
+		}
 
 		Ok(())
 	}
@@ -321,10 +520,16 @@ impl Exporter for SVGExporter {
 			text,
 			align,
 			font_weight,
+			font_style,
+			font_stretch,
 			on_curve,
 			font_size,
+			letter_spacing,
+			word_spacing,
+			decoration,
 			reference_start,
 			direction,
+			up: _,
 			font,
 		}: TextPosition,
 		_: StylePosition,
@@ -332,28 +537,55 @@ impl Exporter for SVGExporter {
 		static ID: LazyLock<AtomicU32> = LazyLock::new(|| AtomicU32::new(0));
 		let id = ID.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
 
-		let weight = match font_weight {
-			FontWeight::Bold | FontWeight::BoldItalic => "bold",
-			_ => "normal",
-		};
-		let text_style = match font_weight {
-			FontWeight::Italic | FontWeight::BoldItalic => "italic",
-			_ => "normal",
+		let properties = FontProperties {
+			weight: font_weight,
+			style: font_style,
+			stretch: font_stretch,
 		};
-		let align = match align {
-			TextAlign::Center => "middle",
-			TextAlign::Left => "start",
-			TextAlign::Right => "end",
+		let weight = font_weight.0;
+		let style = css_font_style(font_style);
+		let stretch = css_font_stretch(font_stretch);
+		let (align, start_offset) = match align {
+			TextAlign::Center => ("middle", "50%"),
+			TextAlign::Left | TextAlign::Justify => ("start", "0%"),
+			TextAlign::Right => ("end", "100%"),
 		};
 
-		let text = text.replace("<", "&lt;").replace(">", "&gt;");
 		let font = font.clone().unwrap_or(FontRef::default());
-		self.used_font.insert((font.clone(), font_weight));
+		// Viewers pick fallbacks themselves, only the ones drawing some characters are embedded.
+		// Faces are declared with their own properties, for viewers to match them as dessin does
+		let face = font::properties(Some(&font), properties)?;
+		self.used_font.insert((font.clone(), face));
+		for run in shape_runs(Some(&font), properties, text, font_size) {
+			let face = font::properties(Some(&run.font), properties)?;
+			self.used_font.insert((run.font, face));
+		}
+		let font = std::iter::once(font.clone())
+			.chain(font::fallbacks(&font))
+			.map(|font| font.to_string())
+			.collect::<Vec<_>>()
+			.join(", ");
+
+		let text = text.replace("<", "&lt;").replace(">", "&gt;");
 
 		write!(
 			self.acc,
-			r#"<text font-family="{font}" text-anchor="{align}" font-size="{font_size}px" font-weight="{weight}" text-style="{text_style}" transform=""#,
+			r#"<text font-family="{font}" text-anchor="{align}" font-size="{font_size}px" font-weight="{weight}" font-style="{style}" font-stretch="{stretch}""#,
 		)?;
+		if letter_spacing != 0. {
+			write!(self.acc, r#" letter-spacing="{letter_spacing}px""#)?;
+		}
+		if word_spacing != 0. {
+			write!(self.acc, r#" word-spacing="{word_spacing}px""#)?;
+		}
+		if !decoration.is_none() {
+			write!(
+				self.acc,
+				r#" text-decoration="{}""#,
+				css_text_decoration(decoration)
+			)?;
+		}
+		write!(self.acc, r#" transform=""#)?;
 
 		write!(
 			self.acc,
@@ -374,7 +606,10 @@ impl Exporter for SVGExporter {
 			self.write_curve(curve)?;
 			write!(self.acc, r#""/>"#)?;
 
-			write!(self.acc, r##"<textPath href="#{id}">{text}</textPath>"##)?;
+			write!(
+				self.acc,
+				r##"<textPath href="#{id}" startOffset="{start_offset}">{text}</textPath>"##
+			)?;
 		} else {
 			write!(self.acc, "{text}")?;
 		}
@@ -384,6 +619,48 @@ impl Exporter for SVGExporter {
 	}
 }
 
+/// CSS keyword of a font style
+pub fn css_font_style(style: FontStyle) -> &'static str {
+	match style {
+		FontStyle::Normal => "normal",
+		FontStyle::Italic => "italic",
+		FontStyle::Oblique => "oblique",
+	}
+}
+
+/// CSS value of a text decoration, `none` without line
+pub fn css_text_decoration(decoration: TextDecoration) -> String {
+	let lines = [
+		(decoration.underline, "underline"),
+		(decoration.overline, "overline"),
+		(decoration.strike_through, "line-through"),
+	]
+	.into_iter()
+	.filter_map(|(drawn, line)| drawn.then_some(line))
+	.collect::<Vec<_>>();
+
+	if lines.is_empty() {
+		"none".to_string()
+	} else {
+		lines.join(" ")
+	}
+}
+
+/// CSS keyword of a font stretch
+pub fn css_font_stretch(stretch: FontStretch) -> &'static str {
+	match stretch {
+		FontStretch::UltraCondensed => "ultra-condensed",
+		FontStretch::ExtraCondensed => "extra-condensed",
+		FontStretch::Condensed => "condensed",
+		FontStretch::SemiCondensed => "semi-condensed",
+		FontStretch::Normal => "normal",
+		FontStretch::SemiExpanded => "semi-expanded",
+		FontStretch::Expanded => "expanded",
+		FontStretch::ExtraExpanded => "extra-expanded",
+		FontStretch::UltraExpanded => "ultra-expanded",
+	}
+}
+
 pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<String, SVGError> {
 	let (min_x, min_y, span_x, span_y) = match options.viewport {
 		ViewPort::ManualCentered { width, height } => (-width / 2., -height / 2., width, height),
@@ -418,6 +695,16 @@ pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<Stri
 		}
 	};
 
+	let outlined;
+	let shape = if options.outline_text {
+		let mut shape = shape.clone();
+		shape.outline_texts();
+		outlined = shape;
+		&outlined
+	} else {
+		shape
+	};
+
 	let mut exporter = SVGExporter::new();
 
 	let parent_transform = nalgebra::convert(Scale2::new(1., -1.));
@@ -427,8 +714,8 @@ pub fn to_string_with_options(shape: &Shape, options: SVGOptions) -> Result<Stri
 			&mut exporter,
 			&parent_transform,
 			StylePosition {
-				fill: *fill,
-				stroke: *stroke,
+				fill: fill.clone(),
+				stroke: stroke.clone(),
 			},
 		)? //Needed to be complete
 	} else {
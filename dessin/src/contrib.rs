@@ -12,11 +12,13 @@ auto_import! {
 	arc,
 	circle,
 	fit,
+	hyphenation,
 	layout,
 	line,
 	padding,
 	polygone,
 	rectangle,
+	rich_text,
 	textbox,
 	thick_arc,
 	triangle,
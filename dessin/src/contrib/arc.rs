@@ -85,6 +85,7 @@ impl From<Arc> for Curve {
 				closed: false,
 				keypoints: arcs,
 				local_transform,
+				..Default::default()
 			}
 			.into()
 		}
@@ -60,6 +60,7 @@ impl From<Circle> for Curve {
 			],
 			local_transform,
 			closed: true,
+			..Default::default()
 		}
 	}
 }
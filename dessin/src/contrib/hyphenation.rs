@@ -0,0 +1,141 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+/// Where words may be hyphenated, found with Liang's patterns as TeX does
+///
+/// Patterns are those of the `hyph-*.pat.txt` files of TeX distributions:
+/// letters with the odd digits allowing a hyphen between them, and the even ones forbidding it.
+/// ```
+/// # use dessin::prelude::*;
+/// // Patterns of The TeXbook, enough for a single word
+/// let hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+/// assert_eq!(hyphenator.hyphenate("hyphenation"), [2, 6]);
+///
+/// let hyphenator = hyphenator.with_exceptions("ta-ble");
+/// assert_eq!(hyphenator.hyphenate("Table"), [2]);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Hyphenator {
+	/// Levels between the letters of each pattern, one more than its letters
+	patterns: HashMap<String, Vec<u8>>,
+	/// Hyphenation points of exceptional words
+	exceptions: HashMap<String, Vec<usize>>,
+	/// Letters of the longest pattern
+	longest: usize,
+	/// Letters kept before the first hyphen
+	pub left_min: usize,
+	/// Letters kept after the last hyphen
+	pub right_min: usize,
+}
+impl Hyphenator {
+	/// Hyphenator of the patterns of `patterns`, separated by whitespaces
+	///
+	/// Comments, from `%` to the end of the line, are ignored.
+	/// At least 2 letters are kept before a hyphen and 3 after it, as for English.
+	pub fn new(patterns: &str) -> Self {
+		let mut hyphenator = Hyphenator {
+			left_min: 2,
+			right_min: 3,
+			..Default::default()
+		};
+
+		for pattern in words(patterns) {
+			let mut letters = String::new();
+			let mut levels = vec![0];
+			for c in pattern.chars() {
+				match c.to_digit(10) {
+					Some(level) => *levels.last_mut().unwrap() = level as u8,
+					None => {
+						letters.extend(c.to_lowercase());
+						levels.push(0);
+					}
+				}
+			}
+
+			hyphenator.longest = hyphenator.longest.max(levels.len() - 1);
+			hyphenator.patterns.insert(letters, levels);
+		}
+
+		hyphenator
+	}
+
+	/// Hyphenator of the patterns of a file, see [`Hyphenator::new`]
+	pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		Ok(Hyphenator::new(&fs::read_to_string(path)?))
+	}
+
+	/// Hyphenate the words of `exceptions` where they have a `-`, whatever the patterns
+	///
+	/// Exceptions are separated by whitespaces, as `hy-phen-ation ta-ble`.
+	pub fn with_exceptions(mut self, exceptions: &str) -> Self {
+		for exception in words(exceptions) {
+			let mut word = String::new();
+			let mut points = vec![];
+			for c in exception.chars() {
+				if c == '-' {
+					points.push(word.chars().count());
+				} else {
+					word.extend(c.to_lowercase());
+				}
+			}
+			self.exceptions.insert(word, points);
+		}
+		self
+	}
+
+	/// Byte indexes of `word` where a hyphen may be inserted
+	///
+	/// Words with other characters than letters are never hyphenated.
+	pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+		if !word.chars().all(char::is_alphabetic) {
+			return vec![];
+		}
+
+		// One lowercase letter per letter of the word, to find its bytes back
+		let letters = word
+			.chars()
+			.map(|c| c.to_lowercase().next().unwrap_or(c))
+			.collect::<Vec<_>>();
+		let count = letters.len();
+
+		let points = match self.exceptions.get(&letters.iter().collect::<String>()) {
+			Some(points) => points.clone(),
+			None => {
+				// Levels before each letter of the word, between dots
+				let dotted = std::iter::once('.')
+					.chain(letters.iter().copied())
+					.chain(std::iter::once('.'))
+					.collect::<Vec<_>>();
+				let mut levels = vec![0u8; dotted.len() + 1];
+				for start in 0..dotted.len() {
+					let end = dotted.len().min(start + self.longest);
+					let mut letters = String::new();
+					for c in &dotted[start..end] {
+						letters.push(*c);
+						if let Some(pattern) = self.patterns.get(&letters) {
+							for (idx, level) in pattern.iter().enumerate() {
+								levels[start + idx] = levels[start + idx].max(*level);
+							}
+						}
+					}
+				}
+
+				// Before letter `idx`, after the leading dot
+				(1..count).filter(|idx| levels[idx + 1] % 2 == 1).collect()
+			}
+		};
+
+		let bytes = word.char_indices().map(|(idx, _)| idx).collect::<Vec<_>>();
+		points
+			.into_iter()
+			.filter(|idx| *idx >= self.left_min && *idx + self.right_min <= count)
+			.filter_map(|idx| bytes.get(idx).copied())
+			.collect()
+	}
+}
+
+/// Words of a pattern file, without comments
+fn words(text: &str) -> impl Iterator<Item = &str> {
+	text.lines()
+		.map(|line| line.split('%').next().unwrap_or_default())
+		.flat_map(str::split_whitespace)
+}
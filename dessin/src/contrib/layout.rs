@@ -127,13 +127,17 @@ mod tests {
 	use assert_float_eq::*;
 	use nalgebra::Point2;
 
+	fn text_height(font_size: f32) -> f32 {
+		Text::default().with_font_size(font_size).metrics().height()
+	}
+
 	#[test]
 	fn one_element() {
 		let layout = dessin!(VerticalLayout(of = Text::default().with_font_size(10.)) > ());
 
 		let bb: BoundingBox<UnParticular> = layout.local_bounding_box();
 
-		assert_float_absolute_eq!(bb.height(), 10., 0.0001);
+		assert_float_absolute_eq!(bb.height(), text_height(10.), 0.0001);
 	}
 
 	#[test]
@@ -147,7 +151,7 @@ mod tests {
 
 		let bb: BoundingBox<UnParticular> = layout.local_bounding_box();
 
-		assert_float_absolute_eq!(bb.height(), 20., 0.0001);
+		assert_float_absolute_eq!(bb.height(), 2. * text_height(10.), 0.0001);
 	}
 
 	#[test]
@@ -161,7 +165,7 @@ mod tests {
 
 		let bb: BoundingBox<UnParticular> = layout.local_bounding_box();
 
-		assert_float_absolute_eq!(bb.height(), 24., 0.0001);
+		assert_float_absolute_eq!(bb.height(), 2. * text_height(10.) + 4., 0.0001);
 	}
 
 	#[test]
@@ -176,7 +180,7 @@ mod tests {
 
 		let bb: BoundingBox<UnParticular> = layout.local_bounding_box();
 
-		assert_float_absolute_eq!(bb.height(), 24., 0.0001);
+		assert_float_absolute_eq!(bb.height(), 2. * text_height(10.) + 4., 0.0001);
 	}
 
 	#[test]
@@ -279,7 +283,7 @@ mod tests {
 				*Text(
 					text = "Notes",
 					fill = Srgb::<f32>::from_format(named::BLACK).into_linear(),
-					font_weight = FontWeight::Bold,
+					font_weight = FontWeight::BOLD,
 					font_size = 3.6,
 					align = TextAlign::Left,
 				),
@@ -294,7 +298,13 @@ mod tests {
 		let height = bounding_bb.height();
 		let min_y = bounding_bb.bottom_left().y;
 
-		assert_float_absolute_eq!(height, 3. * gap + 3. * 3.6 + 3.6, 0.1);
+		// Lines of the text box are also spaced by the line gap of their font
+		let metrics = Text::default().with_font_size(3.6).metrics();
+		assert_float_absolute_eq!(
+			height,
+			3. * gap + 2. * metrics.line_height() + 2. * metrics.height(),
+			0.1
+		);
 		assert_float_absolute_eq!(min_y, -148.5 + 5., 0.1);
 	}
 }
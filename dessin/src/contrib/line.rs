@@ -40,6 +40,7 @@ impl From<Line> for Curve {
 			local_transform,
 			closed: false,
 			keypoints: vec![Keypoint::Point(from), Keypoint::Point(to)],
+			..Default::default()
 		}
 	}
 }
@@ -37,21 +37,25 @@ impl<const N: u32> Polygon<N> {
 	const STEP: f32 = TAU / N as f32;
 }
 
-impl<const N: u32> From<Polygon<N>> for Shape {
+impl<const N: u32> From<Polygon<N>> for Curve {
 	fn from(Polygon { local_transform }: Polygon<N>) -> Self {
 		let step = Polygon::<N>::STEP;
 
-		dessin!(
-			Curve(
-				extend = (0..N).map(|p| Point2::from([
-					(p as f32 * step).cos(),
-					(p as f32 * step).sin()
-				])
-				.into()),
-				closed,
-				transform = local_transform,
-			) > ()
-		)
+		dessin!(Curve(
+			extend = (0..N).map(|p| Point2::from([
+				(p as f32 * step).cos(),
+				(p as f32 * step).sin()
+			])
+			.into()),
+			closed,
+			transform = local_transform,
+		))
+	}
+}
+
+impl<const N: u32> From<Polygon<N>> for Shape {
+	fn from(polygon: Polygon<N>) -> Self {
+		Curve::from(polygon).into()
 	}
 }
 
@@ -0,0 +1,424 @@
+use crate::{font::FontRef, prelude::*};
+use nalgebra::Transform2;
+
+/// Run of text sharing the same style, part of a [`RichText`] or a [`TextBox`]
+///
+/// Unset values are taken from the [`RichText`] or the [`TextBox`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TextSpan {
+	/// The text
+	pub text: String,
+	/// Font
+	pub font: Option<FontRef>,
+	/// Font weight
+	pub font_weight: Option<FontWeight>,
+	/// Font style
+	pub font_style: Option<FontStyle>,
+	/// Font stretch
+	pub font_stretch: Option<FontStretch>,
+	/// Font size
+	pub font_size: Option<f32>,
+	/// Lines drawn along the span
+	pub decoration: Option<TextDecoration>,
+	/// Fill of the text, replacing the style around the [`RichText`]
+	pub fill: Option<Fill>,
+	/// Vertical offset from the baseline, upward
+	pub baseline_shift: f32,
+}
+impl TextSpan {
+	/// Span of `text`, styled as its parent
+	#[inline]
+	pub fn new<S: Into<String>>(text: S) -> Self {
+		TextSpan {
+			text: text.into(),
+			..Default::default()
+		}
+	}
+
+	/// Change the font
+	#[inline]
+	pub fn with_font<F: Into<FontRef>>(mut self, font: F) -> Self {
+		self.font = Some(font.into());
+		self
+	}
+
+	/// Change the font weight
+	#[inline]
+	pub fn with_font_weight(mut self, font_weight: FontWeight) -> Self {
+		self.font_weight = Some(font_weight);
+		self
+	}
+
+	/// Change the font style
+	#[inline]
+	pub fn with_font_style(mut self, font_style: FontStyle) -> Self {
+		self.font_style = Some(font_style);
+		self
+	}
+
+	/// Change the font stretch
+	#[inline]
+	pub fn with_font_stretch(mut self, font_stretch: FontStretch) -> Self {
+		self.font_stretch = Some(font_stretch);
+		self
+	}
+
+	/// Change the font size
+	#[inline]
+	pub fn with_font_size(mut self, font_size: f32) -> Self {
+		self.font_size = Some(font_size);
+		self
+	}
+
+	/// Change the lines drawn along the span
+	#[inline]
+	pub fn with_decoration(mut self, decoration: TextDecoration) -> Self {
+		self.decoration = Some(decoration);
+		self
+	}
+
+	/// Change the fill
+	#[inline]
+	pub fn with_fill<F: Into<Fill>>(mut self, fill: F) -> Self {
+		self.fill = Some(fill.into());
+		self
+	}
+
+	/// Move the span up, or down with a negative shift, as for superscripts and subscripts
+	#[inline]
+	pub fn with_baseline_shift(mut self, baseline_shift: f32) -> Self {
+		self.baseline_shift = baseline_shift;
+		self
+	}
+}
+impl From<&str> for TextSpan {
+	fn from(text: &str) -> Self {
+		TextSpan::new(text)
+	}
+}
+impl From<String> for TextSpan {
+	fn from(text: String) -> Self {
+		TextSpan::new(text)
+	}
+}
+
+/// Style of a span, resolved against its parent
+pub(crate) struct SpanStyle {
+	pub font: Option<FontRef>,
+	pub properties: FontProperties,
+	pub font_size: f32,
+	pub letter_spacing: f32,
+	pub word_spacing: f32,
+	pub decoration: TextDecoration,
+}
+impl SpanStyle {
+	pub fn of(span: &TextSpan, parent: &SpanStyle) -> Self {
+		SpanStyle {
+			font: span.font.clone().or_else(|| parent.font.clone()),
+			properties: FontProperties {
+				weight: span.font_weight.unwrap_or(parent.properties.weight),
+				style: span.font_style.unwrap_or(parent.properties.style),
+				stretch: span.font_stretch.unwrap_or(parent.properties.stretch),
+			},
+			font_size: span.font_size.unwrap_or(parent.font_size),
+			letter_spacing: parent.letter_spacing,
+			word_spacing: parent.word_spacing,
+			decoration: span.decoration.unwrap_or(parent.decoration),
+		}
+	}
+
+	/// Vertical metrics of the font of the span
+	pub fn metrics(&self) -> FontMetrics {
+		crate::font::face(self.font.as_ref(), self.properties).metrics(self.font_size)
+	}
+
+	/// Length of `text` drawn with this style
+	pub fn width(&self, text: &str) -> f32 {
+		size_of(
+			self.font.as_ref(),
+			self.properties,
+			text,
+			self.font_size,
+			self.letter_spacing,
+			self.word_spacing,
+		)
+	}
+
+	/// Number of glyph clusters of `text` drawn with this style, each followed by a letter spacing
+	pub fn clusters(&self, text: &str) -> usize {
+		let mut clusters = shape_runs(self.font.as_ref(), self.properties, text, self.font_size)
+			.into_iter()
+			.flat_map(|run| run.glyphs.into_iter().map(|glyph| glyph.cluster))
+			.collect::<Vec<_>>();
+		clusters.dedup();
+		clusters.len()
+	}
+}
+
+/// Single line of text made of [spans][TextSpan] with their own style
+///
+/// Spans are drawn one after the other, on a common baseline.
+/// ```
+/// # use dessin::prelude::*;
+/// let sentence = dessin!(RichText(
+/// 	span = "One ",
+/// 	span = TextSpan::new("bold").with_font_weight(FontWeight::BOLD),
+/// 	span = " word",
+/// 	font_size = 12.,
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Shape)]
+pub struct RichText {
+	/// [`ShapeOp`]
+	#[local_transform]
+	pub local_transform: Transform2<f32>,
+
+	/// Spans, from left to right
+	pub spans: Vec<TextSpan>,
+
+	/// Horizontal align
+	pub align: TextAlign,
+
+	/// Vertical align
+	pub vertical_align: TextVerticalAlign,
+
+	/// Font weight of spans without one
+	pub font_weight: FontWeight,
+
+	/// Font style of spans without one
+	pub font_style: FontStyle,
+
+	/// Font stretch of spans without one
+	pub font_stretch: FontStretch,
+
+	/// Font size of spans without one
+	pub font_size: f32,
+
+	/// Extra space after each character
+	pub letter_spacing: f32,
+
+	/// Extra space after each space, on top of the letter spacing
+	pub word_spacing: f32,
+
+	/// Lines drawn along spans without their own
+	pub decoration: TextDecoration,
+
+	/// Font of spans without one
+	#[shape(into_some)]
+	pub font: Option<FontRef>,
+}
+impl Default for RichText {
+	fn default() -> Self {
+		RichText {
+			local_transform: Default::default(),
+			spans: Default::default(),
+			align: Default::default(),
+			vertical_align: Default::default(),
+			font_weight: Default::default(),
+			font_style: Default::default(),
+			font_stretch: Default::default(),
+			font_size: 10.,
+			letter_spacing: 0.,
+			word_spacing: 0.,
+			decoration: Default::default(),
+			font: Default::default(),
+		}
+	}
+}
+impl RichText {
+	/// Add a span at the end of the line
+	#[inline]
+	pub fn span<S: Into<TextSpan>>(&mut self, span: S) -> &mut Self {
+		self.spans.push(span.into());
+		self
+	}
+
+	/// Add a span at the end of the line
+	#[inline]
+	pub fn with_span<S: Into<TextSpan>>(mut self, span: S) -> Self {
+		self.span(span);
+		self
+	}
+
+	/// Add some spans at the end of the line
+	#[inline]
+	pub fn extend_spans<T: IntoIterator<Item = TextSpan>>(&mut self, spans: T) -> &mut Self {
+		self.spans.extend(spans);
+		self
+	}
+
+	/// Change the font, if any
+	#[inline]
+	pub fn maybe_font<F: Into<FontRef>>(&mut self, font: Option<F>) -> &mut Self {
+		self.font = font.map(Into::into);
+		self
+	}
+}
+
+impl From<RichText> for Shape {
+	fn from(
+		RichText {
+			local_transform,
+			spans,
+			align,
+			vertical_align,
+			font_weight,
+			font_style,
+			font_stretch,
+			font_size,
+			letter_spacing,
+			word_spacing,
+			decoration,
+			font,
+		}: RichText,
+	) -> Self {
+		let parent = SpanStyle {
+			font,
+			properties: FontProperties {
+				weight: font_weight,
+				style: font_style,
+				stretch: font_stretch,
+			},
+			font_size,
+			letter_spacing,
+			word_spacing,
+			decoration,
+		};
+		let runs = spans
+			.into_iter()
+			.filter(|span| !span.text.is_empty())
+			.map(|span| {
+				let style = SpanStyle::of(&span, &parent);
+				let width = style.width(&span.text);
+				let metrics = style.metrics();
+				(span, style, width, metrics)
+			})
+			.collect::<Vec<_>>();
+
+		let width = runs.iter().map(|(_, _, width, _)| width).sum::<f32>();
+		// The line goes from the lowest to the highest glyphs of its fonts
+		let line = runs
+			.iter()
+			.fold(FontMetrics::default(), |line, (_, _, _, metrics)| {
+				FontMetrics {
+					ascent: line.ascent.max(metrics.ascent),
+					descent: line.descent.min(metrics.descent),
+					line_gap: line.line_gap.max(metrics.line_gap),
+				}
+			});
+
+		// Same reference as a single `Text` as high as the line
+		let baseline = line.baseline(vertical_align);
+		let mut x = match align {
+			TextAlign::Left | TextAlign::Justify => 0.,
+			TextAlign::Center => -width / 2.,
+			TextAlign::Right => -width,
+		};
+
+		dessin!(
+			for (span, style, width, _) in (runs) {
+				let text = dessin!(
+					Text(
+						text = span.text,
+						font_weight = style.properties.weight,
+						font_style = style.properties.style,
+						font_stretch = style.properties.stretch,
+						font_size = style.font_size,
+						letter_spacing = style.letter_spacing,
+						word_spacing = style.word_spacing,
+						decoration = style.decoration,
+						vertical_align = TextVerticalAlign::Baseline,
+						maybe_font = style.font,
+						translate = [x, baseline + span.baseline_shift],
+					) > ()
+				);
+				x += width;
+
+				match span.fill {
+					Some(fill) => Shape::Style {
+						fill: Some(fill),
+						stroke: None,
+						shape: Box::new(text),
+					},
+					None => text,
+				}
+			} > (transform = local_transform)
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nalgebra::{Point2, Transform2};
+	use palette::Srgba;
+
+	#[test]
+	fn runs_follow_each_other() {
+		let shape = dessin!(
+			RichText(
+				span = "Hello ",
+				span = TextSpan::new("world")
+					.with_font_weight(FontWeight::BOLD)
+					.with_fill(Srgba::new(1., 0., 0., 1.)),
+				span = TextSpan::new("2")
+					.with_font_size(5.)
+					.with_baseline_shift(4.),
+				font_size = 10.,
+				vertical_align = TextVerticalAlign::Baseline,
+			) > ()
+		);
+
+		let Shape::Group(Group { shapes, .. }) = &shape else {
+			panic!("Group expected");
+		};
+		assert_eq!(shapes.len(), 3);
+
+		let Shape::Style {
+			fill: Some(_),
+			shape: bold,
+			..
+		} = &shapes[1]
+		else {
+			panic!("Styled span expected");
+		};
+		let (Shape::Text(hello), Shape::Text(bold), Shape::Text(exponent)) =
+			(&shapes[0], bold.as_ref(), &shapes[2])
+		else {
+			panic!("Texts expected");
+		};
+
+		let start = |text: &Text| text.position(&Transform2::default()).reference_start;
+		let hello_width = hello.local_bounding_box().width();
+		assert_eq!(start(hello), Point2::new(0., 0.));
+		assert!((start(bold) - Point2::new(hello_width, 0.)).magnitude() < 10e-6);
+		assert!(start(exponent).x > start(bold).x);
+		assert_eq!(start(exponent).y, 4.);
+		assert_eq!(bold.font_weight, FontWeight::BOLD);
+		assert_eq!(exponent.font_size, 5.);
+
+		let bb = shape.local_bounding_box().straigthen();
+		assert!((bb.left() - 0.).abs() < 10e-6);
+		// The exponent goes above the line
+		let ascent = hello.metrics().ascent;
+		assert!((bb.top() - (4. + ascent / 2.).max(ascent)).abs() < 10e-6);
+		assert!((bb.bottom() - hello.metrics().descent).abs() < 10e-6);
+	}
+
+	#[test]
+	fn aligned() {
+		let line = |align| {
+			dessin!(RichText(span = "Hello ", span = "world", { align }) > ())
+				.local_bounding_box()
+				.straigthen()
+		};
+
+		let left = line(TextAlign::Left);
+		let center = line(TextAlign::Center);
+		let right = line(TextAlign::Right);
+
+		assert!((left.left() - 0.).abs() < 10e-6);
+		assert!(center.center().x.abs() < 10e-6);
+		assert!(right.right().abs() < 10e-6);
+	}
+}
@@ -1,8 +1,16 @@
 use crate::{font::FontRef, prelude::*};
-use fontdue::{Font, FontSettings};
 use nalgebra::Transform2;
+use std::{ops::Range, sync::Arc};
+use unicode_linebreak::{BreakClass, BreakOpportunity};
+
+/// Inserted where a word is hyphenated
+const HYPHEN: &str = "-";
 
 /// Box of text, with auto wrapping text if width is too large
+///
+/// Lines break where Unicode allows it, after spaces and hyphens or between ideographs,
+/// and at each new line. Spaces are kept as they are, except at the end of lines.
+/// Words too long for a line are hyphenated by [`TextBox::hyphenator`] if any, or broken anywhere.
 #[derive(Debug, Clone, PartialEq, Shape)]
 pub struct TextBox {
 	/// [`ShapeOp`]
@@ -12,7 +20,7 @@ pub struct TextBox {
 	/// Font size
 	pub font_size: f32,
 
-	/// Spacing between each line
+	/// Spacing between each line, on top of the line gap of their fonts
 	pub line_spacing: f32,
 
 	/// Horizontal align
@@ -28,6 +36,21 @@ pub struct TextBox {
 	/// Font weight
 	pub font_weight: FontWeight,
 
+	/// Font style
+	pub font_style: FontStyle,
+
+	/// Font stretch
+	pub font_stretch: FontStretch,
+
+	/// Extra space after each character
+	pub letter_spacing: f32,
+
+	/// Extra space after each space, on top of the letter spacing
+	pub word_spacing: f32,
+
+	/// Lines drawn along the text
+	pub decoration: TextDecoration,
+
 	/// Dimension on the x-axis
 	pub width: f32,
 
@@ -38,6 +61,13 @@ pub struct TextBox {
 	/// Font
 	#[shape(into_some)]
 	pub font: Option<FontRef>,
+
+	/// Styled spans, following [`TextBox::text`]
+	pub spans: Vec<TextSpan>,
+
+	/// Hyphenation of words too long for the end of their line, none by default
+	#[shape(into_some)]
+	pub hyphenator: Option<Arc<Hyphenator>>,
 }
 impl Default for TextBox {
 	fn default() -> Self {
@@ -49,9 +79,16 @@ impl Default for TextBox {
 			vertical_align: TextVerticalAlign::Top,
 			text: Default::default(),
 			font_weight: Default::default(),
+			font_style: Default::default(),
+			font_stretch: Default::default(),
+			letter_spacing: 0.,
+			word_spacing: 0.,
+			decoration: Default::default(),
 			width: f32::MAX,
 			height: Default::default(),
 			font: Default::default(),
+			spans: Default::default(),
+			hyphenator: None,
 		}
 	}
 }
@@ -68,6 +105,246 @@ impl TextBox {
 		self.no_height();
 		self
 	}
+
+	/// Add a span at the end of the text
+	#[inline]
+	pub fn span<S: Into<TextSpan>>(&mut self, span: S) -> &mut Self {
+		self.spans.push(span.into());
+		self
+	}
+
+	/// Add a span at the end of the text
+	#[inline]
+	pub fn with_span<S: Into<TextSpan>>(mut self, span: S) -> Self {
+		self.span(span);
+		self
+	}
+
+	/// Add some spans at the end of the text
+	#[inline]
+	pub fn extend_spans<T: IntoIterator<Item = TextSpan>>(&mut self, spans: T) -> &mut Self {
+		self.spans.extend(spans);
+		self
+	}
+}
+
+/// Line of a [`TextBox`], as bytes of its whole text
+struct Line {
+	range: Range<usize>,
+	/// The line ends inside a word, with a hyphen
+	hyphen: bool,
+	/// The line ends its paragraph, and isn't justified
+	last: bool,
+}
+
+/// Text of the spans of a [`TextBox`] as a whole, for lines to break across spans
+struct Paragraphs<'a> {
+	text: String,
+	/// Bytes of each span in `text`
+	ranges: Vec<Range<usize>>,
+	styles: &'a [SpanStyle],
+	metrics: Vec<FontMetrics>,
+}
+impl<'a> Paragraphs<'a> {
+	fn new(spans: &[TextSpan], styles: &'a [SpanStyle]) -> Self {
+		let mut text = String::new();
+		let ranges = spans
+			.iter()
+			.map(|span| {
+				let start = text.len();
+				text.push_str(&span.text);
+				start..text.len()
+			})
+			.collect();
+
+		Paragraphs {
+			text,
+			ranges,
+			styles,
+			metrics: styles.iter().map(SpanStyle::metrics).collect(),
+		}
+	}
+
+	/// Pieces of `range` in each span
+	fn pieces(&self, range: Range<usize>) -> impl Iterator<Item = (usize, Range<usize>)> + '_ {
+		self.ranges
+			.iter()
+			.enumerate()
+			.filter_map(move |(span, bytes)| {
+				let piece = bytes.start.max(range.start)..bytes.end.min(range.end);
+				(!piece.is_empty()).then_some((span, piece))
+			})
+	}
+
+	/// Length of `range`, followed by a hyphen if any
+	fn width(&self, range: Range<usize>, hyphen: bool) -> f32 {
+		let mut last = None;
+		let width = self
+			.pieces(range)
+			.map(|(span, piece)| {
+				last = Some(span);
+				self.styles[span].width(&self.text[piece])
+			})
+			.sum::<f32>();
+
+		match last {
+			Some(span) if hyphen => width + self.styles[span].width(HYPHEN),
+			_ => width,
+		}
+	}
+
+	/// Number of glyph clusters of `range`, followed by a hyphen if any
+	fn clusters(&self, range: Range<usize>, hyphen: bool) -> usize {
+		let clusters = self
+			.pieces(range)
+			.map(|(span, piece)| self.styles[span].clusters(&self.text[piece]))
+			.sum::<usize>();
+		clusters + usize::from(hyphen)
+	}
+
+	/// From the lowest to the highest glyphs of the fonts of `line`, and the largest of their line gaps
+	fn metrics(&self, line: &Line) -> FontMetrics {
+		self.pieces(line.range.clone())
+			.fold(FontMetrics::default(), |metrics, (span, _)| FontMetrics {
+				ascent: metrics.ascent.max(self.metrics[span].ascent),
+				descent: metrics.descent.min(self.metrics[span].descent),
+				line_gap: metrics.line_gap.max(self.metrics[span].line_gap),
+			})
+	}
+
+	/// Text of `line`, with its hyphen
+	fn line_text(&self, line: &Line) -> String {
+		let mut text = self.text[line.range.clone()].to_string();
+		if line.hyphen {
+			text.push_str(HYPHEN);
+		}
+		text
+	}
+
+	/// Spans of `line`, styled as `spans`
+	fn line_spans(&self, line: &Line, spans: &[TextSpan]) -> Vec<TextSpan> {
+		let mut line_spans = self
+			.pieces(line.range.clone())
+			.map(|(span, piece)| TextSpan {
+				text: self.text[piece].to_string(),
+				..spans[span].clone()
+			})
+			.collect::<Vec<_>>();
+		if let (Some(last), true) = (line_spans.last_mut(), line.hyphen) {
+			last.text.push_str(HYPHEN);
+		}
+		line_spans
+	}
+
+	/// Break the text into lines of at most `width`, where Unicode allows it
+	///
+	/// Words longer than a line are hyphenated if possible, or broken between any characters.
+	fn wrap(&self, width: f32, hyphenator: Option<&Hyphenator>) -> Vec<Line> {
+		let mut lines = vec![];
+		// Start of the current line, and end of its text without its trailing spaces
+		let (mut start, mut end) = (0, 0);
+		let mut previous = 0;
+		for (idx, opportunity) in unicode_linebreak::linebreaks(&self.text) {
+			// Spaces and new lines at the end of the line hang past its end
+			let content = previous + self.text[previous..idx].trim_end().len();
+
+			if end > start && self.width(start..content, false) > width {
+				match self.hyphenate(start, previous..content, width, hyphenator) {
+					Some(split) => {
+						lines.push(Line {
+							range: start..split,
+							hyphen: true,
+							last: false,
+						});
+						start = split;
+					}
+					None => {
+						lines.push(Line {
+							range: start..end,
+							hyphen: false,
+							last: false,
+						});
+						start = previous;
+					}
+				}
+			}
+
+			while content > start && self.width(start..content, false) > width {
+				let (split, hyphen) = match self.hyphenate(start, start..content, width, hyphenator)
+				{
+					Some(split) => (split, true),
+					None => (self.force_break(start..content, width), false),
+				};
+				lines.push(Line {
+					range: start..split,
+					hyphen,
+					last: false,
+				});
+				start = split;
+			}
+
+			end = content.max(start);
+			previous = idx;
+			if opportunity == BreakOpportunity::Mandatory {
+				lines.push(Line {
+					range: start..end,
+					hyphen: false,
+					last: true,
+				});
+				(start, end) = (idx, idx);
+			}
+		}
+
+		lines.retain(|line| !line.range.is_empty());
+		lines
+	}
+
+	/// Last hyphenation point of the letters of `word` keeping the line from `start` in `width`
+	fn hyphenate(
+		&self,
+		start: usize,
+		word: Range<usize>,
+		width: f32,
+		hyphenator: Option<&Hyphenator>,
+	) -> Option<usize> {
+		let hyphenator = hyphenator?;
+
+		// Punctuation around the letters stays as it is
+		let text = &self.text[word.clone()];
+		let letters_start = text.find(char::is_alphabetic)?;
+		let letters_end = text[letters_start..]
+			.find(|c: char| !c.is_alphabetic())
+			.map_or(text.len(), |end| letters_start + end);
+
+		hyphenator
+			.hyphenate(&text[letters_start..letters_end])
+			.into_iter()
+			.rev()
+			.map(|point| word.start + letters_start + point)
+			.find(|split| self.width(start..*split, true) <= width)
+	}
+
+	/// End of the longest start of `range` fitting in `width`, of at least a character
+	fn force_break(&self, range: Range<usize>, width: f32) -> usize {
+		// Marks and joiners stay with the character before them
+		let breakable = |end: &usize| {
+			!self.text[*end..].starts_with(|c: char| {
+				matches!(
+					unicode_linebreak::break_property(c as u32),
+					BreakClass::CombiningMark | BreakClass::ZeroWidthJoiner
+				)
+			})
+		};
+		let mut ends = self.text[range.clone()]
+			.char_indices()
+			.map(|(idx, c)| range.start + idx + c.len_utf8())
+			.filter(breakable);
+
+		let first = ends.next().unwrap_or(range.end);
+		ends.take_while(|end| self.width(range.start..*end, false) <= width)
+			.last()
+			.unwrap_or(first)
+	}
 }
 
 impl From<TextBox> for Shape {
@@ -82,89 +359,178 @@ impl From<TextBox> for Shape {
 			align,
 			vertical_align,
 			font_weight,
+			font_style,
+			font_stretch,
+			letter_spacing,
+			word_spacing,
+			decoration,
 			font,
+			spans,
+			hyphenator,
 		}: TextBox,
 	) -> Self {
-		let font_ref = font.clone();
-
-		let fonts = crate::font::get_or_default(font.as_ref());
-		let raw_font = match fonts.get(FontWeight::Regular) {
-			crate::font::Font::OTF(bytes) => bytes,
-			crate::font::Font::TTF(bytes) => bytes,
+		let style = SpanStyle {
+			font: font.clone(),
+			properties: FontProperties {
+				weight: font_weight,
+				style: font_style,
+				stretch: font_stretch,
+			},
+			font_size,
+			letter_spacing,
+			word_spacing,
+			decoration,
+		};
+		// Lines of a plain text are single `Text`s
+		let rich = !spans.is_empty();
+		let spans = (!text.is_empty())
+			.then(|| TextSpan::new(text))
+			.into_iter()
+			.chain(spans)
+			.collect::<Vec<_>>();
+		let styles = spans
+			.iter()
+			.map(|span| SpanStyle::of(span, &style))
+			.collect::<Vec<_>>();
+
+		let paragraphs = Paragraphs::new(&spans, &styles);
+		let height = height.unwrap_or(f32::MAX);
+		let mut used_height = 0.;
+		let lines = paragraphs
+			.wrap(width, hyphenator.as_deref())
+			.into_iter()
+			.map(|line| {
+				let metrics = paragraphs.metrics(&line);
+				(line, metrics)
+			})
+			.take_while(|(_, metrics)| {
+				let fits = used_height + metrics.height() <= height;
+				used_height += metrics.line_height() + line_spacing;
+				fits
+			});
+
+		let vertical_align = match vertical_align {
+			TextVerticalAlign::Bottom => TextVerticalAlign::Top,
+			TextVerticalAlign::Center => TextVerticalAlign::Center,
+			TextVerticalAlign::Top => TextVerticalAlign::Bottom,
+			TextVerticalAlign::Baseline => TextVerticalAlign::Baseline,
+		};
+		let line_align = match align {
+			TextAlign::Justify => TextAlign::Left,
+			align => align,
 		};
 
-		let font = Font::from_bytes(raw_font.as_slice(), FontSettings::default()).unwrap();
-
-		let mut lines = vec![];
-		let mut height = height.unwrap_or(f32::MAX);
-
-		for line in text.lines() {
-			let mut len = 0.;
-			let mut acc = String::new();
-
-			if height - font_size < 0. {
-				break;
-			}
-
-			for word in line.split_whitespace() {
-				if word.is_empty() {
-					continue;
-				}
-
-				let word_size = size_of(&font, word, font_size);
+		// Lines are stacked from the top, each followed by the line gap of its fonts
+		let mut y = 0.;
+		dessin!(
+			for (line, metrics) in (lines) {
+				let mut shape: Shape = {
+					// Justified lines stretch their spaces, or their characters without any
+					let text = paragraphs.line_text(&line);
+					let (mut letter_spacing, mut word_spacing) = (letter_spacing, word_spacing);
+					let extra = width - paragraphs.width(line.range.clone(), line.hyphen);
+					if align == TextAlign::Justify && !line.last && extra > 0. {
+						// Letter spacing also follows the last glyph: the gaps between glyphs take it back
+						let clusters = paragraphs.clusters(line.range.clone(), line.hyphen);
+						match text.matches([' ', '\u{a0}']).count() {
+							0 if clusters > 1 => {
+								letter_spacing += (extra + letter_spacing) / (clusters - 1) as f32
+							}
+							0 => {}
+							spaces => word_spacing += extra / spaces as f32,
+						}
+					}
 
-				if len + word_size > width {
-					lines.push(std::mem::take(&mut acc));
+					if rich {
+						dessin!(RichText(
+							spans = paragraphs.line_spans(&line, &spans),
+							align = line_align,
+							{ vertical_align },
+							{ font_weight },
+							{ font_style },
+							{ font_stretch },
+							{ font_size },
+							{ letter_spacing },
+							{ word_spacing },
+							{ decoration },
+							maybe_font = font.clone(),
+						))
+						.into()
+					} else {
+						dessin!(Text(
+							{ text },
+							align = line_align,
+							{ vertical_align },
+							{ font_weight },
+							{ font_style },
+							{ font_stretch },
+							{ font_size },
+							{ letter_spacing },
+							{ word_spacing },
+							{ decoration },
+							maybe_font = font.clone(),
+						))
+						.into()
+					}
+				};
 
-					acc = word.to_owned();
-					len = word_size;
+				let bb = shape.local_bounding_box().into_straight();
+				shape.translate([0., -bb.top() - y]);
+				y += bb.height() + metrics.line_gap + line_spacing;
 
-					height -= font_size + line_spacing;
-				} else {
-					len += word_size;
+				shape
+			} > (transform = local_transform)
+		)
+	}
+}
 
-					if !acc.is_empty() {
-						acc.push(' ');
-					}
+#[test]
+fn rich_text_wraps() {
+	use assert_float_eq::*;
 
-					acc.push_str(word)
-				}
-			}
+	let mut shape: Shape = dessin!(
+		TextBox(
+			text = "it should ",
+			span = TextSpan::new("work,").with_font_weight(FontWeight::BOLD),
+			span = " famous last word",
+			font_size = 5.,
+			width = 40.,
+			line_spacing = 2.,
+		) > ()
+	);
 
-			if !acc.is_empty() {
-				lines.push(acc)
-			}
-		}
+	let lines = shape.get_or_mutate_as_group().shapes.clone();
+	assert_eq!(lines.len(), 2);
 
-		let (vertical_align, _) = match vertical_align {
-			TextVerticalAlign::Bottom => (TextVerticalAlign::Top, 1.),
-			TextVerticalAlign::Center => (TextVerticalAlign::Center, -1.),
-			TextVerticalAlign::Top => (TextVerticalAlign::Bottom, -1.),
+	let spans = |line: &Shape| -> Vec<String> {
+		let Shape::Group(Group { shapes, .. }) = line else {
+			unreachable!()
 		};
+		shapes
+			.iter()
+			.map(|v| match v {
+				Shape::Text(text) => text.text.clone(),
+				_ => unreachable!(),
+			})
+			.collect()
+	};
+	assert_eq!(spans(&lines[0]), vec!["it should ", "work,"]);
+	assert_eq!(spans(&lines[1]), vec!["famous last word"]);
 
-		dessin!(
-			VerticalLayout(
-				extend = lines.into_iter().map(|text| {
-					dessin!(Text(
-						{ text },
-						{ align },
-						{ vertical_align },
-						{ font_weight },
-						{ font_size },
-						maybe_font = font_ref.clone(),
-					))
-					.into()
-				}),
-				gap = line_spacing,
-				transform = local_transform,
-			) > ()
-		)
-	}
+	let bb = shape.local_bounding_box();
+	// Lines are spaced by the line gap of the font, and the line spacing
+	let metrics = Text::default().with_font_size(5.).metrics();
+	assert_float_absolute_eq!(
+		bb.height(),
+		metrics.height() + metrics.line_height() + 2.,
+		0.001
+	);
 }
 
 #[test]
 fn one_line() {
 	use assert_float_eq::*;
+	use palette::{named, Srgb};
 
 	let text = "it should work, famous last word";
 
@@ -179,12 +545,14 @@ fn one_line() {
 	);
 
 	let bb = shape.local_bounding_box();
-	assert_float_absolute_eq!(bb.height(), 5., 0.001);
+	let line_height = Text::default().with_font_size(5.).metrics().height();
+	assert_float_absolute_eq!(bb.height(), line_height, 0.001);
 }
 
 #[test]
 fn two_lines() {
 	use assert_float_eq::*;
+	use palette::{named, Srgb};
 
 	let text = "it should work\nfamous last word";
 
@@ -198,8 +566,13 @@ fn two_lines() {
 	.into();
 
 	let bb = shape.local_bounding_box();
+	let metrics = Text::default().with_font_size(5.).metrics();
 
-	assert_float_absolute_eq!(bb.height(), 12., 0.0001);
+	assert_float_absolute_eq!(
+		bb.height(),
+		metrics.height() + metrics.line_height() + 2.,
+		0.0001
+	);
 }
 
 #[test]
@@ -221,12 +594,14 @@ fn should_break() {
 	let shapes = shape.get_or_mutate_as_group().shapes.clone();
 	assert_eq!(shapes.len(), 2);
 
+	let metrics = Text::default().with_font_size(5.).metrics();
+
 	{
 		let Shape::Text(text) = shapes[0].clone() else {
 			unreachable!()
 		};
 
-		let lt = convert::<_, Transform2<f32>>(Translation2::new(0., (5. / 2.) * -1.));
+		let lt = convert::<_, Transform2<f32>>(Translation2::new(0., -metrics.height()));
 
 		assert_eq!(
 			text,
@@ -236,7 +611,12 @@ fn should_break() {
 				align: TextAlign::Left,
 				vertical_align: Default::default(),
 				font_weight: Default::default(),
+				font_style: Default::default(),
+				font_stretch: Default::default(),
 				on_curve: None,
+				letter_spacing: 0.,
+				word_spacing: 0.,
+				decoration: Default::default(),
 				font_size: 5.,
 				font: None
 			}
@@ -248,7 +628,10 @@ fn should_break() {
 			unreachable!()
 		};
 
-		let lt = convert::<_, Transform2<f32>>(Translation2::new(0., ((5. / 2.) + 5.) * -1.));
+		let lt = convert::<_, Transform2<f32>>(Translation2::new(
+			0.,
+			-metrics.height() - metrics.line_height(),
+		));
 
 		assert_eq!(
 			text,
@@ -258,7 +641,12 @@ fn should_break() {
 				align: TextAlign::Left,
 				vertical_align: Default::default(),
 				font_weight: Default::default(),
+				font_style: Default::default(),
+				font_stretch: Default::default(),
 				on_curve: None,
+				letter_spacing: 0.,
+				word_spacing: 0.,
+				decoration: Default::default(),
 				font_size: 5.,
 				font: None
 			}
@@ -266,5 +654,127 @@ fn should_break() {
 	}
 
 	let bb = shape.local_bounding_box();
-	assert_float_absolute_eq!(bb.height(), 10., 0.001);
+	assert_float_absolute_eq!(bb.height(), metrics.height() + metrics.line_height(), 0.001);
+}
+
+#[cfg(test)]
+fn line_texts(shape: Shape) -> Vec<Text> {
+	let Shape::Group(Group { shapes, .. }) = shape else {
+		unreachable!()
+	};
+	shapes
+		.into_iter()
+		.map(|line| match line {
+			Shape::Text(text) => text,
+			_ => unreachable!(),
+		})
+		.collect()
+}
+
+#[test]
+fn justified() {
+	use assert_float_eq::*;
+
+	let text = "it  should work, famous last word\nend";
+	let shape: Shape = dessin!(
+		TextBox(
+			{ text },
+			font_size = 5.,
+			width = 40.,
+			align = TextAlign::Justify,
+		) > ()
+	);
+
+	let lines = line_texts(shape);
+	let texts = lines
+		.iter()
+		.map(|line| line.text.as_str())
+		.collect::<Vec<_>>();
+	// Spaces are kept inside lines, and hang at their end
+	assert_eq!(texts, ["it  should work,", "famous last word", "end"]);
+
+	let width = |text: &Text| text.local_bounding_box().straigthen().width();
+	assert_float_absolute_eq!(width(&lines[0]), 40., 0.001);
+	assert!(lines[0].word_spacing > 0.);
+	assert_eq!(lines[0].align, TextAlign::Left);
+	// Last lines of paragraphs aren't stretched
+	assert!(width(&lines[1]) < 40.);
+	assert_eq!(lines[2].word_spacing, 0.);
+}
+
+#[test]
+fn justified_letters() {
+	use assert_float_eq::*;
+
+	let shape: Shape = dessin!(
+		TextBox(
+			text = "Justification\nend",
+			font_size = 5.,
+			width = 20.,
+			align = TextAlign::Justify,
+		) > ()
+	);
+
+	let lines = line_texts(shape);
+	assert!(lines.len() > 2);
+	let line = &lines[0];
+	assert!(!line.text.contains(' '));
+	assert!(line.letter_spacing > 0.);
+
+	// The last glyph ends at the right of the box, less its own side bearing
+	let ink = |text: &Text| text.to_outlines().local_bounding_box().straigthen().right();
+	let natural = Text::default()
+		.with_font_size(5.)
+		.with_text(line.text.clone());
+	let bearing = natural.local_bounding_box().straigthen().width() - ink(&natural);
+	assert_float_absolute_eq!(ink(line), 20. - bearing, 0.001);
+}
+
+#[test]
+fn overlong_words() {
+	let text = "https://example.com/a/very/long/path/to/somewhere";
+	let shape: Shape = dessin!(TextBox({ text }, font_size = 5., width = 30.) > ());
+
+	let lines = line_texts(shape);
+	assert!(lines.len() > 1);
+	assert_eq!(
+		lines
+			.iter()
+			.map(|line| line.text.as_str())
+			.collect::<String>(),
+		text
+	);
+	for line in &lines {
+		assert!(line.local_bounding_box().straigthen().width() <= 30.);
+	}
+
+	// Ideographs break between each other
+	let shape: Shape =
+		dessin!(TextBox(text = "日本語の文章です", font_size = 5., width = 12.) > ());
+	assert!(line_texts(shape).len() > 1);
+}
+
+#[test]
+fn hyphenated() {
+	let hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+	let width = Text::default()
+		.with_font_size(5.)
+		.with_text("the hyphen-")
+		.local_bounding_box()
+		.straigthen()
+		.width();
+
+	let text_box = TextBox::default()
+		.with_text("the hyphenation")
+		.with_font_size(5.)
+		.with_width(width + 0.01);
+	let ragged = line_texts(text_box.clone().into());
+	assert_eq!(ragged[0].text, "the");
+
+	let hyphenated = line_texts(text_box.with_hyphenator(hyphenator).into());
+	let texts = hyphenated
+		.iter()
+		.map(|line| line.text.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(texts, ["the hyphen-", "ation"]);
 }
@@ -28,9 +28,9 @@ where
 	/// # fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 	/// # fn end_style(&mut self) -> Result<(), Self::Error> { Ok(()) }
 	/// # fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> { Ok(()) }
-	/// # fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> { Ok(()) }
+	/// # fn export_ellipse(&mut self, ellipse: EllipsePosition, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 	/// # fn export_curve(&mut self, curve: CurvePosition, StylePosition {fill,stroke,} : StylePosition,) -> Result<(), Self::Error> { Ok(()) }
-	/// # fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> { Ok(()) }
+	/// # fn export_text(&mut self, text: TextPosition, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 	/// }
 	///
 	/// fn export_shape(shape: Shape) {
@@ -62,7 +62,7 @@ where
 		&self,
 		exporter: &mut E,
 		parent_transform: &Transform2<f32>,
-		StylePosition { fill, stroke }: StylePosition,
+		style_position: StylePosition,
 	) -> Result<(), <E as Exporter>::Error> {
 		match self {
 			Shape::Group(Group {
@@ -77,7 +77,7 @@ where
 					shape.write_into_exporter(
 						exporter,
 						&parent_transform,
-						StylePosition { fill, stroke },
+						style_position.clone(),
 					)?;
 				}
 
@@ -91,47 +91,52 @@ where
 				shape,
 			} => {
 				let style = StylePosition {
-					fill: fill.clone(),
+					fill: fill.clone().map(|v| *parent_transform * v),
 					stroke: stroke.clone().map(|v| *parent_transform * v),
 				};
 
-				exporter.start_style(style)?;
+				exporter.start_style(style.clone())?;
 				shape.write_into_exporter(exporter, parent_transform, style)?;
 				exporter.end_style()
 			}
+			Shape::Clip { clip, shape } => {
+				exporter.start_clip(ClipPosition::Curve(clip.position(parent_transform)))?;
+				shape.write_into_exporter(exporter, parent_transform, style_position)?;
+				exporter.end_clip()
+			}
+			Shape::Mask { mask, kind, shape } => {
+				exporter.start_clip(ClipPosition::Mask {
+					mask: mask.as_ref().clone(),
+					kind: *kind,
+					parent_transform: *parent_transform,
+				})?;
+				shape.write_into_exporter(exporter, parent_transform, style_position)?;
+				exporter.end_clip()
+			}
 			Shape::Image(image) => exporter.export_image(image.position(parent_transform)),
 			Shape::Ellipse(ellipse) => {
 				if E::CAN_EXPORT_ELLIPSE {
-					exporter.export_ellipse(
-						ellipse.position(parent_transform),
-						StylePosition { fill, stroke },
-					)
+					exporter.export_ellipse(ellipse.position(parent_transform), style_position)
 				} else {
 					exporter.export_curve(
 						ellipse.as_curve().position(parent_transform),
-						StylePosition { fill, stroke },
+						style_position,
 					)
 				}
 			}
-			Shape::Curve(curve) => exporter.export_curve(
-				curve.position(parent_transform),
-				StylePosition { fill, stroke },
-			),
-			Shape::Text(text) => exporter.export_text(
-				text.position(parent_transform),
-				StylePosition { fill, stroke },
-			),
+			Shape::Curve(curve) => {
+				exporter.export_curve(curve.position(parent_transform), style_position)
+			}
+			Shape::Text(text) => {
+				exporter.export_text(text.position(parent_transform), style_position)
+			}
 			Shape::Dynamic {
 				local_transform,
 				shaper,
 			} => {
 				let shape = shaper();
 				let parent_transform = parent_transform * local_transform;
-				shape.write_into_exporter(
-					exporter,
-					&parent_transform,
-					StylePosition { fill, stroke },
-				)
+				shape.write_into_exporter(exporter, &parent_transform, style_position)
 			}
 		}
 	}
@@ -164,9 +169,9 @@ where
 /// # fn start_style(&mut self, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 /// # fn end_style(&mut self) -> Result<(), Self::Error> { Ok(()) }
 /// # fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error> { Ok(()) }
-/// # fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> { Ok(()) }
+/// # fn export_ellipse(&mut self, ellipse: EllipsePosition, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 /// # fn export_curve(&mut self, curve: CurvePosition, StylePosition {fill,stroke,}: StylePosition,) -> Result<(), Self::Error> { Ok(()) }
-/// # fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> { Ok(()) }
+/// # fn export_text(&mut self, text: TextPosition, style: StylePosition) -> Result<(), Self::Error> { Ok(()) }
 /// }
 ///
 /// trait ToSVG {
@@ -213,6 +218,20 @@ pub trait Exporter {
 		Ok(())
 	}
 
+	/// Enter a scope of clip
+	///
+	/// All [`Shape`][crate::shapes::Shape] between [`start_clip`][Exporter::start_clip] and [`end_clip`][Exporter::end_clip] must only be visible inside the clip.
+	/// Clips can be nested, in which case only the intersection of all clips is visible.
+	///
+	/// By default, the clip is ignored and shapes are exported as is.
+	fn start_clip(&mut self, _clip: ClipPosition) -> Result<(), Self::Error> {
+		Ok(())
+	}
+	/// End a scope of clip
+	fn end_clip(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
 	/// Export an [`Image`][crate::shapes::image::Image]
 	fn export_image(&mut self, image: ImagePosition) -> Result<(), Self::Error>;
 	/// Export an [`Ellipse`][crate::shapes::ellipse::Ellipse]
@@ -0,0 +1,18 @@
+//! Geometric operations on [curves][crate::shapes::Curve]
+//!
+//! Curves are approximated by straight lines where needed, deviating at most by a tolerance,
+//! expressed in drawing units.
+
+mod boolean;
+mod flatten;
+mod hit;
+mod measure;
+mod outline;
+
+pub use boolean::*;
+pub use flatten::*;
+pub use hit::*;
+pub use outline::*;
+
+/// Tolerance used when none is given, in drawing units
+pub const DEFAULT_TOLERANCE: f32 = 0.01;
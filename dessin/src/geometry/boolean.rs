@@ -0,0 +1,636 @@
+use super::DEFAULT_TOLERANCE;
+use crate::shapes::{Curve, FillRule, Keypoint};
+use nalgebra::{Point2, Transform2, Vector2};
+use std::collections::{BTreeMap, HashMap};
+
+/// Boolean operation between two curves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BooleanOp {
+	/// Everything inside either curve
+	Union,
+	/// Only what is inside both curves
+	Intersection,
+	/// What is inside the first curve but not inside the second one
+	Difference,
+	/// What is inside exactly one of the curves
+	Xor,
+}
+impl BooleanOp {
+	fn apply(self, a: bool, b: bool) -> bool {
+		match self {
+			BooleanOp::Union => a || b,
+			BooleanOp::Intersection => a && b,
+			BooleanOp::Difference => a && !b,
+			BooleanOp::Xor => a != b,
+		}
+	}
+}
+
+type P = Point2<f64>;
+
+fn cross(a: Vector2<f64>, b: Vector2<f64>) -> f64 {
+	a.x * b.y - a.y * b.x
+}
+
+/// Closed rings of a curve, with its fill rule
+struct Operand {
+	rings: Vec<Vec<P>>,
+	fill_rule: FillRule,
+}
+impl Operand {
+	fn new(curve: &Curve, tolerance: f32) -> Self {
+		let position = curve.position(&Transform2::identity());
+
+		let rings = position
+			.flatten(tolerance)
+			.into_iter()
+			.map(|polyline| {
+				let mut ring: Vec<P> = polyline.points.iter().map(|p| p.cast()).collect();
+				ring.dedup();
+				while ring.len() > 1 && ring.first() == ring.last() {
+					ring.pop();
+				}
+				ring
+			})
+			.filter(|ring| ring.len() >= 3)
+			.collect();
+
+		Operand {
+			rings,
+			fill_rule: position.fill_rule,
+		}
+	}
+
+	fn edges(&self) -> impl Iterator<Item = (P, P)> + '_ {
+		self.rings
+			.iter()
+			.flat_map(|ring| ring.iter().zip(ring.iter().cycle().skip(1)))
+			.map(|(a, b)| (*a, *b))
+	}
+
+	fn is_inside(&self, winding: i32) -> bool {
+		match self.fill_rule {
+			FillRule::NonZero => winding != 0,
+			FillRule::EvenOdd => winding % 2 != 0,
+		}
+	}
+}
+
+/// Winding contribution of the edge from `a` to `b` around a point right above `p`,
+/// or right below it when `below`
+fn crossing(a: P, b: P, p: P, below: bool) -> i32 {
+	let under = |y: f64| if below { y < p.y } else { y <= p.y };
+	if under(a.y) {
+		if !under(b.y) && cross(b - a, p - a) > 0. {
+			return 1;
+		}
+	} else if under(b.y) && cross(b - a, p - a) < 0. {
+		return -1;
+	}
+	0
+}
+
+/// Vertices merged when closer than `epsilon`
+struct Vertices {
+	epsilon: f64,
+	points: Vec<P>,
+	grid: HashMap<(i64, i64), Vec<usize>>,
+}
+impl Vertices {
+	fn cell(&self, p: P) -> (i64, i64) {
+		(
+			(p.x / self.epsilon).floor() as i64,
+			(p.y / self.epsilon).floor() as i64,
+		)
+	}
+
+	fn insert(&mut self, p: P) -> usize {
+		let (x, y) = self.cell(p);
+		for dx in -1..=1 {
+			for dy in -1..=1 {
+				if let Some(found) = self.grid.get(&(x + dx, y + dy)).and_then(|ids| {
+					ids.iter()
+						.find(|id| (self.points[**id] - p).magnitude() <= self.epsilon)
+				}) {
+					return *found;
+				}
+			}
+		}
+
+		let id = self.points.len();
+		self.points.push(p);
+		self.grid.entry((x, y)).or_default().push(id);
+		id
+	}
+}
+
+/// Record where segments `i` and `j` cross each other
+fn intersect(segments: &[(P, P)], splits: &mut [Vec<f64>], i: usize, j: usize, epsilon: f64) {
+	let (p, p2) = segments[i];
+	let (q, q2) = segments[j];
+	let r = p2 - p;
+	let s = q2 - q;
+
+	if p.x.min(p2.x) > q.x.max(q2.x) + epsilon
+		|| q.x.min(q2.x) > p.x.max(p2.x) + epsilon
+		|| p.y.min(p2.y) > q.y.max(q2.y) + epsilon
+		|| q.y.min(q2.y) > p.y.max(p2.y) + epsilon
+	{
+		return;
+	}
+
+	// Ends of a segment touching the other one, collinear overlaps included
+	let mut touch = |(a, a2): (P, P), idx: usize, (b, b2): (P, P)| {
+		let d = a2 - a;
+		for v in [b, b2] {
+			let t = (v - a).dot(&d) / d.magnitude_squared();
+			if t > 0. && t < 1. && (a + d * t - v).magnitude() <= epsilon {
+				splits[idx].push(t);
+			}
+		}
+	};
+	touch(segments[i], i, segments[j]);
+	touch(segments[j], j, segments[i]);
+
+	let denom = cross(r, s);
+	if denom.abs() <= 1e-12 * r.magnitude() * s.magnitude() {
+		return;
+	}
+
+	let t = cross(q - p, s) / denom;
+	let u = cross(q - p, r) / denom;
+	let t_eps = epsilon / r.magnitude();
+	let u_eps = epsilon / s.magnitude();
+	if (-t_eps..=1. + t_eps).contains(&t) && (-u_eps..=1. + u_eps).contains(&u) {
+		splits[i].push(t.clamp(0., 1.));
+		splits[j].push(u.clamp(0., 1.));
+	}
+}
+
+/// Pieces of segments grouped by horizontal bands, to find those a horizontal ray may cross
+struct Bands {
+	min: f64,
+	height: f64,
+	bands: Vec<Vec<usize>>,
+}
+impl Bands {
+	fn new(pieces: &[((usize, usize), [i32; 2])], points: &[P]) -> Self {
+		let (min, max) = points
+			.iter()
+			.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+				(min.min(p.y), max.max(p.y))
+			});
+		// As high as pieces are on average, so that each piece is in a band or two
+		let count = pieces.len().max(1) as f64;
+		let average = pieces
+			.iter()
+			.map(|((u, v), _)| (points[*u].y - points[*v].y).abs())
+			.sum::<f64>()
+			/ count;
+		let height = average.max((max - min) / count).max(f64::MIN_POSITIVE);
+		let count = ((max - min) / height) as usize + 1;
+
+		let mut bands = Bands {
+			min,
+			height,
+			bands: vec![vec![]; count],
+		};
+		for (idx, ((u, v), _)) in pieces.iter().enumerate() {
+			let (low, high) = (
+				points[*u].y.min(points[*v].y),
+				points[*u].y.max(points[*v].y),
+			);
+			for band in bands.band(low)..=bands.band(high) {
+				bands.bands[band].push(idx);
+			}
+		}
+		bands
+	}
+
+	fn band(&self, y: f64) -> usize {
+		(((y - self.min) / self.height).max(0.) as usize).min(self.bands.len() - 1)
+	}
+
+	/// Pieces spanning the height `y`, and maybe a few more
+	fn around(&self, y: f64) -> &[usize] {
+		&self.bands[self.band(y)]
+	}
+}
+
+/// Where each segment is crossed by the others, sweeping from left to right
+fn splits(segments: &[(P, P)], epsilon: f64) -> Vec<Vec<f64>> {
+	let left = |i: usize| segments[i].0.x.min(segments[i].1.x);
+	let mut by_left = (0..segments.len()).collect::<Vec<_>>();
+	by_left.sort_by(|i, j| left(*i).total_cmp(&left(*j)));
+
+	let mut splits = vec![vec![]; segments.len()];
+	for (k, i) in by_left.iter().enumerate() {
+		let right = segments[*i].0.x.max(segments[*i].1.x) + epsilon;
+		for j in by_left[k + 1..].iter().take_while(|j| left(**j) <= right) {
+			intersect(segments, &mut splits, *i, *j, epsilon);
+		}
+	}
+	splits
+}
+
+/// Remove points lying on a straight line between their neighbours
+fn simplify(ring: &mut Vec<P>, epsilon: f64) {
+	let straight = |prev: P, p: P, next: P| {
+		let (a, b) = (p - prev, next - p);
+		cross(a, b).abs() <= epsilon * (a.magnitude() + b.magnitude()) && a.dot(&b) >= 0.
+	};
+
+	let mut kept: Vec<P> = Vec::with_capacity(ring.len());
+	for p in ring.drain(..) {
+		kept.push(p);
+		while kept.len() >= 3 && straight(kept[kept.len() - 3], kept[kept.len() - 2], p) {
+			kept.remove(kept.len() - 2);
+		}
+	}
+
+	// Where the ring closes
+	while kept.len() >= 3 {
+		let n = kept.len();
+		if straight(kept[n - 2], kept[n - 1], kept[0]) {
+			kept.pop();
+		} else if straight(kept[n - 1], kept[0], kept[1]) {
+			kept.remove(0);
+		} else {
+			break;
+		}
+	}
+
+	*ring = kept;
+}
+
+/// Boolean operation between two curves, approximated by straight lines deviating at most by `tolerance`
+///
+/// Both curves are considered closed, and their subpaths combined according to their [`FillRule`].
+/// The resulting curve is made of straight lines only, with holes and disjoint parts as subpaths.
+pub fn boolean(a: &Curve, b: &Curve, op: BooleanOp, tolerance: f32) -> Curve {
+	let a = Operand::new(a, tolerance);
+	let b = Operand::new(b, tolerance);
+
+	let segments: Vec<(P, P)> = a.edges().chain(b.edges()).collect();
+	if segments.is_empty() {
+		return Curve::default();
+	}
+
+	let (min, max) = segments.iter().flat_map(|(p, q)| [p, q]).fold(
+		(
+			Vector2::repeat(f64::INFINITY),
+			Vector2::repeat(f64::NEG_INFINITY),
+		),
+		|(min, max), p| (min.inf(&p.coords), max.sup(&p.coords)),
+	);
+	// A few steps of `f32` precision, as points computed in `f32` differ by as much
+	let epsilon = (max - min).max().max(f64::MIN_POSITIVE) * 1e-6;
+
+	let mut vertices = Vertices {
+		epsilon,
+		points: vec![],
+		grid: HashMap::new(),
+	};
+	// Pieces of the curves between their vertices, with how many times each curve runs along them
+	// from their first vertex to their second one
+	let a_segments = a.edges().count();
+	let mut pieces = segments
+		.iter()
+		.enumerate()
+		.map(|(idx, (p, q))| {
+			let counts = if idx < a_segments { [1, 0] } else { [0, 1] };
+			((vertices.insert(*p), vertices.insert(*q)), counts)
+		})
+		.collect::<Vec<_>>();
+
+	// Split pieces where they cross each other. Merging close vertices moves pieces a little,
+	// which may make them cross others: split again until nothing changes, which takes a round
+	// or two, bounded in case rounding never settles.
+	for _ in 0..16 {
+		let segments = pieces
+			.iter()
+			.map(|((u, v), _)| (vertices.points[*u], vertices.points[*v]))
+			.collect::<Vec<_>>();
+
+		let mut split: BTreeMap<(usize, usize), [i32; 2]> = BTreeMap::new();
+		for ((((u, v), counts), (p, q)), mut splits) in
+			pieces.iter().zip(&segments).zip(splits(&segments, epsilon))
+		{
+			splits.sort_by(f64::total_cmp);
+
+			let ids = std::iter::once(*u)
+				.chain(splits.iter().map(|t| vertices.insert(p + (q - p) * *t)))
+				.chain([*v])
+				.collect::<Vec<_>>();
+			for w in ids.windows(2) {
+				if w[0] != w[1] {
+					let sign = if w[0] < w[1] { 1 } else { -1 };
+					let entry = split.entry((w[0].min(w[1]), w[0].max(w[1]))).or_default();
+					for (total, count) in entry.iter_mut().zip(counts) {
+						*total += sign * count;
+					}
+				}
+			}
+		}
+
+		// Curves running both ways along a piece cancel out
+		let split = split
+			.into_iter()
+			.filter(|(_, counts)| *counts != [0, 0])
+			.collect::<Vec<_>>();
+		if split == pieces {
+			break;
+		}
+		pieces = split;
+	}
+	let bands = Bands::new(&pieces, &vertices.points);
+
+	// Keep pieces between the inside and the outside of the result, with the inside on their left.
+	// Windings are counted on the pieces themselves, so that both sides of a vertex agree on what is
+	// inside and every kept piece has a next one.
+	let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+	let mut directed = vec![];
+	for (idx, ((u, v), counts)) in pieces.iter().enumerate() {
+		let (pu, pv) = (vertices.points[*u], vertices.points[*v]);
+		let middle = pu + (pv - pu) / 2.;
+
+		// Windings right next to the middle of the piece, on its right: below it when it goes right
+		let below = pv.x > pu.x;
+		let mut right = [0; 2];
+		for other in bands.around(middle.y).iter().filter(|other| **other != idx) {
+			let ((ou, ov), other_counts) = pieces[*other];
+			let crossing = crossing(vertices.points[ou], vertices.points[ov], middle, below);
+			for (winding, count) in right.iter_mut().zip(other_counts) {
+				*winding += crossing * count;
+			}
+		}
+		// The piece itself is crossed by the ray from a point on its right only when it goes down
+		if pv.y < pu.y {
+			for (winding, count) in right.iter_mut().zip(counts) {
+				*winding -= count;
+			}
+		}
+		let left = [right[0] + counts[0], right[1] + counts[1]];
+
+		let inside = |[wa, wb]: [i32; 2]| op.apply(a.is_inside(wa), b.is_inside(wb));
+		let (left, right) = (inside(left), inside(right));
+		if left == right {
+			continue;
+		}
+
+		let edge = if left { (*u, *v) } else { (*v, *u) };
+		outgoing.entry(edge.0).or_default().push(directed.len());
+		directed.push(edge);
+	}
+
+	// Follow edges into rings, turning as much as possible to keep parts apart
+	let mut used = vec![false; directed.len()];
+	let mut rings = vec![];
+	for first in 0..directed.len() {
+		if used[first] {
+			continue;
+		}
+
+		let start = directed[first].0;
+		let mut ring = vec![vertices.points[start]];
+		let mut current = first;
+		loop {
+			used[current] = true;
+			let (from, to) = directed[current];
+			if to == start {
+				break;
+			}
+			ring.push(vertices.points[to]);
+
+			let back = vertices.points[from] - vertices.points[to];
+			let back = back.y.atan2(back.x);
+			let next = outgoing.get(&to).and_then(|candidates| {
+				candidates
+					.iter()
+					.filter(|e| !used[**e])
+					.min_by(|e1, e2| {
+						let clockwise = |e: usize| {
+							let d = vertices.points[directed[e].1] - vertices.points[to];
+							(back - d.y.atan2(d.x)).rem_euclid(std::f64::consts::TAU)
+						};
+						clockwise(**e1).total_cmp(&clockwise(**e2))
+					})
+					.copied()
+			});
+
+			// Pieces are balanced around each vertex, a ring can only stop where it started. Should
+			// rounding leave it open anyway, it is still kept, closed by a straight line.
+			match next {
+				Some(next) => current = next,
+				None => break,
+			}
+		}
+
+		simplify(&mut ring, epsilon);
+		if ring.len() >= 3 {
+			rings.push(ring);
+		}
+	}
+
+	let mut rings = rings.into_iter().map(|ring| Curve {
+		keypoints: ring
+			.into_iter()
+			.map(|p| Keypoint::Point(p.cast()))
+			.collect(),
+		closed: true,
+		..Default::default()
+	});
+
+	let Some(mut curve) = rings.next() else {
+		return Curve::default();
+	};
+	curve.subpaths.extend(rings);
+	curve
+}
+
+impl Curve {
+	/// Boolean operation with another curve, see [`boolean`]
+	#[inline]
+	pub fn boolean(&self, other: &Curve, op: BooleanOp) -> Curve {
+		boolean(self, other, op, DEFAULT_TOLERANCE)
+	}
+
+	/// Everything inside this curve or the other one
+	#[inline]
+	pub fn union(&self, other: &Curve) -> Curve {
+		self.boolean(other, BooleanOp::Union)
+	}
+
+	/// Only what is inside both this curve and the other one
+	#[inline]
+	pub fn intersection(&self, other: &Curve) -> Curve {
+		self.boolean(other, BooleanOp::Intersection)
+	}
+
+	/// What is inside this curve but not inside the other one
+	#[inline]
+	pub fn difference(&self, other: &Curve) -> Curve {
+		self.boolean(other, BooleanOp::Difference)
+	}
+
+	/// What is inside exactly one of this curve and the other one
+	#[inline]
+	pub fn xor(&self, other: &Curve) -> Curve {
+		self.boolean(other, BooleanOp::Xor)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{geometry, prelude::*};
+	use assert_float_eq::*;
+	use nalgebra::{Point2, Rotation2, Vector2};
+	use std::f32::consts::PI;
+
+	fn area(curve: &Curve) -> f32 {
+		curve
+			.position(&Default::default())
+			.flatten(0.001)
+			.iter()
+			.map(|polyline| {
+				let p = &polyline.points;
+				p.iter()
+					.zip(p.iter().cycle().skip(1))
+					.map(|(a, b)| a.x * b.y - a.y * b.x)
+					.sum::<f32>() / 2.
+			})
+			.sum()
+	}
+
+	fn square(x: f32, y: f32) -> Curve {
+		dessin!(Rectangle(width = 2., height = 2., translate = [x, y])).as_curve()
+	}
+
+	#[test]
+	fn squares() {
+		let a = square(0., 0.);
+		let b = square(1., 1.);
+
+		assert_f32_near!(area(&a.union(&b)), 7., 8);
+		assert_f32_near!(area(&a.intersection(&b)), 1., 8);
+		assert_f32_near!(area(&a.difference(&b)), 3., 8);
+		assert_f32_near!(area(&a.xor(&b)), 6., 8);
+
+		assert_eq!(a.union(&b).keypoints.len(), 8);
+		assert!(a.intersection(&square(5., 5.)).keypoints.is_empty());
+	}
+
+	#[test]
+	fn hole_and_shared_edges() {
+		let outer = dessin!(Rectangle(width = 4., height = 4.)).as_curve();
+		let inner = square(0., 0.);
+
+		let ring = outer.difference(&inner);
+		assert_eq!(ring.subpaths.len(), 1);
+		assert_f32_near!(area(&ring), 12., 8);
+
+		let a = square(0., 0.);
+		let b = square(2., 0.);
+		let union = a.union(&b);
+		assert!(union.subpaths.is_empty());
+		assert_eq!(union.keypoints.len(), 4);
+		assert_f32_near!(area(&union), 8., 8);
+	}
+
+	#[test]
+	fn circles() {
+		let a = dessin!(Circle(radius = 1.)).as_curve();
+		let b = dessin!(Circle(radius = 1., translate = [1., 0.])).as_curve();
+
+		// Lens of two unit circles one radius apart
+		let lens = 2. * std::f32::consts::PI / 3. - 3f32.sqrt() / 2.;
+		let intersection = geometry::boolean(&a, &b, BooleanOp::Intersection, 0.0001);
+		assert!((area(&intersection) - lens).abs() < 0.001);
+		assert!((intersection.local_bounding_box().width() - 1.).abs() < 0.001);
+
+		// Coarser with the default tolerance, but still within a few percents
+		assert!((area(&a.intersection(&b)) - lens).abs() < lens * 0.03);
+	}
+
+	/// Closed curve through `points`, with `more` polygons as subpaths
+	fn polygons(points: &[Point2<f32>], more: &[&[Point2<f32>]]) -> Curve {
+		let polygon = |points: &[Point2<f32>]| Curve {
+			keypoints: points.iter().copied().map(Keypoint::Point).collect(),
+			closed: true,
+			..Default::default()
+		};
+
+		let mut curve = polygon(points);
+		curve
+			.subpaths
+			.extend(more.iter().map(|points| polygon(points)));
+		curve
+	}
+
+	#[test]
+	fn many_overlaps() {
+		// Strips crossing in a star: each pair overlaps on a rhombus, all three on a hexagon
+		let strips = [0., PI / 3., 2. * PI / 3.].map(|angle| {
+			let rotation = Rotation2::new(angle);
+			[(-2., -0.5), (2., -0.5), (2., 0.5), (-2., 0.5)]
+				.map(|(x, y)| rotation * Point2::new(x, y))
+		});
+		let star = 12. - 3. / (PI / 3.).sin() + 3f32.sqrt() / 2.;
+
+		let union = polygons(&strips[0], &[&strips[1], &strips[2]]).union(&Curve::default());
+		assert!(union.subpaths.is_empty());
+		assert_eq!(union.keypoints.len(), 18);
+		assert!((area(&union) - star).abs() < 0.0001);
+
+		let chained = polygons(&strips[0], &[])
+			.union(&polygons(&strips[1], &[]))
+			.union(&polygons(&strips[2], &[]));
+		assert!((area(&chained) - star).abs() < 0.0001);
+
+		// Staircase, the first and the last squares only touching at a corner
+		let stairs = [0., 1., 2.].map(|c| {
+			[(c, c), (c + 2., c), (c + 2., c + 2.), (c, c + 2.)].map(|(x, y)| Point2::new(x, y))
+		});
+		let stairs = polygons(&stairs[0], &[&stairs[1], &stairs[2]]);
+		assert_f32_near!(area(&stairs.union(&Curve::default())), 10., 8);
+		assert_f32_near!(area(&stairs.intersection(&square(1.5, 1.5))), 3.5, 8);
+	}
+
+	#[test]
+	fn polyline_quads() {
+		// Quads of a stroke along a polyline, their ends crossing at tiny angles
+		let points = [
+			(7., 4.316),
+			(8., 3.377),
+			(9., 2.137),
+			(10., 0.706),
+			(11., -0.789),
+		]
+		.map(|(x, y)| Point2::new(x, y));
+		let quads = points
+			.windows(2)
+			.map(|w| {
+				let n = (w[1] - w[0]).normalize() * 0.5;
+				let n = Vector2::new(-n.y, n.x);
+				[w[0] - n, w[1] - n, w[1] + n, w[0] + n]
+			})
+			.collect::<Vec<_>>();
+
+		let all = polygons(&quads[0], &[&quads[1], &quads[2], &quads[3]]).union(&Curve::default());
+		let chained = quads[1..]
+			.iter()
+			.fold(polygons(&quads[0], &[]), |union, quad| {
+				union.union(&polygons(quad, &[]))
+			});
+		assert!(all.subpaths.is_empty());
+		assert!(chained.subpaths.is_empty());
+		assert!((area(&all) - area(&chained)).abs() < 0.0001);
+
+		// The first quad apart from the last two
+		let apart = polygons(&quads[0], &[&quads[2], &quads[3]]).union(&Curve::default());
+		assert_eq!(apart.subpaths.len(), 1);
+		let expected = area(&polygons(&quads[0], &[]))
+			+ area(&polygons(&quads[2], &[]).union(&polygons(&quads[3], &[])));
+		assert!((area(&apart) - expected).abs() < 0.0001);
+	}
+}
@@ -0,0 +1,80 @@
+use crate::shapes::{CurvePosition, KeypointPosition};
+use nalgebra::Point2;
+
+/// Straight lines approximating a subpath of a curve
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Polyline {
+	/// Points
+	pub points: Vec<Point2<f32>>,
+	/// Is the polyline closing between last and first point ?
+	pub closed: bool,
+}
+
+/// Point of the cubic bezier `p0, p1, p2, p3` at `t`, between 0 and 1
+pub(crate) fn cubic_point([p0, p1, p2, p3]: [Point2<f32>; 4], t: f32) -> Point2<f32> {
+	let mt = 1. - t;
+	Point2::from(
+		p0.coords * (mt * mt * mt)
+			+ p1.coords * (3. * mt * mt * t)
+			+ p2.coords * (3. * mt * t * t)
+			+ p3.coords * (t * t * t),
+	)
+}
+
+/// Push the points of the cubic bezier `p0, p1, p2, p3`, `p0` excluded
+fn flatten_cubic(control: [Point2<f32>; 4], tolerance: f32, points: &mut Vec<Point2<f32>>) {
+	let [p0, p1, p2, p3] = control;
+
+	// Straight lines stay within `tolerance` if there is enough of them,
+	// given the maximum of the second derivative
+	let dd = (p0 - 2. * p1.coords + p2.coords)
+		.coords
+		.magnitude()
+		.max((p1 - 2. * p2.coords + p3.coords).coords.magnitude());
+	let n = (0.75 * dd / tolerance.max(f32::EPSILON))
+		.sqrt()
+		.ceil()
+		.clamp(1., 1024.) as usize;
+
+	points.extend((1..=n).map(|i| cubic_point(control, i as f32 / n as f32)));
+}
+
+impl CurvePosition {
+	/// Approximate each subpath with straight lines, deviating at most by `tolerance` from the curve
+	pub fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+		self.to_cubics()
+			.iter_subpaths()
+			.map(|subpath| {
+				let mut points: Vec<Point2<f32>> = vec![];
+
+				for keypoint in &subpath.keypoints {
+					match keypoint {
+						KeypointPosition::Point(p) => points.push(*p),
+						KeypointPosition::Bezier(b) => {
+							if let Some(start) = b.start {
+								points.push(start);
+							}
+
+							match points.last() {
+								Some(start) => flatten_cubic(
+									[*start, b.start_control, b.end_control, b.end],
+									tolerance,
+									&mut points,
+								),
+								None => points.push(b.end),
+							}
+						}
+						KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+							unreachable!("converted to cubics")
+						}
+					}
+				}
+
+				Polyline {
+					points,
+					closed: subpath.closed,
+				}
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,311 @@
+use super::DEFAULT_TOLERANCE;
+use crate::{
+	prelude::*,
+	shapes::{CurvePosition, FillRule, Group, ImagePosition},
+	style::StylePosition,
+};
+use nalgebra::{Point2, Transform2};
+use palette::Srgba;
+
+/// Shape under a point, found by [`Shape::hit_test`]
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Hit {
+	/// Index of the shape in each group containing it, from the root
+	pub path: Vec<usize>,
+	/// Metadata of each group containing the shape, from the root
+	pub metadata: Vec<Vec<(String, String)>>,
+}
+
+/// Winding number of `ring` around `point`
+fn winding(ring: &[Point2<f32>], point: Point2<f32>) -> i32 {
+	ring.iter()
+		.zip(ring.iter().cycle().skip(1))
+		.map(|(a, b)| {
+			let side = (b - a).perp(&(point - a));
+			if a.y <= point.y {
+				if b.y > point.y && side > 0. {
+					return 1;
+				}
+			} else if b.y <= point.y && side < 0. {
+				return -1;
+			}
+			0
+		})
+		.sum()
+}
+
+/// Distance from `point` to the segment between `a` and `b`
+fn distance_to_segment(a: Point2<f32>, b: Point2<f32>, point: Point2<f32>) -> f32 {
+	let d = b - a;
+	let t = if d.magnitude_squared() > 0. {
+		((point - a).dot(&d) / d.magnitude_squared()).clamp(0., 1.)
+	} else {
+		0.
+	};
+	(a + d * t - point).magnitude()
+}
+
+/// Whether `point` is on the filled area or the stroke of `curve`
+///
+/// Joins and caps of the stroke are considered round.
+fn curve_contains(
+	curve: &CurvePosition,
+	is_filled: bool,
+	stroke: Option<&Stroke>,
+	point: Point2<f32>,
+) -> bool {
+	let polylines = curve.flatten(DEFAULT_TOLERANCE);
+
+	if is_filled {
+		let winding: i32 = polylines.iter().map(|p| winding(&p.points, point)).sum();
+		let inside = match curve.fill_rule {
+			FillRule::NonZero => winding != 0,
+			FillRule::EvenOdd => winding % 2 != 0,
+		};
+		if inside {
+			return true;
+		}
+	}
+
+	let Some(stroke) = stroke else {
+		return false;
+	};
+
+	polylines.iter().any(|polyline| {
+		let points = &polyline.points;
+		let closing = polyline.closed.then(|| points.first()).flatten();
+		points
+			.iter()
+			.zip(points.iter().skip(1).chain(closing))
+			.any(|(a, b)| distance_to_segment(*a, *b, point) <= stroke.width / 2.)
+			|| (points.len() == 1 && (points[0] - point).magnitude() <= stroke.width / 2.)
+	})
+}
+
+impl Shape {
+	/// Shapes under `point`, in root coordinates, topmost first
+	///
+	/// Curves and ellipses are hit on their fill and their stroke, texts on the outlines of their glyphs
+	/// and images on their extents.
+	pub fn hit_test(&self, point: Point2<f32>) -> Vec<Hit> {
+		let mut hits = vec![];
+		self.hit_test_into(
+			point,
+			&Transform2::identity(),
+			&StylePosition {
+				fill: None,
+				stroke: None,
+			},
+			&mut Hit::default(),
+			&mut hits,
+		);
+
+		hits.reverse();
+		hits
+	}
+
+	fn hit_test_into(
+		&self,
+		point: Point2<f32>,
+		parent_transform: &Transform2<f32>,
+		style: &StylePosition,
+		current: &mut Hit,
+		hits: &mut Vec<Hit>,
+	) {
+		let is_hit = match self {
+			Shape::Group(Group {
+				local_transform,
+				shapes,
+				metadata,
+			}) => {
+				let parent_transform = parent_transform * local_transform;
+
+				current.metadata.push(metadata.clone());
+				for (idx, shape) in shapes.iter().enumerate() {
+					current.path.push(idx);
+					shape.hit_test_into(point, &parent_transform, style, current, hits);
+					current.path.pop();
+				}
+				current.metadata.pop();
+
+				return;
+			}
+			Shape::Style {
+				fill,
+				stroke,
+				shape,
+			} => {
+				let style = StylePosition {
+					fill: fill.clone().map(|v| *parent_transform * v),
+					stroke: stroke.clone().map(|v| *parent_transform * v),
+				};
+				return shape.hit_test_into(point, parent_transform, &style, current, hits);
+			}
+			Shape::Clip { clip, shape } => {
+				if curve_contains(&clip.position(parent_transform), true, None, point) {
+					shape.hit_test_into(point, parent_transform, style, current, hits);
+				}
+				return;
+			}
+			Shape::Mask { mask, shape, .. } => {
+				let mut mask_hits = vec![];
+				mask.hit_test_into(
+					point,
+					parent_transform,
+					&StylePosition {
+						fill: None,
+						stroke: None,
+					},
+					&mut Hit::default(),
+					&mut mask_hits,
+				);
+				if !mask_hits.is_empty() {
+					shape.hit_test_into(point, parent_transform, style, current, hits);
+				}
+				return;
+			}
+			Shape::Dynamic {
+				local_transform,
+				shaper,
+			} => {
+				let parent_transform = parent_transform * local_transform;
+				return shaper().hit_test_into(point, &parent_transform, style, current, hits);
+			}
+			Shape::Ellipse(ellipse) => curve_contains(
+				&ellipse.as_curve().position(parent_transform),
+				style.fill.is_some(),
+				style.stroke.as_ref(),
+				point,
+			),
+			Shape::Curve(curve) => curve_contains(
+				&curve.position(parent_transform),
+				style.fill.is_some(),
+				style.stroke.as_ref(),
+				point,
+			),
+			Shape::Text(text) => {
+				// Texts without style are drawn filled
+				let style = match style {
+					StylePosition {
+						fill: None,
+						stroke: None,
+					} => &StylePosition {
+						fill: Some(Fill::Solid {
+							color: Srgba::default(),
+						}),
+						stroke: None,
+					},
+					style => style,
+				};
+
+				let mut glyph_hits = vec![];
+				text.to_outlines().hit_test_into(
+					point,
+					parent_transform,
+					style,
+					&mut Hit::default(),
+					&mut glyph_hits,
+				);
+				!glyph_hits.is_empty()
+			}
+			Shape::Image(image) => {
+				let ImagePosition {
+					top_left,
+					top_right,
+					bottom_right,
+					bottom_left,
+					..
+				} = image.position(parent_transform);
+				winding(&[top_left, top_right, bottom_right, bottom_left], point) != 0
+			}
+		};
+
+		if is_hit {
+			hits.push(current.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use nalgebra::Point2;
+	use palette::Srgba;
+
+	#[test]
+	fn hit_groups() {
+		let red = Srgba::new(1., 0., 0., 1.);
+
+		let mut inner = dessin!(
+			[
+				*Circle(radius = 1., fill = red),
+				*Rectangle(width = 4., height = 4., stroke = (red, 0.2)),
+			] > (translate = [10., 0.])
+		);
+		inner.add_metadata(("id", "inner"));
+
+		let mut root = dessin!([
+			*Circle(radius = 1., fill = red),
+			{ inner },
+			Text(text = "Hello", translate = [0., 10.]),
+		]);
+		root.add_metadata(("id", "root"));
+
+		let hits = root.hit_test(Point2::new(10., 0.));
+		assert_eq!(hits.len(), 1);
+		assert_eq!(hits[0].path, vec![1, 0]);
+		assert_eq!(
+			hits[0].metadata,
+			vec![
+				vec![("id".to_string(), "root".to_string())],
+				vec![("id".to_string(), "inner".to_string())],
+			]
+		);
+
+		// Only the stroke of the rectangle is hit, not its inside
+		assert_eq!(root.hit_test(Point2::new(12., 0.))[0].path, vec![1, 1]);
+		assert!(root.hit_test(Point2::new(11.5, 0.)).is_empty());
+
+		// On the stem of the H
+		assert_eq!(root.hit_test(Point2::new(1.2, 15.))[0].path, vec![2]);
+		assert!(root.hit_test(Point2::new(0., 3.)).is_empty());
+	}
+
+	#[test]
+	fn topmost_first() {
+		let red = Srgba::new(1., 0., 0., 1.);
+
+		let shape = dessin!([
+			*Circle(radius = 2., fill = red),
+			*Circle(radius = 1., fill = red, translate = [1., 0.]),
+		]);
+
+		let hits = shape.hit_test(Point2::new(1., 0.));
+		assert_eq!(
+			hits.iter().map(|h| h.path.clone()).collect::<Vec<_>>(),
+			vec![vec![1], vec![0]]
+		);
+
+		// Unfilled shapes can't be hit
+		assert!(dessin!(Circle(radius = 2.) > ())
+			.hit_test(Point2::origin())
+			.is_empty());
+	}
+
+	#[test]
+	fn text_glyphs() {
+		let text = dessin!(Text(text = "I          I", font_size = 10.));
+
+		// Between the glyphs, inside the extents of the text
+		let bb = text.local_bounding_box();
+		let middle = Point2::new((bb.left() + bb.right()) / 2., (bb.top() + bb.bottom()) / 2.);
+		assert!(Shape::from(text.clone()).hit_test(middle).is_empty());
+
+		let Shape::Group(Group { shapes, .. }) = text.to_outlines() else {
+			panic!("outlines are a group");
+		};
+		let bb = shapes[0].local_bounding_box();
+		let stem = Point2::new((bb.left() + bb.right()) / 2., (bb.top() + bb.bottom()) / 2.);
+		assert_eq!(Shape::from(text).hit_test(stem).len(), 1);
+	}
+}
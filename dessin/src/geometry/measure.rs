@@ -0,0 +1,345 @@
+use super::cubic_point;
+use crate::shapes::{Bezier, Curve, CurvePosition, Keypoint, KeypointPosition};
+use nalgebra::{Point2, Transform2, Unit, Vector2};
+
+/// Gauss-Legendre nodes and weights on [-1, 1]
+const GAUSS_LEGENDRE: [(f32, f32); 8] = [
+	(-0.960_289_9, 0.101_228_54),
+	(-0.796_666_5, 0.222_381_03),
+	(-0.525_532_4, 0.313_706_64),
+	(-0.183_434_64, 0.362_683_8),
+	(0.183_434_64, 0.362_683_8),
+	(0.525_532_4, 0.313_706_64),
+	(0.796_666_5, 0.222_381_03),
+	(0.960_289_9, 0.101_228_54),
+];
+
+/// Straight or curved part of a curve
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+	Line([Point2<f32>; 2]),
+	Cubic([Point2<f32>; 4]),
+}
+impl Segment {
+	fn point_at(&self, u: f32) -> Point2<f32> {
+		match self {
+			Segment::Line([a, b]) => a + (b - a) * u,
+			Segment::Cubic(control) => cubic_point(*control, u),
+		}
+	}
+
+	fn derivative_at(&self, u: f32) -> Vector2<f32> {
+		match self {
+			Segment::Line([a, b]) => b - a,
+			Segment::Cubic([p0, p1, p2, p3]) => {
+				let mu = 1. - u;
+				3. * mu * mu * (p1 - p0) + 6. * mu * u * (p2 - p1) + 3. * u * u * (p3 - p2)
+			}
+		}
+	}
+
+	/// Length between 0 and `u`
+	fn length_to(&self, u: f32) -> f32 {
+		match self {
+			Segment::Line([a, b]) => (b - a).magnitude() * u,
+			Segment::Cubic(_) => {
+				// Quadrature on a few sub-intervals, the derivative may vary a lot
+				const PARTS: usize = 4;
+				let step = u / PARTS as f32;
+				(0..PARTS)
+					.map(|part| {
+						let start = part as f32 * step;
+						GAUSS_LEGENDRE
+							.iter()
+							.map(|(x, w)| {
+								w * self.derivative_at(start + (x + 1.) * step / 2.).magnitude()
+							})
+							.sum::<f32>() * step / 2.
+					})
+					.sum()
+			}
+		}
+	}
+
+	/// Parameter at which the length from the start of the segment is `length`
+	fn parameter_at(&self, length: f32, total: f32) -> f32 {
+		if total <= 0. {
+			return 0.;
+		}
+
+		match self {
+			Segment::Line(_) => (length / total).clamp(0., 1.),
+			Segment::Cubic(_) => {
+				let (mut low, mut high) = (0., 1.);
+				let mut u = (length / total).clamp(0., 1.);
+				for _ in 0..32 {
+					let diff = self.length_to(u) - length;
+					if diff.abs() <= total * 1e-6 {
+						break;
+					}
+
+					if diff > 0. {
+						high = u;
+					} else {
+						low = u;
+					}
+
+					// Newton step, unless it goes out of the bracket
+					let speed = self.derivative_at(u).magnitude();
+					let next = u - diff / speed;
+					u = if speed > 0. && next > low && next < high {
+						next
+					} else {
+						(low + high) / 2.
+					};
+				}
+				u
+			}
+		}
+	}
+
+	/// Both parts of the segment, before and after `u`
+	fn split_at(&self, u: f32) -> (Segment, Segment) {
+		match *self {
+			Segment::Line([a, b]) => {
+				let m = a + (b - a) * u;
+				(Segment::Line([a, m]), Segment::Line([m, b]))
+			}
+			Segment::Cubic([p0, p1, p2, p3]) => {
+				let lerp = |a: Point2<f32>, b: Point2<f32>| a + (b - a) * u;
+				let (p01, p12, p23) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+				let (p012, p123) = (lerp(p01, p12), lerp(p12, p23));
+				let m = lerp(p012, p123);
+				(
+					Segment::Cubic([p0, p01, p012, m]),
+					Segment::Cubic([m, p123, p23, p3]),
+				)
+			}
+		}
+	}
+
+	fn start(&self) -> Point2<f32> {
+		match self {
+			Segment::Line([a, _]) => *a,
+			Segment::Cubic([a, ..]) => *a,
+		}
+	}
+
+	fn as_keypoint(&self) -> Keypoint {
+		match *self {
+			Segment::Line([_, b]) => Keypoint::Point(b),
+			Segment::Cubic([_, p1, p2, p3]) => Keypoint::Bezier(Bezier::new(p1, p2, p3)),
+		}
+	}
+}
+
+/// Segments of the curve, without its subpaths, with their lengths
+fn segments(curve: &CurvePosition) -> Vec<(Segment, f32)> {
+	let mut segments = vec![];
+	let mut first = None;
+	let mut last: Option<Point2<f32>> = None;
+
+	for keypoint in &curve.to_cubics().keypoints {
+		match keypoint {
+			KeypointPosition::Point(p) => {
+				if let Some(last) = last {
+					segments.push(Segment::Line([last, *p]));
+				}
+				last = Some(*p);
+			}
+			KeypointPosition::Bezier(b) => {
+				if let (Some(l), Some(start)) = (last, b.start) {
+					if l != start {
+						segments.push(Segment::Line([l, start]));
+					}
+				}
+
+				if let Some(start) = b.start.or(last) {
+					segments.push(Segment::Cubic([
+						start,
+						b.start_control,
+						b.end_control,
+						b.end,
+					]));
+				}
+				last = Some(b.end);
+			}
+			KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+				unreachable!("converted to cubics")
+			}
+		}
+
+		if first.is_none() {
+			first = match keypoint {
+				KeypointPosition::Point(p) => Some(*p),
+				KeypointPosition::Bezier(b) => b.start.or(Some(b.end)),
+				KeypointPosition::Quadratic(_) | KeypointPosition::ArcTo(_) => {
+					unreachable!("converted to cubics")
+				}
+			};
+		}
+	}
+
+	if let (true, Some(first), Some(last)) = (curve.closed, first, last) {
+		if first != last {
+			segments.push(Segment::Line([last, first]));
+		}
+	}
+
+	segments
+		.into_iter()
+		.map(|segment| (segment, segment.length_to(1.)))
+		.collect()
+}
+
+impl Curve {
+	/// Segments of the curve, positioned with its local transform
+	fn measured_segments(&self) -> Vec<(Segment, f32)> {
+		segments(&self.position(&Transform2::identity()))
+	}
+
+	/// Segment index and parameter on this segment at `t`, a fraction of the length of the curve
+	fn locate(segments: &[(Segment, f32)], t: f32) -> Option<(usize, f32)> {
+		let total: f32 = segments.iter().map(|(_, length)| length).sum();
+		let mut remaining = t.clamp(0., 1.) * total;
+
+		for (idx, (segment, length)) in segments.iter().enumerate() {
+			if remaining <= *length || idx == segments.len() - 1 {
+				return Some((idx, segment.parameter_at(remaining, *length)));
+			}
+			remaining -= length;
+		}
+
+		None
+	}
+
+	/// Length of the curve, closing line included, subpaths excluded
+	pub fn length(&self) -> f32 {
+		self.measured_segments()
+			.iter()
+			.map(|(_, length)| length)
+			.sum()
+	}
+
+	/// Point at `t`, a fraction of the length of the curve between 0 and 1
+	pub fn point_at(&self, t: f32) -> Option<Point2<f32>> {
+		let segments = self.measured_segments();
+		Curve::locate(&segments, t).map(|(idx, u)| segments[idx].0.point_at(u))
+	}
+
+	/// Direction of the curve at `t`, a fraction of the length of the curve between 0 and 1
+	pub fn tangent_at(&self, t: f32) -> Option<Unit<Vector2<f32>>> {
+		let segments = self.measured_segments();
+		Curve::locate(&segments, t).and_then(|(idx, u)| {
+			let segment = segments[idx].0;
+			let derivative = segment.derivative_at(u);
+			// Control points on top of their point have no derivative, look a little further
+			let derivative = if derivative.magnitude() > f32::EPSILON {
+				derivative
+			} else {
+				segment.point_at((u + 1e-3).min(1.)) - segment.point_at((u - 1e-3).max(0.))
+			};
+			Unit::try_new(derivative, f32::EPSILON)
+		})
+	}
+
+	/// Left normal of the curve at `t`, a fraction of the length of the curve between 0 and 1
+	pub fn normal_at(&self, t: f32) -> Option<Unit<Vector2<f32>>> {
+		self.tangent_at(t)
+			.map(|tangent| Unit::new_unchecked(Vector2::new(-tangent.y, tangent.x)))
+	}
+
+	/// Both parts of the curve, before and after `t`, a fraction of the length of the curve between 0 and 1
+	///
+	/// Parts are opened curves, positioned with the local transform of this curve, without its subpaths.
+	pub fn split_at(&self, t: f32) -> (Curve, Curve) {
+		let segments = self.measured_segments();
+		let Some((at, u)) = Curve::locate(&segments, t) else {
+			return (Curve::default(), Curve::default());
+		};
+
+		let (mut before, mut after) = (Curve::default(), Curve::default());
+		let mut is_after = false;
+		for (idx, (segment, _)) in segments.into_iter().enumerate() {
+			let current = if is_after { &mut after } else { &mut before };
+			if current.keypoints.is_empty() {
+				current.then(segment.start());
+			}
+
+			if idx == at {
+				let (head, tail) = segment.split_at(u);
+				before.then(head.as_keypoint());
+				after.then(tail.start());
+				after.then(tail.as_keypoint());
+				is_after = true;
+			} else {
+				current.then(segment.as_keypoint());
+			}
+		}
+
+		(before, after)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use assert_float_eq::*;
+	use nalgebra::Point2;
+	use std::f32::consts::PI;
+
+	#[test]
+	fn square() {
+		let square = dessin!(Rectangle(width = 2., height = 2.)).as_curve();
+
+		assert_f32_near!(square.length(), 8., 8);
+
+		let p = square.point_at(0.5).unwrap();
+		let start = square.point_at(0.).unwrap();
+		assert!(((p - start).magnitude() - 8f32.sqrt()).abs() < 10e-6);
+	}
+
+	#[test]
+	fn circle() {
+		let circle = dessin!(Circle(radius = 1.)).as_curve();
+
+		// Beziers are very close to the circle
+		assert!((circle.length() - 2. * PI).abs() < 0.01);
+
+		for t in [0., 0.1, 0.25, 0.3, 0.5, 0.8] {
+			let angle = t * 2. * PI;
+			let p = circle.point_at(t).unwrap();
+			assert!((p - Point2::new(angle.cos(), angle.sin())).magnitude() < 0.01);
+
+			let tangent = circle.tangent_at(t).unwrap();
+			assert!((tangent.x + angle.sin()).abs() < 0.01);
+			assert!((tangent.y - angle.cos()).abs() < 0.01);
+
+			// Left normal of a counter clockwise circle points inside
+			let normal = circle.normal_at(t).unwrap();
+			assert!((p + normal.into_inner() - Point2::origin()).magnitude() < 0.01);
+		}
+	}
+
+	#[test]
+	fn split() {
+		let line = dessin!(Curve(
+			then = Point2::new(0., 0.),
+			then = Point2::new(4., 0.),
+			then = Point2::new(4., 4.),
+		));
+
+		let (before, after) = line.split_at(0.25);
+		assert_f32_near!(before.length(), 2., 8);
+		assert_f32_near!(after.length(), 6., 8);
+		assert_eq!(after.start_point(), Some(Point2::new(2., 0.)));
+
+		let circle = dessin!(Circle(radius = 1.)).as_curve();
+		let (before, after) = circle.split_at(0.3);
+		assert!((before.length() - 0.3 * circle.length()).abs() < 10e-4);
+		assert!((after.length() - 0.7 * circle.length()).abs() < 10e-4);
+		assert!(!before.closed && !after.closed);
+
+		assert!(dessin!(Curve()).point_at(0.5).is_none());
+	}
+}
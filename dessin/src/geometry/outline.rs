@@ -0,0 +1,412 @@
+use super::{boolean, BooleanOp, Polyline, DEFAULT_TOLERANCE};
+use crate::{
+	shapes::{Curve, FillRule, Keypoint},
+	style::{LineCap, LineJoin, Stroke},
+};
+use nalgebra::{Point2, Transform2, Vector2};
+use std::f32::consts::{PI, TAU};
+
+fn cross(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+	a.x * b.y - a.y * b.x
+}
+
+/// Angle between two points of a circle of `radius` for the chord to stay within `tolerance`
+fn arc_step(radius: f32, tolerance: f32) -> f32 {
+	(2. * (1. - (tolerance / radius).min(1.)).acos()).clamp(TAU / 256., PI / 4.)
+}
+
+/// Polygon approximating the arc of `radius` around `center`, from `from` to `to` counter clockwise
+fn arc(center: Point2<f32>, radius: f32, from: f32, to: f32, tolerance: f32) -> Vec<Point2<f32>> {
+	let span = (to - from).rem_euclid(TAU);
+	let span = if span == 0. { TAU } else { span };
+	let n = (span / arc_step(radius, tolerance)).ceil().max(1.) as usize;
+
+	(0..=n)
+		.map(|i| {
+			let angle = from + span * i as f32 / n as f32;
+			center + Vector2::new(angle.cos(), angle.sin()) * radius
+		})
+		.collect()
+}
+
+/// Split a polyline into its dashes
+fn dashed(polyline: &Polyline, dash: &[f32], dash_offset: f32) -> Vec<Polyline> {
+	let pattern: f32 = dash.iter().sum();
+	if dash.is_empty() || pattern <= 0. {
+		return vec![polyline.clone()];
+	}
+
+	// Where the line starts in the pattern
+	let mut idx = 0;
+	let mut remaining = dash_offset.rem_euclid(pattern);
+	while remaining >= dash[idx] {
+		remaining -= dash[idx];
+		idx = (idx + 1) % dash.len();
+	}
+	remaining = dash[idx] - remaining;
+
+	let points = &polyline.points;
+	let closing = polyline.closed.then(|| points.first()).flatten();
+
+	let mut dashes = vec![];
+	let mut current = (idx % 2 == 0)
+		.then(|| points.first().copied())
+		.flatten()
+		.map(|p| vec![p]);
+	for (a, b) in points.iter().zip(points.iter().skip(1).chain(closing)) {
+		let length = (b - a).magnitude();
+		let mut done = 0.;
+
+		while length - done > remaining {
+			done += remaining;
+			let p = a + (b - a) * (done / length);
+
+			match current.take() {
+				Some(mut dash) => {
+					dash.push(p);
+					dashes.push(dash);
+				}
+				None => current = Some(vec![p]),
+			}
+
+			idx = (idx + 1) % dash.len();
+			remaining = dash[idx];
+		}
+
+		remaining -= length - done;
+		if let Some(dash) = &mut current {
+			dash.push(*b);
+		}
+	}
+	dashes.extend(current);
+
+	dashes
+		.into_iter()
+		.map(|points| Polyline {
+			points,
+			closed: false,
+		})
+		.collect()
+}
+
+/// Polygons whose union is the area covered by the stroke of `polylines`
+fn stroke_polygons(
+	polylines: &[Polyline],
+	width: f32,
+	cap: LineCap,
+	join: LineJoin,
+	miter_limit: f32,
+	tolerance: f32,
+) -> Vec<Vec<Point2<f32>>> {
+	let h = width / 2.;
+	let mut polygons = vec![];
+
+	for polyline in polylines {
+		let mut points = polyline.points.clone();
+		points.dedup();
+		if polyline.closed && points.len() > 1 && points.first() == points.last() {
+			points.pop();
+		}
+
+		if points.len() == 1 {
+			// Zero length lines are only visible with round caps
+			if cap == LineCap::Round {
+				polygons.push(arc(points[0], h, 0., TAU, tolerance));
+			}
+			continue;
+		}
+
+		let closed = polyline.closed && points.len() > 2;
+		let count = if closed {
+			points.len()
+		} else {
+			points.len() - 1
+		};
+		let segment = |i: usize| (points[i % points.len()], points[(i + 1) % points.len()]);
+
+		for i in 0..count {
+			let (a, b) = segment(i);
+			let n = Vector2::new(-(b - a).y, (b - a).x).normalize() * h;
+			polygons.push(vec![a - n, b - n, b + n, a + n]);
+		}
+
+		// Joins between a segment and the next one
+		let joins = if closed { 0..count } else { 0..(count - 1) };
+		for i in joins {
+			let (a, p) = segment(i);
+			let (_, b) = segment(i + 1);
+			let (d1, d2) = ((p - a).normalize(), (b - p).normalize());
+
+			let turn = cross(d1, d2);
+			if turn.abs() <= f32::EPSILON && d1.dot(&d2) > 0. {
+				continue;
+			}
+
+			// Normals on the outer side of the turn
+			let side = if turn > 0. { -1. } else { 1. };
+			let n1 = Vector2::new(-d1.y, d1.x) * h * side;
+			let n2 = Vector2::new(-d2.y, d2.x) * h * side;
+
+			let m = n1 + n2;
+			let miter_ratio = 2. * h / m.magnitude();
+			match join {
+				LineJoin::Round => {
+					let (from, to) = (n1.y.atan2(n1.x), n2.y.atan2(n2.x));
+					let (from, to) = if side < 0. { (from, to) } else { (to, from) };
+					let mut polygon = arc(p, h, from, to, tolerance);
+					polygon.push(p);
+					polygons.push(polygon);
+				}
+				LineJoin::Miter if m.magnitude() > f32::EPSILON && miter_ratio <= miter_limit => {
+					polygons.push(vec![
+						p,
+						p + n1,
+						p + m * (2. * h * h / m.magnitude_squared()),
+						p + n2,
+					]);
+				}
+				_ => polygons.push(vec![p, p + n1, p + n2]),
+			}
+		}
+
+		if closed {
+			continue;
+		}
+
+		// Caps at both ends
+		for (end, before) in [(points[0], points[1]), (points[count], points[count - 1])] {
+			let d = (end - before).normalize() * h;
+			let n = Vector2::new(-d.y, d.x);
+			match cap {
+				LineCap::Butt => {}
+				LineCap::Round => polygons.push(arc(end, h, 0., TAU, tolerance)),
+				LineCap::Square => polygons.push(vec![end - n, end + d - n, end + d + n, end + n]),
+			}
+		}
+	}
+
+	// Counter clockwise, so that they add up
+	for polygon in &mut polygons {
+		let area: f32 = polygon
+			.iter()
+			.zip(polygon.iter().cycle().skip(1))
+			.map(|(a, b)| a.x * b.y - a.y * b.x)
+			.sum();
+		if area < 0. {
+			polygon.reverse();
+		}
+	}
+
+	polygons
+}
+
+/// Curve made of all `polygons`, where they overlap included
+fn merged(polygons: Vec<Vec<Point2<f32>>>, tolerance: f32) -> Curve {
+	let mut polygons = polygons.into_iter().map(|polygon| Curve {
+		keypoints: polygon.into_iter().map(Keypoint::Point).collect(),
+		closed: true,
+		..Default::default()
+	});
+
+	let Some(mut curve) = polygons.next() else {
+		return Curve::default();
+	};
+	curve.subpaths.extend(polygons);
+	curve.fill_rule = FillRule::NonZero;
+
+	boolean(&curve, &Curve::default(), BooleanOp::Union, tolerance)
+}
+
+/// Filled outline of the stroke of a curve, approximated by straight lines deviating at most by `tolerance`
+///
+/// Width, caps, joins and dashes of the stroke are respected, its color is ignored.
+pub fn stroke_outline(curve: &Curve, stroke: &Stroke, tolerance: f32) -> Curve {
+	let polylines = curve
+		.position(&Transform2::identity())
+		.flatten(tolerance)
+		.iter()
+		.flat_map(|polyline| dashed(polyline, &stroke.dash, stroke.dash_offset))
+		.collect::<Vec<_>>();
+
+	merged(
+		stroke_polygons(
+			&polylines,
+			stroke.width,
+			stroke.cap,
+			stroke.join,
+			stroke.miter_limit,
+			tolerance,
+		),
+		tolerance,
+	)
+}
+
+/// Curve grown outward by `distance`, or shrunk inward if `distance` is negative,
+/// approximated by straight lines deviating at most by `tolerance`
+///
+/// The curve is considered closed, corners are joined with `join`.
+pub fn offset(curve: &Curve, distance: f32, join: LineJoin, tolerance: f32) -> Curve {
+	let polylines = curve
+		.position(&Transform2::identity())
+		.flatten(tolerance)
+		.into_iter()
+		.map(|polyline| Polyline {
+			closed: true,
+			..polyline
+		})
+		.collect::<Vec<_>>();
+
+	let band = merged(
+		stroke_polygons(
+			&polylines,
+			2. * distance.abs(),
+			LineCap::Butt,
+			join,
+			// Miters as far as they go, so that corners stay sharp
+			f32::INFINITY,
+			tolerance,
+		),
+		tolerance,
+	);
+
+	let op = if distance >= 0. {
+		BooleanOp::Union
+	} else {
+		BooleanOp::Difference
+	};
+	boolean(&curve.clone().with_close(true), &band, op, tolerance)
+}
+
+impl Curve {
+	/// Replace the curve by the filled outline of its stroke, see [`stroke_outline`]
+	#[inline]
+	pub fn outline<S: Into<Stroke>>(&mut self, stroke: S) -> &mut Self {
+		*self = stroke_outline(self, &stroke.into(), DEFAULT_TOLERANCE);
+		self
+	}
+
+	/// Replace the curve by the filled outline of its stroke, see [`stroke_outline`]
+	#[inline]
+	pub fn with_outline<S: Into<Stroke>>(mut self, stroke: S) -> Self {
+		self.outline(stroke);
+		self
+	}
+
+	/// Grow the curve outward by `distance`, or shrink it inward if `distance` is negative, see [`offset`]
+	#[inline]
+	pub fn offset(&mut self, distance: f32) -> &mut Self {
+		*self = offset(self, distance, LineJoin::Miter, DEFAULT_TOLERANCE);
+		self
+	}
+
+	/// Grow the curve outward by `distance`, or shrink it inward if `distance` is negative, see [`offset`]
+	#[inline]
+	pub fn with_offset(mut self, distance: f32) -> Self {
+		self.offset(distance);
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::prelude::*;
+	use assert_float_eq::*;
+
+	fn area(curve: &Curve) -> f32 {
+		curve
+			.position(&Default::default())
+			.flatten(0.001)
+			.iter()
+			.map(|polyline| {
+				let p = &polyline.points;
+				p.iter()
+					.zip(p.iter().cycle().skip(1))
+					.map(|(a, b)| a.x * b.y - a.y * b.x)
+					.sum::<f32>() / 2.
+			})
+			.sum()
+	}
+
+	fn black() -> palette::Srgba {
+		palette::Srgba::new(0., 0., 0., 1.)
+	}
+
+	#[test]
+	fn line_outline() {
+		let line = dessin!(Curve(
+			then = Point2::new(0., 0.),
+			then = Point2::new(4., 0.),
+		));
+
+		let butt = line.clone().with_outline((black(), 2.));
+		assert_f32_near!(area(&butt), 8., 8);
+
+		let square = line
+			.clone()
+			.with_outline(Stroke::new_solid(black(), 2.).with_cap(LineCap::Square));
+		assert_f32_near!(area(&square), 12., 8);
+
+		let dashed = line.with_outline(Stroke::new_dashed(black(), 2., 1., 1.));
+		assert_eq!(dashed.subpaths.len(), 1);
+		assert_f32_near!(area(&dashed), 4., 8);
+	}
+
+	#[test]
+	fn corner_outline() {
+		let corner = dessin!(Curve(
+			then = Point2::new(0., 0.),
+			then = Point2::new(4., 0.),
+			then = Point2::new(4., 4.),
+		));
+
+		let miter = corner.clone().with_outline((black(), 2.));
+		assert_f32_near!(area(&miter), 16., 8);
+
+		let bevel = corner.with_outline(Stroke::new_solid(black(), 2.).with_join(LineJoin::Bevel));
+		assert_f32_near!(area(&bevel), 15.5, 8);
+	}
+
+	#[test]
+	fn polyline_outline() {
+		let points = (0..12)
+			.map(|i| Point2::new(i as f32, 1.5 * (i as f32 * 0.9).sin()))
+			.collect::<Vec<_>>();
+		let length: f32 = points.windows(2).map(|w| (w[1] - w[0]).magnitude()).sum();
+		let mut wave = Curve::default();
+		for p in points {
+			wave.then(p);
+		}
+
+		// Miters add as much outside of each turn as segments overlap inside of it
+		let miter = wave.clone().with_outline((black(), 1.));
+		assert!(miter.subpaths.is_empty());
+		assert!((area(&miter) - length).abs() < 0.001);
+
+		let round = wave
+			.clone()
+			.with_outline(Stroke::new_solid(black(), 1.).with_join(LineJoin::Round));
+		let bevel = wave.with_outline(Stroke::new_solid(black(), 1.).with_join(LineJoin::Bevel));
+		assert!(area(&bevel) < area(&round) && area(&round) < area(&miter));
+		assert!(area(&bevel) > length * 0.99);
+	}
+
+	#[test]
+	fn offset_square() {
+		let square = dessin!(Rectangle(width = 2., height = 2.)).as_curve();
+
+		assert_f32_near!(area(&square.clone().with_offset(1.)), 16., 8);
+		assert_f32_near!(area(&square.clone().with_offset(-0.5)), 1., 8);
+		assert!(square.clone().with_offset(-2.).keypoints.is_empty());
+
+		let rounded = offset(&square, 1., LineJoin::Round, 0.0001);
+		assert!((area(&rounded) - (4. + 8. + PI)).abs() < 0.001);
+	}
+
+	#[test]
+	fn offset_polygon_from_macro() {
+		let hexagon = dessin!({ polygons::Hexagon::default().as_curve() }(offset = -0.1));
+		let bb = hexagon.local_bounding_box().straigthen();
+		assert!(bb.width() < 2. - 0.2 + 10e-3);
+	}
+}
@@ -166,10 +166,14 @@ extern crate self as dessin;
 pub mod contrib;
 /// Declarations to create an export format.
 pub mod export;
+pub mod geometry;
+#[cfg(feature = "serde")]
+pub mod serialize;
 /// Building blocks of a dessin
 pub mod shapes;
 /// Styling of the building blocks
 pub mod style;
+pub mod visit;
 
 pub use ::image;
 pub use ::nalgebra;
@@ -178,7 +182,12 @@ pub use ::palette;
 /// Prelude module includes everyting you need to build a dessin.
 /// You can of courses cherry pick what you need by importing directly from other modules.
 pub mod prelude {
-	pub use crate::{contrib::*, shapes::*, style::*};
+	pub use crate::{
+		contrib::*,
+		geometry::{BooleanOp, Hit},
+		shapes::*,
+		style::*,
+	};
 	pub use ::dessin_macros::{dessin, Shape};
 }
 
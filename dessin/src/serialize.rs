@@ -0,0 +1,267 @@
+//! Serialization of a dessin with [serde](https://serde.rs), behind the `serde` feature.
+//!
+//! [`Shape`] and all the types it is made of implement `Serialize` and `Deserialize`:
+//! - images are stored as PNG bytes,
+//! - fonts are stored by name, the font itself has to be registered again before export,
+//! - a [`Shape::Dynamic`] is stored as a snapshot of the shape it currently generates,
+//!   and is deserialized into a dynamic shape always returning this snapshot.
+//!
+//! To store a dessin for a long time or to send it somewhere else, wrap it in a [`VersionedShape`]:
+//! it records [`SCHEMA_VERSION`] and refuses dessins serialized with a newer schema.
+//!
+//! ## Example
+//! ```
+//! # use dessin::{prelude::*, serialize::VersionedShape};
+//! let dessin = dessin!([Circle(radius = 2.), Text(text = "Hello")]);
+//!
+//! let json = serde_json::to_string(&VersionedShape::from(dessin.clone())).unwrap();
+//! let restored: VersionedShape = serde_json::from_str(&json).unwrap();
+//!
+//! assert_eq!(Shape::from(restored), dessin);
+//! ```
+use crate::shapes::{Shape, Shaper};
+use nalgebra::Transform2;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::Arc;
+
+/// Version of the serialized format, bumped on each breaking change
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A [`Shape`] serialized along with the version of its schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionedShape {
+	/// Version of the schema used to serialize the shape
+	#[serde(deserialize_with = "supported_version")]
+	pub version: u32,
+	/// Serialized shape
+	pub shape: Shape,
+}
+impl From<Shape> for VersionedShape {
+	fn from(shape: Shape) -> Self {
+		VersionedShape {
+			version: SCHEMA_VERSION,
+			shape,
+		}
+	}
+}
+impl From<VersionedShape> for Shape {
+	fn from(VersionedShape { shape, .. }: VersionedShape) -> Self {
+		shape
+	}
+}
+
+fn supported_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+	let version = u32::deserialize(deserializer)?;
+	if version == 0 || version > SCHEMA_VERSION {
+		return Err(de::Error::custom(format!(
+			"unsupported dessin schema version {version}, expected at most {SCHEMA_VERSION}"
+		)));
+	}
+	Ok(version)
+}
+
+#[derive(Serialize)]
+struct DynamicSnapshot<'a> {
+	local_transform: &'a Transform2<f32>,
+	shape: Shape,
+}
+
+#[derive(Deserialize)]
+struct OwnedDynamicSnapshot {
+	local_transform: Transform2<f32>,
+	shape: Shape,
+}
+
+pub(crate) fn serialize_dynamic<S: Serializer>(
+	local_transform: &Transform2<f32>,
+	shaper: &Arc<Shaper>,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	DynamicSnapshot {
+		local_transform,
+		shape: shaper(),
+	}
+	.serialize(serializer)
+}
+
+pub(crate) fn deserialize_dynamic<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<(Transform2<f32>, Arc<Shaper>), D::Error> {
+	let OwnedDynamicSnapshot {
+		local_transform,
+		shape,
+	} = OwnedDynamicSnapshot::deserialize(deserializer)?;
+
+	Ok((local_transform, Arc::new(move || shape.clone())))
+}
+
+/// Images as PNG bytes, empty for an empty image
+pub(crate) mod encoded_image {
+	use image::{DynamicImage, ImageFormat};
+	use serde::{de, Deserializer, Serializer};
+	use std::{fmt, io::Cursor};
+
+	pub fn serialize<S: Serializer>(
+		image: &DynamicImage,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		if image.width() == 0 || image.height() == 0 {
+			return serializer.serialize_bytes(&[]);
+		}
+
+		// PNG has no floating point pixels
+		let converted;
+		let image = match image {
+			DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+				converted = DynamicImage::ImageRgba16(image.to_rgba16());
+				&converted
+			}
+			_ => image,
+		};
+
+		let mut bytes = Cursor::new(vec![]);
+		image
+			.write_to(&mut bytes, ImageFormat::Png)
+			.map_err(serde::ser::Error::custom)?;
+		serializer.serialize_bytes(bytes.get_ref())
+	}
+
+	struct BytesVisitor;
+	impl<'de> de::Visitor<'de> for BytesVisitor {
+		type Value = Vec<u8>;
+
+		fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("encoded image bytes")
+		}
+
+		fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+			Ok(v.to_vec())
+		}
+
+		fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+			Ok(v)
+		}
+
+		fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+			let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+			while let Some(byte) = seq.next_element()? {
+				bytes.push(byte);
+			}
+			Ok(bytes)
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<DynamicImage, D::Error> {
+		let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+		if bytes.is_empty() {
+			return Ok(DynamicImage::default());
+		}
+
+		image::load_from_memory(&bytes).map_err(de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::prelude::*;
+	use ::image::{DynamicImage, RgbaImage};
+	use nalgebra::{Point2, Rotation2};
+	use palette::Srgba;
+
+	fn round_trip(shape: &Shape) -> Shape {
+		let json = serde_json::to_string(&VersionedShape::from(shape.clone())).unwrap();
+		serde_json::from_str::<VersionedShape>(&json)
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn whole_tree() {
+		let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 2, |x, y| {
+			[x as u8 * 80, y as u8 * 120, 40, 255].into()
+		}));
+
+		let mut shape = dessin!(
+			[
+				*Circle(
+					radius = 2.,
+					fill = Fill::new_linear_gradient(
+						[0., 0.],
+						[1., 0.],
+						[
+							(0., Srgba::new(1., 0., 0., 1.)),
+							(1., Srgba::new(0., 0., 1., 1.))
+						]
+					),
+					stroke = Stroke::new_dashed(Srgba::new(0., 0., 0., 1.), 0.1, 0.2, 0.3),
+				),
+				Curve(
+					then = Point2::new(0., 0.),
+					then = Bezier::new(
+						Point2::new(1., 1.),
+						Point2::new(2., 1.),
+						Point2::new(3., 0.)
+					),
+					closed,
+					subpath = dessin!(Rectangle(width = 0.5, height = 0.5)).as_curve(),
+					fill_rule = FillRule::EvenOdd,
+				),
+				Text(
+					text = "Hello",
+					font = "Hyperlegible",
+					align = TextAlign::Center
+				),
+				Image(image = image, translate = [3., 4.]),
+				{
+					Shape::Clip {
+						clip: dessin!(Rectangle(width = 1., height = 1.)).as_curve(),
+						shape: Box::new(dessin!(Ellipse() > ())),
+					}
+				},
+			] > (rotate = Rotation2::new(0.3))
+		);
+		shape.add_metadata(("id", "root"));
+
+		assert_eq!(round_trip(&shape), shape);
+	}
+
+	#[test]
+	fn dynamic_snapshot() {
+		let circle_ref = Default::default();
+		let shape = dessin!(Dynamic::<Circle>(_ref = &circle_ref, radius = 2.) > ());
+
+		let restored = round_trip(&shape);
+		circle_ref.write().unwrap().radius(4.);
+
+		let Shape::Dynamic { shaper, .. } = restored else {
+			panic!("Dynamic shape expected");
+		};
+		assert_eq!(shaper(), dessin!(Circle(radius = 2.) > ()));
+	}
+
+	#[test]
+	fn text_defaults() {
+		let text = dessin!(Text(text = "Hello", font_weight = FontWeight::SEMI_BOLD));
+
+		let mut json = serde_json::to_value(&text).unwrap();
+		let fields = json.as_object_mut().unwrap();
+		for field in ["letter_spacing", "word_spacing", "decoration"] {
+			assert!(fields.remove(field).is_some());
+		}
+
+		assert_eq!(serde_json::from_value::<Text>(json).unwrap(), text);
+	}
+
+	#[test]
+	fn newer_schema() {
+		let json = serde_json::to_string(&VersionedShape {
+			version: SCHEMA_VERSION + 1,
+			shape: Shape::default(),
+		})
+		.unwrap();
+		assert!(serde_json::from_str::<VersionedShape>(&json).is_err());
+	}
+}
@@ -68,6 +68,7 @@
 //!
 //! ## Details
 
+pub(crate) mod clip;
 pub(crate) mod curve;
 pub(crate) mod dynamic;
 pub(crate) mod ellipse;
@@ -75,6 +76,7 @@ pub(crate) mod image;
 pub(crate) mod text;
 
 pub use self::image::*;
+pub use clip::*;
 pub use curve::*;
 pub use dynamic::*;
 pub use ellipse::*;
@@ -498,6 +500,7 @@ pub trait ShapeBoundingBox {
 
 /// A group of [`Shape`], locally positionned by a transform
 #[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group {
 	/// Transform of the whole group
 	pub local_transform: Transform2<f32>,
@@ -511,6 +514,7 @@ pub struct Group {
 ///
 /// Every complex shape should boil down to these.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Shape {
 	/// A group of [`Shape`], locally positionned by a transform
 	Group(Group),
@@ -523,6 +527,22 @@ pub enum Shape {
 		/// Styled shape. (Or Shapes if it is a [`Groupe`][Shape::Group])
 		shape: Box<Shape>,
 	},
+	/// Shape only visible inside a curve
+	Clip {
+		/// Only what is inside this curve is visible
+		clip: Curve,
+		/// Clipped shape
+		shape: Box<Shape>,
+	},
+	/// Shape whose visibility follows the luminance or the alpha of another shape
+	Mask {
+		/// Mask
+		mask: Box<Shape>,
+		/// How the mask hides the masked shape
+		kind: MaskKind,
+		/// Masked shape
+		shape: Box<Shape>,
+	},
 	/// Ellipse
 	Ellipse(Ellipse),
 	/// Image
@@ -536,6 +556,15 @@ pub enum Shape {
 	/// Enables chirurgical changes of the shape.
 	///
 	/// See [`Dynamic`] for more details.
+	///
+	/// Serialized as a snapshot of the current shape, see [`serialize`][crate::serialize].
+	#[cfg_attr(
+		feature = "serde",
+		serde(
+			serialize_with = "crate::serialize::serialize_dynamic",
+			deserialize_with = "crate::serialize::deserialize_dynamic"
+		)
+	)]
 	Dynamic {
 		/// Transform of the whole group
 		local_transform: Transform2<f32>,
@@ -559,6 +588,28 @@ impl PartialEq for Shape {
 					shape: shape2,
 				},
 			) => fill1 == fill2 && stroke1 == stroke2 && shape1 == shape2,
+			(
+				Shape::Clip {
+					clip: clip1,
+					shape: shape1,
+				},
+				Shape::Clip {
+					clip: clip2,
+					shape: shape2,
+				},
+			) => clip1 == clip2 && shape1 == shape2,
+			(
+				Shape::Mask {
+					mask: mask1,
+					kind: kind1,
+					shape: shape1,
+				},
+				Shape::Mask {
+					mask: mask2,
+					kind: kind2,
+					shape: shape2,
+				},
+			) => mask1 == mask2 && kind1 == kind2 && shape1 == shape2,
 			(Shape::Ellipse(e1), Shape::Ellipse(e2)) => e1 == e2,
 			(Shape::Image(i1), Shape::Image(i2)) => i1 == i2,
 			(Shape::Text(t1), Shape::Text(t2)) => t1 == t2,
@@ -648,6 +699,17 @@ impl fmt::Debug for Shape {
 				.field("stroke", stroke)
 				.field("shape", shape)
 				.finish(),
+			Self::Clip { clip, shape } => f
+				.debug_struct("Clip")
+				.field("clip", clip)
+				.field("shape", shape)
+				.finish(),
+			Self::Mask { mask, kind, shape } => f
+				.debug_struct("Mask")
+				.field("mask", mask)
+				.field("kind", kind)
+				.field("shape", shape)
+				.finish(),
 			Self::Ellipse(arg0) => f.debug_tuple("Ellipse").field(arg0).finish(),
 			Self::Image(arg0) => f.debug_tuple("Image").field(arg0).finish(),
 			Self::Text(arg0) => f.debug_tuple("Text").field(arg0).finish(),
@@ -685,6 +747,14 @@ impl ShapeOp for Shape {
 			Shape::Style { shape, .. } => {
 				shape.transform(transform_matrix);
 			}
+			Shape::Clip { clip, shape } => {
+				clip.transform(transform_matrix);
+				shape.transform(transform_matrix);
+			}
+			Shape::Mask { mask, shape, .. } => {
+				mask.transform(transform_matrix);
+				shape.transform(transform_matrix);
+			}
 			Shape::Ellipse(v) => {
 				v.transform(transform_matrix);
 			}
@@ -713,7 +783,9 @@ impl ShapeOp for Shape {
 			Shape::Group(Group {
 				local_transform, ..
 			}) => local_transform,
-			Shape::Style { shape, .. } => shape.local_transform(),
+			Shape::Style { shape, .. } | Shape::Clip { shape, .. } | Shape::Mask { shape, .. } => {
+				shape.local_transform()
+			}
 			Shape::Ellipse(v) => v.local_transform(),
 			Shape::Image(v) => v.local_transform(),
 			Shape::Text(v) => v.local_transform(),
@@ -739,6 +811,16 @@ impl ShapeBoundingBox for Shape {
 				.unwrap_or_else(|| BoundingBox::zero())
 				.as_unparticular(),
 			Shape::Style { shape, .. } => shape.local_bounding_box(),
+			Shape::Clip { clip, shape } => shape
+				.local_bounding_box()
+				.straigthen()
+				.intersect(clip.local_bounding_box().straigthen())
+				.as_unparticular(),
+			Shape::Mask { mask, shape, .. } => shape
+				.local_bounding_box()
+				.straigthen()
+				.intersect(mask.local_bounding_box().straigthen())
+				.as_unparticular(),
 			Shape::Ellipse(e) => e.local_bounding_box(),
 			Shape::Image(i) => i.local_bounding_box(),
 			Shape::Text(t) => t.local_bounding_box(),
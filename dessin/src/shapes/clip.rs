@@ -0,0 +1,218 @@
+use crate::prelude::*;
+use nalgebra::Transform2;
+use std::ops::{Deref, DerefMut};
+
+/// How a mask hides the masked shape
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaskKind {
+	/// Bright parts of the mask are visible, dark parts are hidden
+	#[default]
+	Luminance,
+	/// Opaque parts of the mask are visible, transparent parts are hidden
+	Alpha,
+}
+
+/// Clip of a shape, as given to an [`Exporter`][crate::export::Exporter]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipPosition {
+	/// Only what is inside the curve is visible
+	Curve(CurvePosition),
+	/// Visibility follows the luminance or the alpha of the mask
+	Mask {
+		/// Shape used as mask, to be exported with `parent_transform`
+		mask: Shape,
+		/// How the mask hides the masked shape
+		kind: MaskKind,
+		/// Transform to apply to the mask
+		parent_transform: Transform2<f32>,
+	},
+}
+
+/// Clip a shape to the inside of a [`Curve`]
+///
+/// ```
+/// # use dessin::{palette::Srgba, prelude::*};
+/// dessin!(
+/// 	Clip<Shape>(
+/// 		clip = dessin!(Rectangle(width = 2., height = 2.)),
+/// 		shape = dessin!(*Circle(radius = 1.5, fill = Srgba::new(1., 0., 0., 1.))),
+/// 	)
+/// );
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Shape)]
+pub struct Clip<T> {
+	/// Clipped `Shape`
+	#[shape(into)]
+	pub shape: T,
+
+	/// Only what is inside this curve is visible
+	#[shape(into)]
+	pub clip: Curve,
+}
+impl<T> Clip<T> {
+	/// Create a new `Clip`
+	#[inline]
+	pub fn new(shape: T, clip: Curve) -> Self {
+		Clip { shape, clip }
+	}
+}
+
+impl<T> Deref for Clip<T> {
+	type Target = T;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.shape
+	}
+}
+
+impl<T> DerefMut for Clip<T> {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.shape
+	}
+}
+
+impl<T: Into<Shape>> From<Clip<T>> for Shape {
+	#[inline]
+	fn from(Clip { shape, clip }: Clip<T>) -> Self {
+		Shape::Clip {
+			clip,
+			shape: Box::new(shape.into()),
+		}
+	}
+}
+
+impl<T: ShapeOp> ShapeOp for Clip<T> {
+	#[inline]
+	fn transform(&mut self, transform_matrix: Transform2<f32>) -> &mut Self {
+		self.shape.transform(transform_matrix);
+		self.clip.transform(transform_matrix);
+		self
+	}
+
+	#[inline]
+	fn local_transform(&self) -> &Transform2<f32> {
+		self.shape.local_transform()
+	}
+}
+
+impl<T: ShapeBoundingBox> ShapeBoundingBox for Clip<T> {
+	#[inline]
+	fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
+		self.shape
+			.local_bounding_box()
+			.straigthen()
+			.intersect(self.clip.local_bounding_box().straigthen())
+			.as_unparticular()
+	}
+}
+
+/// Mask a shape with the luminance or the alpha of another shape
+///
+/// ```
+/// # use dessin::{palette::Srgba, prelude::*};
+/// dessin!(
+/// 	Mask<Shape>(
+/// 		mask = dessin!(*Circle(radius = 1., fill = Srgba::new(1., 1., 1., 1.))),
+/// 		kind = MaskKind::Luminance,
+/// 		shape = dessin!(*Rectangle(width = 3., height = 3., fill = Srgba::new(1., 0., 0., 1.))),
+/// 	)
+/// );
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Shape)]
+pub struct Mask<T> {
+	/// Masked `Shape`
+	#[shape(into)]
+	pub shape: T,
+
+	/// Shape whose luminance or alpha gives the visibility of the masked shape
+	#[shape(into)]
+	pub mask: Shape,
+
+	/// How the mask hides the masked shape
+	pub kind: MaskKind,
+}
+impl<T> Mask<T> {
+	/// Create a new `Mask`
+	#[inline]
+	pub fn new(shape: T, mask: Shape) -> Self {
+		Mask {
+			shape,
+			mask,
+			kind: MaskKind::default(),
+		}
+	}
+}
+
+impl<T> Deref for Mask<T> {
+	type Target = T;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.shape
+	}
+}
+
+impl<T> DerefMut for Mask<T> {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.shape
+	}
+}
+
+impl<T: Into<Shape>> From<Mask<T>> for Shape {
+	#[inline]
+	fn from(Mask { shape, mask, kind }: Mask<T>) -> Self {
+		Shape::Mask {
+			mask: Box::new(mask),
+			kind,
+			shape: Box::new(shape.into()),
+		}
+	}
+}
+
+impl<T: ShapeOp> ShapeOp for Mask<T> {
+	#[inline]
+	fn transform(&mut self, transform_matrix: Transform2<f32>) -> &mut Self {
+		self.shape.transform(transform_matrix);
+		self.mask.transform(transform_matrix);
+		self
+	}
+
+	#[inline]
+	fn local_transform(&self) -> &Transform2<f32> {
+		self.shape.local_transform()
+	}
+}
+
+impl<T: ShapeBoundingBox> ShapeBoundingBox for Mask<T> {
+	#[inline]
+	fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
+		self.shape
+			.local_bounding_box()
+			.straigthen()
+			.intersect(self.mask.local_bounding_box().straigthen())
+			.as_unparticular()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use nalgebra::Point2;
+
+	#[test]
+	fn clip_bounding_box() {
+		let clipped = dessin!(Clip<Shape>(
+			clip = dessin!(Rectangle(width = 2., height = 2.)),
+			shape = dessin!(Circle(radius = 3.)),
+			translate = [1., 0.],
+		));
+
+		let bb = clipped.local_bounding_box().straigthen();
+		assert!((bb.top_left() - Point2::new(0., 1.)).magnitude() < 10e-6);
+		assert!((bb.bottom_right() - Point2::new(2., -1.)).magnitude() < 10e-6);
+	}
+}
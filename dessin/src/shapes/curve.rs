@@ -1,9 +1,22 @@
 mod keypoint;
+mod path_data;
 
-use super::{BoundingBox, ShapeBoundingBox, UnParticular};
+use super::{BoundingBox, ShapeBoundingBox, Straight, UnParticular};
 use crate::shapes::{Shape, ShapeOp};
 pub use keypoint::*;
 use nalgebra::{Point2, Transform2};
+pub use path_data::*;
+
+/// Rule deciding which parts of a curve are inside when subpaths overlap
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillRule {
+	/// Inside if the subpaths wind around the point a non zero number of times
+	#[default]
+	NonZero,
+	/// Inside if a ray from the point crosses the subpaths an odd number of times
+	EvenOdd,
+}
 
 /// Final curve position for render
 #[derive(Debug, Clone, PartialEq)]
@@ -12,10 +25,61 @@ pub struct CurvePosition {
 	pub keypoints: Vec<KeypointPosition>,
 	/// Is the curve closing between last and first point ?
 	pub closed: bool,
+	/// Other subpaths of the curve, each one starting with a move to its first point.
+	///
+	/// Subpaths never have subpaths themselves.
+	pub subpaths: Vec<CurvePosition>,
+	/// Fill rule of the whole curve
+	pub fill_rule: FillRule,
+}
+impl CurvePosition {
+	/// Iterate over all subpaths, starting with the curve itself
+	pub fn iter_subpaths(&self) -> impl Iterator<Item = &CurvePosition> {
+		std::iter::once(self).chain(self.subpaths.iter())
+	}
+
+	/// Same curve, with quadratic beziers and arcs replaced by cubic beziers
+	///
+	/// Meant for exporters whose format only knows cubic beziers.
+	pub fn to_cubics(&self) -> CurvePosition {
+		let mut keypoints = Vec::with_capacity(self.keypoints.len());
+		let mut last = None;
+
+		for keypoint in &self.keypoints {
+			match keypoint {
+				KeypointPosition::Point(p) => {
+					keypoints.push(KeypointPosition::Point(*p));
+					last = Some(*p);
+				}
+				KeypointPosition::Bezier(b) => {
+					keypoints.push(KeypointPosition::Bezier(b.clone()));
+					last = Some(b.end);
+				}
+				KeypointPosition::Quadratic(q) => {
+					let start = q.start.or(last).unwrap_or(q.control);
+					keypoints.push(KeypointPosition::Bezier(q.to_cubic(start)));
+					last = Some(q.end);
+				}
+				KeypointPosition::ArcTo(a) => {
+					let start = a.start.or(last).unwrap_or(a.end);
+					keypoints.extend(a.to_cubics(start).into_iter().map(KeypointPosition::Bezier));
+					last = Some(a.end);
+				}
+			}
+		}
+
+		CurvePosition {
+			keypoints,
+			closed: self.closed,
+			subpaths: self.subpaths.iter().map(CurvePosition::to_cubics).collect(),
+			fill_rule: self.fill_rule,
+		}
+	}
 }
 
 /// Multipoint curve
 #[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Curve {
 	/// Positionming
 	pub local_transform: Transform2<f32>,
@@ -23,6 +87,10 @@ pub struct Curve {
 	pub keypoints: Vec<Keypoint>,
 	/// Is the curve closing between last and first point ?
 	pub closed: bool,
+	/// Other subpaths, drawn with the same style, such as holes
+	pub subpaths: Vec<Curve>,
+	/// Fill rule of the whole curve, subpaths included
+	pub fill_rule: FillRule,
 }
 impl Curve {
 	/// Add points
@@ -72,6 +140,34 @@ impl Curve {
 		self.close(false)
 	}
 
+	/// Add a subpath, starting with a move to its first point
+	#[inline]
+	pub fn subpath<C: Into<Curve>>(&mut self, subpath: C) -> &mut Self {
+		self.subpaths.push(subpath.into());
+		self
+	}
+
+	/// Add a subpath, starting with a move to its first point
+	#[inline]
+	pub fn with_subpath<C: Into<Curve>>(mut self, subpath: C) -> Self {
+		self.subpath(subpath);
+		self
+	}
+
+	/// Change the fill rule
+	#[inline]
+	pub fn fill_rule(&mut self, fill_rule: FillRule) -> &mut Self {
+		self.fill_rule = fill_rule;
+		self
+	}
+
+	/// Change the fill rule
+	#[inline]
+	pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+		self.fill_rule(fill_rule);
+		self
+	}
+
 	/// Reverse points order
 	#[inline]
 	pub fn reverse(&mut self) -> &mut Self {
@@ -84,6 +180,8 @@ impl Curve {
 		match self.keypoints.first() {
 			Some(Keypoint::Point(p)) => Some(*p),
 			Some(Keypoint::Bezier(b)) => b.start,
+			Some(Keypoint::Quadratic(q)) => q.start,
+			Some(Keypoint::ArcTo(a)) => a.start,
 			Some(Keypoint::Curve(c)) => c.start_point(),
 			None => None,
 		}
@@ -98,76 +196,81 @@ impl Curve {
 		c
 	}
 
-	fn _reversed<'a>(&'a self) -> (Self, Option<&'a Bezier>) {
+	fn _reversed<'a>(&'a self) -> (Self, Option<&'a Keypoint>) {
+		/// Start of a segment, if it has one
+		fn segment_start(keypoint: &Keypoint) -> Option<Point2<f32>> {
+			match keypoint {
+				Keypoint::Bezier(b) => b.start,
+				Keypoint::Quadratic(q) => q.start,
+				Keypoint::ArcTo(a) => a.start,
+				Keypoint::Point(_) | Keypoint::Curve(_) => None,
+			}
+		}
+
+		/// End of a segment
+		fn segment_end(keypoint: &Keypoint) -> Point2<f32> {
+			match keypoint {
+				Keypoint::Bezier(b) => b.end,
+				Keypoint::Quadratic(q) => q.end,
+				Keypoint::ArcTo(a) => a.end,
+				Keypoint::Point(p) => *p,
+				Keypoint::Curve(_) => unreachable!("nested curves are not segments"),
+			}
+		}
+
+		/// Same segment the other way round, from its end to `to`
+		fn reversed_to(keypoint: &Keypoint, to: Point2<f32>) -> Keypoint {
+			match keypoint {
+				Keypoint::Bezier(b) => Keypoint::Bezier(Bezier {
+					start: Some(b.end),
+					start_control: b.end_control,
+					end_control: b.start_control,
+					end: to,
+				}),
+				Keypoint::Quadratic(q) => Keypoint::Quadratic(QuadraticBezier {
+					start: Some(q.end),
+					control: q.control,
+					end: to,
+				}),
+				Keypoint::ArcTo(a) => Keypoint::ArcTo(a.reversed_to(to)),
+				Keypoint::Point(_) | Keypoint::Curve(_) => keypoint.clone(),
+			}
+		}
+
 		let mut keypoints = Vec::with_capacity(self.keypoints.len());
 
-		let mut tmp: Option<&'a Bezier> = None;
+		// Segment without start point, waiting for the end of the previous keypoint
+		let mut tmp: Option<&'a Keypoint> = None;
 
 		for k in self.keypoints.iter().rev() {
-			match (k, tmp) {
-				(Keypoint::Point(p), None) => {
-					keypoints.push(Keypoint::Point(*p));
-				}
-				(Keypoint::Point(p), Some(t)) => {
-					keypoints.push(Keypoint::Bezier(Bezier {
-						start: Some(t.end),
-						start_control: t.end_control,
-						end_control: t.start_control,
-						end: *p,
-					}));
-					tmp = None;
-				}
-				(Keypoint::Bezier(b), None) => {
-					if let Some(start) = b.start {
-						keypoints.push(Keypoint::Bezier(Bezier {
-							start: Some(b.end),
-							start_control: b.end_control,
-							end_control: b.start_control,
-							end: start,
-						}));
-					} else {
-						tmp = Some(b);
-					}
-				}
-				(Keypoint::Bezier(b), Some(t)) => {
-					keypoints.push(Keypoint::Bezier(Bezier {
-						start: Some(t.end),
-						start_control: t.end_control,
-						end_control: t.start_control,
-						end: b.end,
-					}));
-					tmp = None;
-
-					if let Some(start) = b.start {
-						keypoints.push(Keypoint::Bezier(Bezier {
-							start: Some(b.end),
-							start_control: b.end_control,
-							end_control: b.start_control,
-							end: start,
-						}));
-					} else {
-						tmp = Some(b);
-					}
-				}
-				(Keypoint::Curve(c), None) => {
+			match k {
+				Keypoint::Point(p) => match tmp.take() {
+					Some(t) => keypoints.push(reversed_to(t, *p)),
+					None => keypoints.push(Keypoint::Point(*p)),
+				},
+				Keypoint::Curve(c) => {
 					let (curve, rest) = c._reversed();
+					match (tmp, curve.start_point()) {
+						(Some(t), Some(start)) => {
+							keypoints.push(reversed_to(t, start));
+							tmp = rest;
+						}
+						(Some(_), None) if rest.is_none() => {
+							// Empty curve, the segment still waits for its start
+						}
+						(Some(_), None) => panic!(""),
+						(None, _) => tmp = rest,
+					}
 					keypoints.push(Keypoint::Curve(curve));
-					tmp = rest;
 				}
-				(Keypoint::Curve(c), Some(t)) => {
-					let (curve, rest) = c._reversed();
-					if let Some(start) = curve.start_point() {
-						keypoints.push(Keypoint::Bezier(Bezier {
-							start: Some(t.end),
-							start_control: t.end_control,
-							end_control: t.start_control,
-							end: start,
-						}));
-						tmp = rest;
-					} else if let None = rest {
-						// Everithing's good here
-					} else {
-						panic!("")
+				segment => {
+					if let Some(t) = tmp.take() {
+						keypoints.push(reversed_to(t, segment_end(segment)));
+					}
+
+					match segment_start(segment) {
+						Some(start) => keypoints.push(reversed_to(segment, start)),
+						None => tmp = Some(segment),
 					}
 				}
 			}
@@ -178,6 +281,8 @@ impl Curve {
 				local_transform: self.local_transform,
 				closed: self.closed,
 				keypoints,
+				subpaths: self.subpaths.iter().map(Curve::reversed).collect(),
+				fill_rule: self.fill_rule,
 			},
 			tmp,
 		)
@@ -199,6 +304,12 @@ impl Curve {
 					Keypoint::Bezier(b) => {
 						vec![KeypointPosition::Bezier(b.transform(&parent_transform))]
 					}
+					Keypoint::Quadratic(q) => {
+						vec![KeypointPosition::Quadratic(q.transform(&parent_transform))]
+					}
+					Keypoint::ArcTo(a) => {
+						vec![KeypointPosition::ArcTo(a.transform(&parent_transform))]
+					}
 					Keypoint::Curve(c) => flatten_curve(c, &parent_transform),
 				})
 				.collect::<Vec<_>>();
@@ -206,9 +317,22 @@ impl Curve {
 			res
 		}
 
+		let global_transform = self.global_transform(parent_transform);
+		let subpaths = self
+			.subpaths
+			.iter()
+			.flat_map(|subpath| {
+				let mut position = subpath.position(&global_transform);
+				let subpaths = std::mem::take(&mut position.subpaths);
+				std::iter::once(position).chain(subpaths)
+			})
+			.collect();
+
 		CurvePosition {
 			keypoints: flatten_curve(self, parent_transform),
 			closed: self.closed,
+			subpaths,
+			fill_rule: self.fill_rule,
 		}
 	}
 }
@@ -231,12 +355,73 @@ impl From<Curve> for Shape {
 	}
 }
 
+/// Join the tight bounding boxes of `keypoints`, following them from `last`
+fn join_bounding_boxes(
+	keypoints: &[Keypoint],
+	transform: &Transform2<f32>,
+	last: &mut Option<Point2<f32>>,
+	bb: &mut Option<BoundingBox<Straight>>,
+) {
+	for keypoint in keypoints {
+		let keypoint_bb = match keypoint {
+			Keypoint::Point(p) => {
+				let p = transform * p;
+				*last = Some(p);
+				BoundingBox::at(p)
+			}
+			Keypoint::Bezier(b) => {
+				let b = b.transform(transform);
+				let keypoint_bb = match b.start.or(*last) {
+					Some(start) => b.bounding_box_from(start),
+					None => Keypoint::Bezier(b.clone()).bounding_box().straigthen(),
+				};
+				*last = Some(b.end);
+				keypoint_bb
+			}
+			Keypoint::Quadratic(q) => {
+				let q = q.transform(transform);
+				let keypoint_bb = match q.start.or(*last) {
+					Some(start) => q.bounding_box_from(start),
+					None => Keypoint::Quadratic(q.clone()).bounding_box().straigthen(),
+				};
+				*last = Some(q.end);
+				keypoint_bb
+			}
+			Keypoint::ArcTo(a) => {
+				let a = a.transform(transform);
+				let keypoint_bb = match a.start.or(*last) {
+					Some(start) => a.bounding_box_from(start),
+					None => BoundingBox::at(a.end),
+				};
+				*last = Some(a.end);
+				keypoint_bb
+			}
+			Keypoint::Curve(c) => {
+				join_bounding_boxes(&c.keypoints, &(transform * c.local_transform), last, bb);
+				continue;
+			}
+		};
+
+		*bb = Some(match bb.take() {
+			Some(acc) => acc.join(keypoint_bb),
+			None => keypoint_bb,
+		});
+	}
+}
+
 impl ShapeBoundingBox for Curve {
 	fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
-		let bb = self
-			.keypoints
-			.iter()
-			.map(|v| v.bounding_box().straigthen())
+		let mut bb = None;
+		join_bounding_boxes(&self.keypoints, &Transform2::identity(), &mut None, &mut bb);
+
+		let bb = bb
+			.into_iter()
+			.chain(
+				self.subpaths
+					.iter()
+					.filter(|v| !v.keypoints.is_empty())
+					.map(|v| v.local_bounding_box().straigthen()),
+			)
 			.reduce(|acc, curr| acc.join(curr))
 			.unwrap_or_else(|| BoundingBox::zero());
 
@@ -267,3 +452,104 @@ where
 		self.clone().into()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use nalgebra::Point2;
+
+	#[test]
+	fn subpaths_position_and_bounding_box() {
+		let ring = dessin!(Curve(
+			then = Point2::new(-2., -2.),
+			then = Point2::new(2., -2.),
+			then = Point2::new(2., 2.),
+			then = Point2::new(-2., 2.),
+			closed,
+			subpath = Curve::default()
+				.with_then(Point2::new(-1., -1.))
+				.with_then(Point2::new(1., -1.))
+				.with_then(Point2::new(1., 1.))
+				.with_close(true),
+			fill_rule = FillRule::EvenOdd,
+			translate = [1., 0.],
+		));
+
+		let position = ring.position(&Default::default());
+		assert_eq!(position.iter_subpaths().count(), 2);
+		assert_eq!(position.fill_rule, FillRule::EvenOdd);
+		assert_eq!(
+			position.subpaths[0].keypoints[0],
+			KeypointPosition::Point(Point2::new(0., -1.))
+		);
+
+		let bb = ring.local_bounding_box().straigthen();
+		assert!((bb.top_left() - Point2::new(-1., 2.)).magnitude() < 10e-6);
+		assert!((bb.bottom_right() - Point2::new(3., -2.)).magnitude() < 10e-6);
+	}
+
+	#[test]
+	fn tight_arc_bounding_box() {
+		// Quarter of a unit circle, from 45° to 135°
+		let arc = dessin!(Arc(
+			start_angle = std::f32::consts::FRAC_PI_4,
+			end_angle = 3. * std::f32::consts::FRAC_PI_4,
+			radius = 1.,
+		))
+		.as_curve();
+
+		let bb = arc.local_bounding_box().straigthen();
+		let half = std::f32::consts::FRAC_1_SQRT_2;
+		assert!((bb.top_left() - Point2::new(-half, 1.)).magnitude() < 10e-6);
+		assert!((bb.bottom_right() - Point2::new(half, half)).magnitude() < 10e-6);
+	}
+
+	#[test]
+	fn quadratic_and_arc() {
+		let curve = dessin!(Curve(
+			then = Point2::new(0., 0.),
+			then = QuadraticBezier::new(Point2::new(1., 2.), Point2::new(2., 0.)),
+			then = EllipticalArc::new(
+				nalgebra::Vector2::new(1., 1.),
+				0.,
+				false,
+				true,
+				Point2::new(4., 0.),
+			),
+		));
+
+		// The arc goes below, counter clockwise
+		let bb = curve.local_bounding_box().straigthen();
+		assert!((bb.top_left() - Point2::new(0., 1.)).magnitude() < 10e-3);
+		assert!((bb.bottom_right() - Point2::new(4., -1.)).magnitude() < 10e-3);
+
+		let reversed = curve.reversed();
+		assert_eq!(
+			reversed.keypoints[0],
+			Keypoint::ArcTo(EllipticalArc::new_with_start(
+				Point2::new(4., 0.),
+				nalgebra::Vector2::new(1., 1.),
+				0.,
+				false,
+				false,
+				Point2::new(2., 0.),
+			))
+		);
+		assert_eq!(
+			reversed.keypoints[1],
+			Keypoint::Quadratic(QuadraticBezier::new_with_start(
+				Point2::new(2., 0.),
+				Point2::new(1., 2.),
+				Point2::new(0., 0.),
+			))
+		);
+		assert_eq!(reversed.local_bounding_box(), curve.local_bounding_box());
+
+		let cubics = curve.position(&Default::default()).to_cubics();
+		assert_eq!(cubics.keypoints.len(), 4);
+		assert!(cubics
+			.keypoints
+			.iter()
+			.all(|k| matches!(k, KeypointPosition::Point(_) | KeypointPosition::Bezier(_))));
+	}
+}
@@ -1,9 +1,11 @@
 use super::Curve;
 use crate::{
-	prelude::{BoundingBox, ShapeBoundingBox, UnParticular},
+	geometry::cubic_point,
+	prelude::{BoundingBox, ShapeBoundingBox, Straight, UnParticular},
 	shapes::ShapeOpWith,
 };
-use nalgebra::{Point2, Transform2, Vector2};
+use nalgebra::{Matrix2, Point2, Rotation2, Transform2, Vector2};
+use std::f32::consts::{FRAC_PI_2, TAU};
 
 /// Final position of a point
 #[derive(Debug, Clone, PartialEq)]
@@ -12,15 +14,24 @@ pub enum KeypointPosition {
 	Point(Point2<f32>),
 	/// Bezier point
 	Bezier(Bezier),
+	/// Quadratic bezier point
+	Quadratic(QuadraticBezier),
+	/// Elliptical arc
+	ArcTo(EllipticalArc),
 }
 
 /// Position of a point
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Keypoint {
 	/// 2d point
 	Point(Point2<f32>),
 	/// Bezier point
 	Bezier(Bezier),
+	/// Quadratic bezier point
+	Quadratic(QuadraticBezier),
+	/// Elliptical arc
+	ArcTo(EllipticalArc),
 	/// Nested curve
 	Curve(Curve),
 }
@@ -30,6 +41,8 @@ impl Keypoint {
 		match self {
 			Keypoint::Point(p) => Keypoint::Point(parent_transform * p),
 			Keypoint::Bezier(b) => Keypoint::Bezier(b.transform(parent_transform)),
+			Keypoint::Quadratic(q) => Keypoint::Quadratic(q.transform(parent_transform)),
+			Keypoint::ArcTo(a) => Keypoint::ArcTo(a.transform(parent_transform)),
 			Keypoint::Curve(c) => Keypoint::Curve(c.clone().with_transform(*parent_transform)),
 		}
 	}
@@ -39,17 +52,25 @@ impl Keypoint {
 		match self {
 			Keypoint::Curve(c) => c.local_bounding_box(),
 			Keypoint::Point(p) => BoundingBox::at(*p).as_unparticular(),
-			Keypoint::Bezier(b) => {
-				let mut bb = BoundingBox::at(b.start_control)
+			Keypoint::Bezier(b) => match b.start {
+				Some(start) => b.bounding_box_from(start).as_unparticular(),
+				// Without its start point, the control points are the best guess
+				None => BoundingBox::at(b.start_control)
 					.join(BoundingBox::at(b.end_control))
-					.join(BoundingBox::at(b.end));
-
-				if let Some(start) = b.start {
-					bb = bb.join(BoundingBox::at(start))
-				}
-
-				bb.as_unparticular()
-			}
+					.join(BoundingBox::at(b.end))
+					.as_unparticular(),
+			},
+			Keypoint::Quadratic(q) => match q.start {
+				Some(start) => q.bounding_box_from(start).as_unparticular(),
+				None => BoundingBox::at(q.control)
+					.join(BoundingBox::at(q.end))
+					.as_unparticular(),
+			},
+			// Without its start point, the arc can't be placed
+			Keypoint::ArcTo(a) => match a.start {
+				Some(start) => a.bounding_box_from(start).as_unparticular(),
+				None => BoundingBox::at(a.end).as_unparticular(),
+			},
 		}
 	}
 }
@@ -62,6 +83,7 @@ impl Default for Keypoint {
 
 /// Cubic bezier point
 #[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bezier {
 	/// Move first point
 	pub start: Option<Point2<f32>>,
@@ -129,6 +151,45 @@ impl Bezier {
 		}
 	}
 
+	/// Tight bounding box, starting at `start` if the bezier has no start point
+	pub fn bounding_box_from(&self, start: Point2<f32>) -> BoundingBox<Straight> {
+		let start = self.start.unwrap_or(start);
+		let control = [start, self.start_control, self.end_control, self.end];
+
+		// Extrema are where the derivative, a quadratic, is zero on one of the axes
+		let a = -start.coords + 3. * self.start_control.coords - 3. * self.end_control.coords
+			+ self.end.coords;
+		let b = 2. * (start.coords - 2. * self.start_control.coords + self.end_control.coords);
+		let c = self.start_control.coords - start.coords;
+
+		let mut roots = vec![];
+		for axis in 0..2 {
+			let (a, b, c) = (a[axis], b[axis], c[axis]);
+			let scale = a.abs() + b.abs() + c.abs();
+			if a.abs() <= 1e-6 * scale {
+				if b.abs() > 1e-6 * scale {
+					roots.push(-c / b);
+				}
+			} else {
+				let delta = b * b - 4. * a * c;
+				if delta >= 0. {
+					let sqrt_delta = delta.sqrt();
+					roots.push((-b + sqrt_delta) / (2. * a));
+					roots.push((-b - sqrt_delta) / (2. * a));
+				}
+			}
+		}
+
+		roots
+			.into_iter()
+			.filter(|t| *t > 0. && *t < 1.)
+			.map(|t| BoundingBox::at(cubic_point(control, t)))
+			.fold(
+				BoundingBox::at(start).join(BoundingBox::at(self.end)),
+				BoundingBox::join,
+			)
+	}
+
 	/// Transform
 	pub fn transform(&self, parent_transform: &Transform2<f32>) -> Self {
 		Bezier {
@@ -140,6 +201,271 @@ impl Bezier {
 	}
 }
 
+/// Quadratic bezier point
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadraticBezier {
+	/// Move first point
+	pub start: Option<Point2<f32>>,
+	/// Control point
+	pub control: Point2<f32>,
+	/// End point
+	pub end: Point2<f32>,
+}
+impl QuadraticBezier {
+	/// New with a start point
+	pub fn new_with_start(start: Point2<f32>, control: Point2<f32>, end: Point2<f32>) -> Self {
+		QuadraticBezier {
+			start: Some(start),
+			control,
+			end,
+		}
+	}
+
+	/// New without a start point
+	pub fn new(control: Point2<f32>, end: Point2<f32>) -> Self {
+		QuadraticBezier {
+			start: None,
+			control,
+			end,
+		}
+	}
+
+	/// Same curve as a cubic bezier, starting at `start` if the bezier has no start point
+	pub fn to_cubic(&self, start: Point2<f32>) -> Bezier {
+		let from = self.start.unwrap_or(start);
+		Bezier {
+			start: self.start,
+			start_control: from + (self.control - from) * (2. / 3.),
+			end_control: self.end + (self.control - self.end) * (2. / 3.),
+			end: self.end,
+		}
+	}
+
+	/// Tight bounding box, starting at `start` if the bezier has no start point
+	pub fn bounding_box_from(&self, start: Point2<f32>) -> BoundingBox<Straight> {
+		self.to_cubic(start).bounding_box_from(start)
+	}
+
+	/// Transform
+	pub fn transform(&self, parent_transform: &Transform2<f32>) -> Self {
+		QuadraticBezier {
+			start: self.start.map(|v| parent_transform * v),
+			control: parent_transform * self.control,
+			end: parent_transform * self.end,
+		}
+	}
+}
+
+/// Elliptical arc point, from the previous point to `end`, as in SVG
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EllipticalArc {
+	/// Move first point
+	pub start: Option<Point2<f32>>,
+	/// Radii of the ellipse along its axes, scaled up if too small to reach `end`
+	pub radii: Vector2<f32>,
+	/// Rotation of the first axis of the ellipse, in radians
+	pub rotation: f32,
+	/// Take the longest of the two possible arcs
+	pub large_arc: bool,
+	/// Go in the direction of positive angles, counter clockwise when y goes up
+	pub sweep: bool,
+	/// End point
+	pub end: Point2<f32>,
+}
+impl EllipticalArc {
+	/// New with a start point
+	pub fn new_with_start(
+		start: Point2<f32>,
+		radii: Vector2<f32>,
+		rotation: f32,
+		large_arc: bool,
+		sweep: bool,
+		end: Point2<f32>,
+	) -> Self {
+		EllipticalArc {
+			start: Some(start),
+			..EllipticalArc::new(radii, rotation, large_arc, sweep, end)
+		}
+	}
+
+	/// New without a start point
+	pub fn new(
+		radii: Vector2<f32>,
+		rotation: f32,
+		large_arc: bool,
+		sweep: bool,
+		end: Point2<f32>,
+	) -> Self {
+		EllipticalArc {
+			start: None,
+			radii,
+			rotation,
+			large_arc,
+			sweep,
+			end,
+		}
+	}
+
+	/// Same arc the other way round, from `end` to `to`
+	pub(crate) fn reversed_to(&self, to: Point2<f32>) -> Self {
+		EllipticalArc {
+			start: Some(self.end),
+			sweep: !self.sweep,
+			end: to,
+			..self.clone()
+		}
+	}
+
+	/// Center, radii, start angle and swept angle of the arc, starting at `start` if the arc has no start point.
+	///
+	/// `None` if the arc is a straight line, as when a radius is zero.
+	fn center_parameters(
+		&self,
+		start: Point2<f32>,
+	) -> Option<(Point2<f32>, Vector2<f32>, f32, f32)> {
+		let from = self.start.unwrap_or(start);
+		let (mut rx, mut ry) = (self.radii.x.abs(), self.radii.y.abs());
+		if from == self.end || rx <= f32::EPSILON || ry <= f32::EPSILON {
+			return None;
+		}
+
+		// See https://www.w3.org/TR/SVG11/implnote.html#ArcConversionEndpointToCenter
+		let rotation = Rotation2::new(self.rotation);
+		let p = rotation.inverse() * ((from - self.end) / 2.);
+
+		// Radii too small to reach the end are scaled up
+		let lambda = (p.x * p.x) / (rx * rx) + (p.y * p.y) / (ry * ry);
+		if lambda > 1. {
+			rx *= lambda.sqrt();
+			ry *= lambda.sqrt();
+		}
+
+		let num = rx * rx * ry * ry - rx * rx * p.y * p.y - ry * ry * p.x * p.x;
+		let den = rx * rx * p.y * p.y + ry * ry * p.x * p.x;
+		let sign = if self.large_arc != self.sweep {
+			1.
+		} else {
+			-1.
+		};
+		let coef = sign * (num / den).max(0.).sqrt();
+		let c = Vector2::new(coef * rx * p.y / ry, -coef * ry * p.x / rx);
+
+		let center = rotation * c + (from.coords + self.end.coords) / 2.;
+
+		let angle = |v: Vector2<f32>| v.y.atan2(v.x);
+		let start_angle = angle(Vector2::new((p.x - c.x) / rx, (p.y - c.y) / ry));
+		let end_angle = angle(Vector2::new((-p.x - c.x) / rx, (-p.y - c.y) / ry));
+
+		let mut sweep_angle = (end_angle - start_angle).rem_euclid(TAU);
+		if !self.sweep && sweep_angle > 0. {
+			sweep_angle -= TAU;
+		}
+
+		Some((
+			center.into(),
+			Vector2::new(rx, ry),
+			start_angle,
+			sweep_angle,
+		))
+	}
+
+	/// Same arc as cubic beziers, starting at `start` if the arc has no start point
+	///
+	/// Only the first bezier has the start point of the arc.
+	pub fn to_cubics(&self, start: Point2<f32>) -> Vec<Bezier> {
+		let from = self.start.unwrap_or(start);
+		let Some((center, radii, start_angle, sweep_angle)) = self.center_parameters(start) else {
+			return vec![Bezier {
+				start: self.start,
+				start_control: from,
+				end_control: self.end,
+				end: self.end,
+			}];
+		};
+
+		let rotation = Rotation2::new(self.rotation);
+		let point = |angle: f32| {
+			center + rotation * Vector2::new(radii.x * angle.cos(), radii.y * angle.sin())
+		};
+		let derivative =
+			|angle: f32| rotation * Vector2::new(-radii.x * angle.sin(), radii.y * angle.cos());
+
+		// At most a quarter of the ellipse per bezier
+		let n = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.) as usize;
+		let step = sweep_angle / n as f32;
+		let k = 4. / 3. * (step / 4.).tan();
+
+		(0..n)
+			.map(|i| {
+				let (a, b) = (
+					start_angle + step * i as f32,
+					start_angle + step * (i + 1) as f32,
+				);
+				let p0 = if i == 0 { from } else { point(a) };
+				let p3 = if i == n - 1 { self.end } else { point(b) };
+				Bezier {
+					start: if i == 0 { self.start } else { None },
+					start_control: p0 + derivative(a) * k,
+					end_control: p3 - derivative(b) * k,
+					end: p3,
+				}
+			})
+			.collect()
+	}
+
+	/// Bounding box, starting at `start` if the arc has no start point
+	pub fn bounding_box_from(&self, start: Point2<f32>) -> BoundingBox<Straight> {
+		self.to_cubics(start)
+			.into_iter()
+			.fold(BoundingBox::at(self.start.unwrap_or(start)), |bb, b| {
+				bb.join(b.bounding_box_from(start))
+			})
+	}
+
+	/// Transform
+	///
+	/// Radii and rotation follow the transform, the sweep is reversed by mirroring transforms.
+	pub fn transform(&self, parent_transform: &Transform2<f32>) -> Self {
+		let m = parent_transform.matrix();
+		let linear = Matrix2::new(m[(0, 0)], m[(0, 1)], m[(1, 0)], m[(1, 1)]);
+
+		// Axes of the transformed ellipse
+		let axes = linear
+			* Rotation2::new(self.rotation).matrix()
+			* Matrix2::new(self.radii.x, 0., 0., self.radii.y);
+		let svd = axes.svd(true, false);
+		let rotation = svd
+			.u
+			.map(|u| u[(1, 0)].atan2(u[(0, 0)]))
+			.unwrap_or(self.rotation);
+
+		EllipticalArc {
+			start: self.start.map(|v| parent_transform * v),
+			radii: Vector2::new(svd.singular_values[0], svd.singular_values[1]),
+			rotation,
+			large_arc: self.large_arc,
+			sweep: self.sweep != (linear.determinant() < 0.),
+			end: parent_transform * self.end,
+		}
+	}
+}
+
+impl From<QuadraticBezier> for Keypoint {
+	#[inline]
+	fn from(v: QuadraticBezier) -> Self {
+		Keypoint::Quadratic(v)
+	}
+}
+
+impl From<EllipticalArc> for Keypoint {
+	#[inline]
+	fn from(v: EllipticalArc) -> Self {
+		Keypoint::ArcTo(v)
+	}
+}
+
 impl From<Bezier> for Keypoint {
 	#[inline]
 	fn from(v: Bezier) -> Self {
@@ -199,4 +525,82 @@ mod tests {
 			Point2::new(0., 0.),
 		);
 	}
+
+	#[test]
+	fn tight_bounding_box() {
+		let b = Bezier::new_with_start(
+			Point2::new(0., 0.),
+			Point2::new(0., 4.),
+			Point2::new(4., 4.),
+			Point2::new(4., 0.),
+		);
+
+		let bb = Keypoint::Bezier(b).bounding_box().straigthen();
+		assert!((bb.top_left() - Point2::new(0., 3.)).magnitude() < EPS);
+		assert!((bb.bottom_right() - Point2::new(4., 0.)).magnitude() < EPS);
+	}
+
+	#[test]
+	fn quadratic_bounding_box() {
+		let q = QuadraticBezier::new_with_start(
+			Point2::new(0., 0.),
+			Point2::new(2., 4.),
+			Point2::new(4., 0.),
+		);
+
+		let bb = Keypoint::Quadratic(q).bounding_box().straigthen();
+		assert!((bb.top_left() - Point2::new(0., 2.)).magnitude() < EPS);
+		assert!((bb.bottom_right() - Point2::new(4., 0.)).magnitude() < EPS);
+	}
+
+	#[test]
+	fn arc_to_cubics() {
+		// Upper half of the circle of radius 2 around (2, 0)
+		let arc = EllipticalArc::new(Vector2::new(2., 2.), 0., false, true, Point2::new(0., 0.));
+
+		let cubics = arc.to_cubics(Point2::new(4., 0.));
+		assert_eq!(cubics.len(), 2);
+		assert!((cubics[0].end - Point2::new(2., 2.)).magnitude() < 10e-5);
+		assert_eq!(cubics[1].end, Point2::new(0., 0.));
+
+		let bb = arc.bounding_box_from(Point2::new(4., 0.));
+		assert!((bb.top_left() - Point2::new(0., 2.)).magnitude() < 10e-3);
+		assert!((bb.bottom_right() - Point2::new(4., 0.)).magnitude() < 10e-3);
+
+		// Radii too small are scaled up to reach the end
+		let small = EllipticalArc {
+			radii: Vector2::new(1., 1.),
+			..arc.clone()
+		};
+		assert!(
+			(small.to_cubics(Point2::new(4., 0.))[0].end - Point2::new(2., 2.)).magnitude() < 10e-5
+		);
+
+		// Other sweep goes below
+		let below = EllipticalArc {
+			sweep: false,
+			..arc
+		};
+		assert!(
+			(below.to_cubics(Point2::new(4., 0.))[0].end - Point2::new(2., -2.)).magnitude()
+				< 10e-5
+		);
+	}
+
+	#[test]
+	fn arc_transform() {
+		let arc = EllipticalArc::new(Vector2::new(2., 1.), 0., true, true, Point2::new(0., 1.));
+
+		let t: Transform2<f32> = nalgebra::convert(nalgebra::Rotation2::new(FRAC_PI_2));
+		let rotated = arc.transform(&t);
+		assert!((rotated.radii - Vector2::new(2., 1.)).magnitude() < EPS);
+		assert!((rotated.rotation.rem_euclid(std::f32::consts::PI) - FRAC_PI_2).abs() < EPS);
+		assert!(rotated.sweep);
+
+		let t: Transform2<f32> = nalgebra::convert(nalgebra::Scale2::new(1., -3.));
+		let mirrored = arc.transform(&t);
+		assert!((mirrored.radii - Vector2::new(3., 2.)).magnitude() < EPS);
+		assert!(!mirrored.sweep);
+		assert_eq!(mirrored.end, Point2::new(0., -3.));
+	}
 }
@@ -0,0 +1,433 @@
+use super::{Bezier, Curve, EllipticalArc, Keypoint, QuadraticBezier};
+use nalgebra::{Point2, Vector2};
+use std::{fmt, str::FromStr};
+
+/// Error while reading SVG path data, see [`Curve::from_svg_path_data`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathDataError {
+	/// The path draws something before its first move
+	MissingMoveTo {
+		/// Offset of the command in the path data
+		position: usize,
+		/// Drawing command
+		command: char,
+	},
+	/// A character which is neither a command, a number nor a separator
+	UnexpectedCharacter {
+		/// Offset of the character in the path data
+		position: usize,
+		/// Unexpected character
+		character: char,
+	},
+	/// A command isn't followed by all its numbers
+	MissingArgument {
+		/// Offset where the number was expected in the path data
+		position: usize,
+		/// Command needing the number
+		command: char,
+	},
+	/// An arc flag is something else than `0` or `1`
+	InvalidFlag {
+		/// Offset of the flag in the path data
+		position: usize,
+	},
+}
+impl fmt::Display for PathDataError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PathDataError::MissingMoveTo { position, command } => write!(
+				f,
+				"path data must start with a move, found `{command}` at {position}"
+			),
+			PathDataError::UnexpectedCharacter {
+				position,
+				character,
+			} => write!(f, "unexpected character `{character}` at {position}"),
+			PathDataError::MissingArgument { position, command } => {
+				write!(f, "missing number at {position} for command `{command}`")
+			}
+			PathDataError::InvalidFlag { position } => {
+				write!(f, "arc flag at {position} must be 0 or 1")
+			}
+		}
+	}
+}
+impl std::error::Error for PathDataError {}
+
+/// Reads numbers and commands out of path data
+struct Reader<'a> {
+	data: &'a [u8],
+	position: usize,
+}
+impl Reader<'_> {
+	fn skip_separators(&mut self) {
+		while let Some(c) = self.data.get(self.position) {
+			if !(c.is_ascii_whitespace() || *c == b',') {
+				break;
+			}
+			self.position += 1;
+		}
+	}
+
+	fn peek(&mut self) -> Option<u8> {
+		self.skip_separators();
+		self.data.get(self.position).copied()
+	}
+
+	fn next_is_number(&mut self) -> bool {
+		matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'-' | b'+'))
+	}
+
+	fn number(&mut self, command: u8) -> Result<f32, PathDataError> {
+		self.skip_separators();
+		let start = self.position;
+		let missing = PathDataError::MissingArgument {
+			position: start,
+			command: command as char,
+		};
+
+		let digits = |reader: &mut Self| {
+			let from = reader.position;
+			while reader
+				.data
+				.get(reader.position)
+				.is_some_and(u8::is_ascii_digit)
+			{
+				reader.position += 1;
+			}
+			reader.position - from
+		};
+		let sign = |reader: &mut Self| {
+			if matches!(reader.data.get(reader.position), Some(b'-' | b'+')) {
+				reader.position += 1;
+			}
+		};
+
+		sign(self);
+		let mut count = digits(self);
+		if self.data.get(self.position) == Some(&b'.') {
+			self.position += 1;
+			count += digits(self);
+		}
+		if count == 0 {
+			self.position = start;
+			return Err(missing);
+		}
+
+		// Exponent, unless the `e` is something else
+		if matches!(self.data.get(self.position), Some(b'e' | b'E')) {
+			let before = self.position;
+			self.position += 1;
+			sign(self);
+			if digits(self) == 0 {
+				self.position = before;
+			}
+		}
+
+		std::str::from_utf8(&self.data[start..self.position])
+			.ok()
+			.and_then(|v| v.parse().ok())
+			.ok_or(missing)
+	}
+
+	fn point(&mut self, command: u8) -> Result<Point2<f32>, PathDataError> {
+		Ok(Point2::new(self.number(command)?, self.number(command)?))
+	}
+
+	fn flag(&mut self, command: u8) -> Result<bool, PathDataError> {
+		self.skip_separators();
+		match self.data.get(self.position) {
+			Some(b'0') => {
+				self.position += 1;
+				Ok(false)
+			}
+			Some(b'1') => {
+				self.position += 1;
+				Ok(true)
+			}
+			Some(_) => Err(PathDataError::InvalidFlag {
+				position: self.position,
+			}),
+			None => Err(PathDataError::MissingArgument {
+				position: self.position,
+				command: command as char,
+			}),
+		}
+	}
+}
+
+/// Last control point, reflected by the smooth commands
+#[derive(Clone, Copy)]
+enum LastControl {
+	None,
+	Cubic(Point2<f32>),
+	Quadratic(Point2<f32>),
+}
+
+impl Curve {
+	/// Read the `d` attribute of an SVG `path`
+	///
+	/// All commands are supported, absolute and relative. Each move after the first one starts a subpath.
+	/// Coordinates are kept as is: the y axis of SVG goes down, use `scale = [1., -1.]` to turn them upside up.
+	///
+	/// ```
+	/// # use dessin::prelude::*;
+	/// let arrow = Curve::from_svg_path_data("M 0 0 L 10 0 l -2 -2 m 2 2 l -2 2").unwrap();
+	/// assert_eq!(arrow.subpaths.len(), 1);
+	///
+	/// let icon = dessin!({ arrow }(scale = [1., -1.]));
+	/// ```
+	pub fn from_svg_path_data(data: &str) -> Result<Curve, PathDataError> {
+		let mut reader = Reader {
+			data: data.as_bytes(),
+			position: 0,
+		};
+
+		let mut subpaths: Vec<Curve> = vec![];
+		let mut current: Option<Curve> = None;
+		let mut point = Point2::origin();
+		let mut subpath_start: Option<Point2<f32>> = None;
+		let mut last_control = LastControl::None;
+		let mut command: Option<u8> = None;
+
+		while let Some(c) = reader.peek() {
+			let position = reader.position;
+			if c.is_ascii_alphabetic() {
+				reader.position += 1;
+				command = Some(c);
+			} else {
+				// Numbers repeat the last command, moves being followed by lines
+				command = match command {
+					_ if !reader.next_is_number() => None,
+					Some(b'M') => Some(b'L'),
+					Some(b'm') => Some(b'l'),
+					Some(b'Z' | b'z') => None,
+					other => other,
+				};
+			}
+
+			let Some(c) = command else {
+				return Err(PathDataError::UnexpectedCharacter {
+					position,
+					character: data[position..].chars().next().unwrap_or_default(),
+				});
+			};
+			let relative = c.is_ascii_lowercase();
+			let origin = if relative {
+				point.coords
+			} else {
+				Vector2::zeros()
+			};
+
+			if !matches!(c, b'M' | b'm') {
+				// Drawing right after a close starts a subpath at the same place
+				let Some(start) = subpath_start else {
+					return Err(PathDataError::MissingMoveTo {
+						position,
+						command: c as char,
+					});
+				};
+				if current.is_none() && !matches!(c, b'Z' | b'z') {
+					current = Some(Curve::default().with_then(start));
+				}
+			}
+
+			let keypoint: Keypoint = match c.to_ascii_uppercase() {
+				b'M' => {
+					subpaths.extend(current.take());
+					point = reader.point(c)? + origin;
+					subpath_start = Some(point);
+					current = Some(Curve::default().with_then(point));
+					last_control = LastControl::None;
+					continue;
+				}
+				b'Z' => {
+					if let Some(mut curve) = current.take() {
+						curve.closed = true;
+						subpaths.push(curve);
+					}
+					point = subpath_start.unwrap_or(point);
+					last_control = LastControl::None;
+					continue;
+				}
+				b'L' => {
+					point = reader.point(c)? + origin;
+					last_control = LastControl::None;
+					point.into()
+				}
+				b'H' => {
+					point.x = reader.number(c)? + origin.x;
+					last_control = LastControl::None;
+					point.into()
+				}
+				b'V' => {
+					point.y = reader.number(c)? + origin.y;
+					last_control = LastControl::None;
+					point.into()
+				}
+				b'C' | b'S' => {
+					let start_control = if c.eq_ignore_ascii_case(&b'C') {
+						reader.point(c)? + origin
+					} else {
+						match last_control {
+							LastControl::Cubic(control) => point + (point - control),
+							_ => point,
+						}
+					};
+					let end_control = reader.point(c)? + origin;
+					point = reader.point(c)? + origin;
+					last_control = LastControl::Cubic(end_control);
+					Bezier::new(start_control, end_control, point).into()
+				}
+				b'Q' | b'T' => {
+					let control = if c.eq_ignore_ascii_case(&b'Q') {
+						reader.point(c)? + origin
+					} else {
+						match last_control {
+							LastControl::Quadratic(control) => point + (point - control),
+							_ => point,
+						}
+					};
+					point = reader.point(c)? + origin;
+					last_control = LastControl::Quadratic(control);
+					QuadraticBezier::new(control, point).into()
+				}
+				b'A' => {
+					let radii = Vector2::new(reader.number(c)?, reader.number(c)?);
+					let rotation = reader.number(c)?.to_radians();
+					let large_arc = reader.flag(c)?;
+					let sweep = reader.flag(c)?;
+					point = reader.point(c)? + origin;
+					last_control = LastControl::None;
+					EllipticalArc::new(radii, rotation, large_arc, sweep, point).into()
+				}
+				_ => {
+					return Err(PathDataError::UnexpectedCharacter {
+						position,
+						character: c as char,
+					})
+				}
+			};
+
+			if let Some(curve) = &mut current {
+				curve.then(keypoint);
+			}
+		}
+		subpaths.extend(current);
+
+		// A move alone draws nothing
+		let mut subpaths = subpaths
+			.into_iter()
+			.filter(|subpath| subpath.closed || subpath.keypoints.len() > 1);
+
+		let mut curve = subpaths.next().unwrap_or_default();
+		curve.subpaths.extend(subpaths);
+		Ok(curve)
+	}
+}
+
+impl FromStr for Curve {
+	type Err = PathDataError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Curve::from_svg_path_data(s)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn p(x: f32, y: f32) -> Keypoint {
+		Keypoint::Point(Point2::new(x, y))
+	}
+
+	#[test]
+	fn lines_and_subpaths() {
+		let curve = Curve::from_svg_path_data("M10,10 h 5 V20 L0 0z m1 1 2-2 3.5.5").unwrap();
+
+		assert_eq!(
+			curve.keypoints,
+			vec![p(10., 10.), p(15., 10.), p(15., 20.), p(0., 0.)]
+		);
+		assert!(curve.closed);
+
+		// Relative to the start of the closed subpath
+		assert_eq!(curve.subpaths.len(), 1);
+		assert_eq!(
+			curve.subpaths[0].keypoints,
+			vec![p(11., 11.), p(13., 9.), p(16.5, 9.5)]
+		);
+		assert!(!curve.subpaths[0].closed);
+	}
+
+	#[test]
+	fn curves() {
+		let curve = Curve::from_svg_path_data(
+			"M0 0 C 0 1 1 2 2 2 s 2 -1 2 -2 Q 5 -1 6 0 t 2 0 A 1 1 0 0 1 10 0 a1 1 30 104 0",
+		)
+		.unwrap();
+
+		assert_eq!(
+			curve.keypoints,
+			vec![
+				p(0., 0.),
+				Bezier::new(
+					Point2::new(0., 1.),
+					Point2::new(1., 2.),
+					Point2::new(2., 2.)
+				)
+				.into(),
+				Bezier::new(
+					Point2::new(3., 2.),
+					Point2::new(4., 1.),
+					Point2::new(4., 0.)
+				)
+				.into(),
+				QuadraticBezier::new(Point2::new(5., -1.), Point2::new(6., 0.)).into(),
+				QuadraticBezier::new(Point2::new(7., 1.), Point2::new(8., 0.)).into(),
+				EllipticalArc::new(Vector2::new(1., 1.), 0., false, true, Point2::new(10., 0.))
+					.into(),
+				EllipticalArc::new(
+					Vector2::new(1., 1.),
+					30f32.to_radians(),
+					true,
+					false,
+					Point2::new(14., 0.)
+				)
+				.into(),
+			]
+		);
+	}
+
+	#[test]
+	fn errors() {
+		assert_eq!(
+			Curve::from_svg_path_data("L 1 1"),
+			Err(PathDataError::MissingMoveTo {
+				position: 0,
+				command: 'L'
+			})
+		);
+		assert_eq!(
+			Curve::from_svg_path_data("M 1 1 L 2"),
+			Err(PathDataError::MissingArgument {
+				position: 9,
+				command: 'L'
+			})
+		);
+		assert_eq!(
+			Curve::from_svg_path_data("M 1 1 X"),
+			Err(PathDataError::UnexpectedCharacter {
+				position: 6,
+				character: 'X'
+			})
+		);
+		assert_eq!(
+			Curve::from_svg_path_data("M 1 1 A 1 1 0 2 0 3 3"),
+			Err(PathDataError::InvalidFlag { position: 14 })
+		);
+
+		assert_eq!(Curve::from_svg_path_data(""), Ok(Curve::default()));
+	}
+}
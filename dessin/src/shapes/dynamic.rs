@@ -118,7 +118,11 @@ fn dynamic() {
 			unimplemented!()
 		}
 
-		fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> {
+		fn export_ellipse(
+			&mut self,
+			ellipse: EllipsePosition,
+			_: StylePosition,
+		) -> Result<(), Self::Error> {
 			assert_eq!(ellipse.center, Point2::new(0., 0.));
 			assert_eq!(ellipse.semi_major_axis, self.0);
 			assert_eq!(ellipse.semi_minor_axis, self.0);
@@ -128,12 +132,16 @@ fn dynamic() {
 		fn export_curve(
 			&mut self,
 			_curve: CurvePosition,
-			StylePosition { fill, stroke }: StylePosition,
+			_: StylePosition,
 		) -> Result<(), Self::Error> {
 			unimplemented!()
 		}
 
-		fn export_text(&mut self, _text: TextPosition) -> Result<(), Self::Error> {
+		fn export_text(
+			&mut self,
+			_text: TextPosition,
+			_: StylePosition,
+		) -> Result<(), Self::Error> {
 			unimplemented!()
 		}
 	}
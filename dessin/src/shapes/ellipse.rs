@@ -21,6 +21,7 @@ pub struct EllipsePosition {
 }
 
 #[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 ///
 pub struct Ellipse {
 	///
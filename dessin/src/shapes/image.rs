@@ -30,9 +30,11 @@ pub struct ImagePosition<'a> {
 }
 
 #[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 ///
 pub struct Image {
 	///
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::encoded_image"))]
 	pub image: DynamicImage,
 	///
 	pub local_transform: Transform2<f32>,
@@ -1,44 +1,277 @@
 /// Font storage
 pub mod font;
+mod outline;
+mod shaping;
+pub use shaping::*;
 
 use crate::prelude::*;
 use font::FontRef;
 use na::{Point2, Unit, Vector2};
 use nalgebra::{self as na, Transform2};
+use std::collections::{BTreeSet, HashSet};
+
+pub(crate) fn size_of(
+	font: Option<&FontRef>,
+	properties: FontProperties,
+	s: &str,
+	font_size: f32,
+	letter_spacing: f32,
+	word_spacing: f32,
+) -> f32 {
+	let mut runs = shape_runs(font, properties, s, font_size);
+	space_runs(&mut runs, s, letter_spacing, word_spacing);
+	runs.iter().map(|run| run.width).sum()
+}
+
+/// Thickness of the strokes of a font, from 100 (thin) to 900 (black)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontWeight(pub u16);
+impl FontWeight {
+	/// Thin, 100
+	pub const THIN: FontWeight = FontWeight(100);
+	/// Extra light, 200
+	pub const EXTRA_LIGHT: FontWeight = FontWeight(200);
+	/// Light, 300
+	pub const LIGHT: FontWeight = FontWeight(300);
+	/// Regular, 400
+	pub const REGULAR: FontWeight = FontWeight(400);
+	/// Medium, 500
+	pub const MEDIUM: FontWeight = FontWeight(500);
+	/// Semi bold, 600
+	pub const SEMI_BOLD: FontWeight = FontWeight(600);
+	/// Bold, 700
+	pub const BOLD: FontWeight = FontWeight(700);
+	/// Extra bold, 800
+	pub const EXTRA_BOLD: FontWeight = FontWeight(800);
+	/// Black, 900
+	pub const BLACK: FontWeight = FontWeight(900);
+}
+impl Default for FontWeight {
+	fn default() -> Self {
+		FontWeight::REGULAR
+	}
+}
+
+/// Slant of a font
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStyle {
+	#[default]
+	/// Upright
+	Normal,
+	/// Cursive glyphs, drawn for slanted text
+	Italic,
+	/// Upright glyphs, slanted
+	Oblique,
+}
+
+/// Width of the glyphs of a font
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStretch {
+	/// 50%
+	UltraCondensed,
+	/// 62.5%
+	ExtraCondensed,
+	/// 75%
+	Condensed,
+	/// 87.5%
+	SemiCondensed,
+	#[default]
+	/// 100%
+	Normal,
+	/// 112.5%
+	SemiExpanded,
+	/// 125%
+	Expanded,
+	/// 150%
+	ExtraExpanded,
+	/// 200%
+	UltraExpanded,
+}
+impl FontStretch {
+	/// Width, as a percentage of the normal width
+	pub fn percentage(self) -> f32 {
+		match self {
+			FontStretch::UltraCondensed => 50.,
+			FontStretch::ExtraCondensed => 62.5,
+			FontStretch::Condensed => 75.,
+			FontStretch::SemiCondensed => 87.5,
+			FontStretch::Normal => 100.,
+			FontStretch::SemiExpanded => 112.5,
+			FontStretch::Expanded => 125.,
+			FontStretch::ExtraExpanded => 150.,
+			FontStretch::UltraExpanded => 200.,
+		}
+	}
+}
 
-pub(crate) fn size_of(font: &fontdue::Font, s: &str, font_size: f32) -> f32 {
-	s.chars()
-		.scan(None, |last, curr| {
-			let l = last.unwrap_or(' ');
-			let r = if let Some(v) = font.horizontal_kern(l, curr, font_size) {
-				v
+/// Weight, style and stretch, selecting a face in a [`FontGroup`][font::FontGroup]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontProperties {
+	/// Weight
+	pub weight: FontWeight,
+	/// Style
+	pub style: FontStyle,
+	/// Stretch
+	pub stretch: FontStretch,
+}
+impl FontProperties {
+	/// Regular face
+	pub const REGULAR: FontProperties = FontProperties {
+		weight: FontWeight::REGULAR,
+		style: FontStyle::Normal,
+		stretch: FontStretch::Normal,
+	};
+	/// Bold face
+	pub const BOLD: FontProperties = FontProperties {
+		weight: FontWeight::BOLD,
+		..FontProperties::REGULAR
+	};
+	/// Italic face
+	pub const ITALIC: FontProperties = FontProperties {
+		style: FontStyle::Italic,
+		..FontProperties::REGULAR
+	};
+	/// Bold italic face
+	pub const BOLD_ITALIC: FontProperties = FontProperties {
+		weight: FontWeight::BOLD,
+		style: FontStyle::Italic,
+		..FontProperties::REGULAR
+	};
+
+	/// How far `face` is from these properties, the closest face has the lowest distance
+	///
+	/// As CSS does, stretch matters first, then style and weight.
+	pub(crate) fn distance(&self, face: &FontProperties) -> impl Ord {
+		// In tenths of percent
+		let (desired, available) = (
+			(self.stretch.percentage() * 10.) as u32,
+			(face.stretch.percentage() * 10.) as u32,
+		);
+		// Narrower faces for condensed text, wider ones for expanded text
+		let preferred = if desired <= 1000 {
+			available <= desired
+		} else {
+			available >= desired
+		};
+		let stretch = (!preferred, desired.abs_diff(available));
+
+		let preferences = match self.style {
+			FontStyle::Normal => [FontStyle::Normal, FontStyle::Oblique, FontStyle::Italic],
+			FontStyle::Italic => [FontStyle::Italic, FontStyle::Oblique, FontStyle::Normal],
+			FontStyle::Oblique => [FontStyle::Oblique, FontStyle::Italic, FontStyle::Normal],
+		};
+		let style = preferences
+			.iter()
+			.position(|style| *style == face.style)
+			.unwrap_or_default();
+
+		let (desired, available) = (self.weight.0, face.weight.0);
+		let weight = if desired == available {
+			(0, 0)
+		} else if (400..=500).contains(&desired) {
+			// Up to 500, then lighter, then heavier
+			if available > desired && available <= 500 {
+				(1, available - desired)
+			} else if available < desired {
+				(2, desired - available)
 			} else {
-				font.metrics(curr, font_size).advance_width
-			};
+				(3, available - desired)
+			}
+		} else if desired < 400 {
+			// Lighter, then heavier
+			if available < desired {
+				(1, desired - available)
+			} else {
+				(2, available - desired)
+			}
+		} else if available > desired {
+			// Heavier, then lighter
+			(1, available - desired)
+		} else {
+			(2, desired - available)
+		};
 
-			*last = Some(curr);
+		(stretch, style, weight)
+	}
+}
 
-			Some(r)
-		})
-		.sum()
+/// Lines drawn along a text, combined with `|`
+///
+/// ```
+/// # use dessin::prelude::*;
+/// let both = TextDecoration::UNDERLINE | TextDecoration::OVERLINE;
+/// assert!(both.underline && both.overline && !both.strike_through);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextDecoration {
+	/// Line below the baseline
+	pub underline: bool,
+	/// Line through the middle of lowercase letters
+	pub strike_through: bool,
+	/// Line at the top of the glyphs
+	pub overline: bool,
 }
+impl TextDecoration {
+	/// No line
+	pub const NONE: TextDecoration = TextDecoration {
+		underline: false,
+		strike_through: false,
+		overline: false,
+	};
+	/// Underline
+	pub const UNDERLINE: TextDecoration = TextDecoration {
+		underline: true,
+		..TextDecoration::NONE
+	};
+	/// Strike through
+	pub const STRIKE_THROUGH: TextDecoration = TextDecoration {
+		strike_through: true,
+		..TextDecoration::NONE
+	};
+	/// Overline
+	pub const OVERLINE: TextDecoration = TextDecoration {
+		overline: true,
+		..TextDecoration::NONE
+	};
 
-/// Weight of a font
-#[derive(Default, Debug, Clone, Copy, PartialEq, Hash, Eq)]
-pub enum FontWeight {
-	#[default]
-	/// Regular
-	Regular,
-	/// Bold
-	Bold,
-	/// Italic
-	Italic,
-	/// BoldItalic
-	BoldItalic,
+	/// Whether no line is drawn
+	#[inline]
+	pub fn is_none(&self) -> bool {
+		*self == TextDecoration::NONE
+	}
+
+	/// Lines drawn, with their metrics in `metrics`
+	pub(crate) fn lines(&self, metrics: &DecorationMetrics) -> Vec<DecorationLine> {
+		[
+			(self.underline, metrics.underline),
+			(self.strike_through, metrics.strike_through),
+			(self.overline, metrics.overline),
+		]
+		.into_iter()
+		.filter_map(|(drawn, line)| drawn.then_some(line))
+		.collect()
+	}
+}
+impl std::ops::BitOr for TextDecoration {
+	type Output = TextDecoration;
+
+	fn bitor(self, rhs: Self) -> Self::Output {
+		TextDecoration {
+			underline: self.underline || rhs.underline,
+			strike_through: self.strike_through || rhs.strike_through,
+			overline: self.overline || rhs.overline,
+		}
+	}
 }
 
 /// TextAlign
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextAlign {
 	#[default]
 	/// Left
@@ -47,10 +280,14 @@ pub enum TextAlign {
 	Center,
 	/// Right
 	Right,
+	/// Left, with the lines of a [`TextBox`] stretched to its width,
+	/// except the last one of each paragraph
+	Justify,
 }
 
 /// TextVerticalAlign
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextVerticalAlign {
 	#[default]
 	/// Bottom
@@ -59,6 +296,8 @@ pub enum TextVerticalAlign {
 	Center,
 	/// Top
 	Top,
+	/// Baseline, with the descent of the glyphs below the reference point
+	Baseline,
 }
 
 ///
@@ -69,19 +308,167 @@ pub struct TextPosition<'a> {
 	pub align: TextAlign,
 	///
 	pub font_weight: FontWeight,
+	/// Slant of the glyphs
+	pub font_style: FontStyle,
+	/// Width of the glyphs
+	pub font_stretch: FontStretch,
 	///
 	pub on_curve: Option<CurvePosition>,
 	///
 	pub font_size: f32,
+	/// Extra space after each character
+	pub letter_spacing: f32,
+	/// Extra space after each space, on top of the letter spacing
+	pub word_spacing: f32,
+	/// Lines drawn along the text, see [`TextPosition::decorations`]
+	pub decoration: TextDecoration,
 	///
 	pub reference_start: Point2<f32>,
 	///
 	pub direction: Unit<Vector2<f32>>,
+	/// Direction from the baseline to the top of the glyphs
+	pub up: Unit<Vector2<f32>>,
 	///
 	pub font: &'a Option<FontRef>,
 }
 
+/// Glyph placed by [`TextPosition::runs`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPosition<'a> {
+	/// Index of the glyph in the font
+	pub id: u16,
+	/// First character drawn by this glyph
+	pub character: char,
+	/// Characters drawn by this glyph, several for ligatures
+	///
+	/// Empty for the other glyphs of a character drawn by several glyphs, so that each character is drawn once.
+	pub text: &'a str,
+	/// Origin of the glyph, on the baseline
+	pub position: Point2<f32>,
+}
+
+/// Glyphs drawn with the same font, placed by [`TextPosition::runs`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphRun<'a> {
+	/// Font of the text, or one of its [fallbacks][font::set_fallbacks]
+	pub font: FontRef,
+	/// Glyphs, from left to right
+	pub glyphs: Vec<GlyphPosition<'a>>,
+}
+
+impl<'a> TextPosition<'a> {
+	/// Weight, style and stretch of the text
+	#[inline]
+	pub fn font_properties(&self) -> FontProperties {
+		FontProperties {
+			weight: self.font_weight,
+			style: self.font_style,
+			stretch: self.font_stretch,
+		}
+	}
+
+	/// Shaped and spaced runs of the text, with the start of the text along its direction
+	fn shaped_runs(&self) -> (Vec<ShapedRun>, f32) {
+		let mut runs = shape_runs(
+			self.font.as_ref(),
+			self.font_properties(),
+			self.text,
+			self.font_size,
+		);
+		space_runs(&mut runs, self.text, self.letter_spacing, self.word_spacing);
+		let width = runs.iter().map(|run| run.width).sum::<f32>();
+
+		let start = match self.align {
+			TextAlign::Left | TextAlign::Justify => 0.,
+			TextAlign::Center => -width / 2.,
+			TextAlign::Right => -width,
+		};
+		(runs, start)
+	}
+
+	/// Shaped glyphs of the text, by font, placed along its direction according to its alignment
+	///
+	/// Text following a curve is placed as if the curve was a straight line.
+	pub fn runs(&self) -> Vec<GlyphRun<'a>> {
+		let (runs, start) = self.shaped_runs();
+		let origin = self.reference_start + self.direction.into_inner() * start;
+
+		// A cluster goes to the next one in the text, whatever the direction of the glyphs
+		let text = self.text;
+		let clusters = runs
+			.iter()
+			.flat_map(|run| run.glyphs.iter().map(|glyph| glyph.cluster))
+			.collect::<BTreeSet<_>>();
+		let mut drawn = HashSet::new();
+
+		runs.into_iter()
+			.map(|run| GlyphRun {
+				font: run.font,
+				glyphs: run
+					.glyphs
+					.into_iter()
+					.map(|glyph| GlyphPosition {
+						id: glyph.id,
+						character: text[glyph.cluster..].chars().next().unwrap_or(' '),
+						text: match drawn.insert(glyph.cluster) {
+							true => {
+								let end = clusters
+									.range(glyph.cluster + 1..)
+									.next()
+									.copied()
+									.unwrap_or(text.len());
+								&text[glyph.cluster..end]
+							}
+							false => "",
+						},
+						position: origin
+							+ self.direction.into_inner() * glyph.offset.x
+							+ self.up.into_inner() * glyph.offset.y,
+					})
+					.collect(),
+			})
+			.collect()
+	}
+
+	/// Lines of the [decoration][TextPosition::decoration] of the text, as filled rectangles
+	///
+	/// Lines go from the start to the end of the text, at the positions and thicknesses
+	/// given by its font. As [runs][TextPosition::runs], they are straight along curves.
+	pub fn decorations(&self) -> Vec<CurvePosition> {
+		if self.decoration.is_none() {
+			return vec![];
+		}
+
+		let (runs, start) = self.shaped_runs();
+		let width = runs.iter().map(|run| run.width).sum::<f32>();
+		let metrics =
+			font::face(self.font.as_ref(), self.font_properties()).decorations(self.font_size);
+
+		let along = self.direction.into_inner();
+		let up = self.up.into_inner();
+		self.decoration
+			.lines(&metrics)
+			.into_iter()
+			.map(|line| {
+				let bottom =
+					self.reference_start + along * start + up * (line.offset - line.thickness / 2.);
+				let top = bottom + up * line.thickness;
+				CurvePosition {
+					keypoints: [bottom, bottom + along * width, top + along * width, top]
+						.into_iter()
+						.map(KeypointPosition::Point)
+						.collect(),
+					closed: true,
+					subpaths: vec![],
+					fill_rule: FillRule::default(),
+				}
+			})
+			.collect()
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Shape)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 ///
 pub struct Text {
 	/// [`ShapeOp`]
@@ -101,6 +488,12 @@ pub struct Text {
 	///
 	pub font_weight: FontWeight,
 
+	/// Slant of the glyphs, selecting the face of the font
+	pub font_style: FontStyle,
+
+	/// Width of the glyphs, selecting the face of the font
+	pub font_stretch: FontStretch,
+
 	#[shape(into_some)]
 	///
 	pub on_curve: Option<Curve>,
@@ -108,6 +501,18 @@ pub struct Text {
 	///
 	pub font_size: f32,
 
+	/// Extra space after each character, negative to tighten the text
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub letter_spacing: f32,
+
+	/// Extra space after each space, on top of the letter spacing
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub word_spacing: f32,
+
+	/// Lines drawn along the text
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub decoration: TextDecoration,
+
 	#[shape(into_some)]
 	///
 	pub font: Option<FontRef>,
@@ -120,8 +525,13 @@ impl Default for Text {
 			align: Default::default(),
 			vertical_align: Default::default(),
 			font_weight: Default::default(),
+			font_style: Default::default(),
+			font_stretch: Default::default(),
 			on_curve: Default::default(),
 			font_size: 10.,
+			letter_spacing: 0.,
+			word_spacing: 0.,
+			decoration: Default::default(),
 			font: Default::default(),
 		}
 	}
@@ -141,29 +551,44 @@ impl Text {
 		self
 	}
 
+	/// Weight, style and stretch of the text
+	#[inline]
+	pub fn font_properties(&self) -> FontProperties {
+		FontProperties {
+			weight: self.font_weight,
+			style: self.font_style,
+			stretch: self.font_stretch,
+		}
+	}
+
+	/// Vertical metrics of the font of the text, at its font size
+	pub fn metrics(&self) -> FontMetrics {
+		font::face(self.font.as_ref(), self.font_properties()).metrics(self.font_size)
+	}
+
 	///
 	pub fn position<'a>(&'a self, parent_transform: &Transform2<f32>) -> TextPosition<'a> {
 		let transform = self.global_transform(parent_transform);
 
-		let font_size = self.font_size * (transform * Vector2::new(0., 1.)).magnitude();
-		let reference_start = transform
-			* Point2::new(
-				0.,
-				match self.vertical_align {
-					TextVerticalAlign::Bottom => font_size / 2.,
-					TextVerticalAlign::Center => 0.,
-					TextVerticalAlign::Top => -font_size / 2.,
-				},
-			);
+		let scale = (transform * Vector2::new(0., 1.)).magnitude();
+		let font_size = self.font_size * scale;
+		let reference_start =
+			transform * Point2::new(0., self.metrics().baseline(self.vertical_align));
 
 		TextPosition {
 			text: &self.text,
 			align: self.align,
 			font_weight: self.font_weight,
+			font_style: self.font_style,
+			font_stretch: self.font_stretch,
 			on_curve: self.on_curve.as_ref().map(|v| v.position(&transform)),
 			font_size,
+			letter_spacing: self.letter_spacing * scale,
+			word_spacing: self.word_spacing * scale,
+			decoration: self.decoration,
 			reference_start,
 			direction: Unit::new_normalize(transform * Vector2::new(1., 0.)),
+			up: Unit::new_normalize(transform * Vector2::new(0., 1.)),
 			font: &self.font,
 		}
 	}
@@ -177,18 +602,49 @@ impl From<Text> for Shape {
 
 impl ShapeBoundingBox for Text {
 	fn local_bounding_box(&self) -> BoundingBox<UnParticular> {
-		let fonts = crate::font::get_or_default(self.font.as_ref());
-		let raw_font = match fonts.get(FontWeight::Regular) {
-			crate::font::Font::OTF(bytes) => bytes,
-			crate::font::Font::TTF(bytes) => bytes,
-		};
-
-		let font = fontdue::Font::from_bytes(raw_font.as_slice(), fontdue::FontSettings::default())
-			.unwrap();
+		// Text following a curve stays around it
+		if let Some(curve) = &self.on_curve {
+			let bb = curve.local_bounding_box().straigthen();
+			let margin = self.font_size;
+			return BoundingBox::mins_maxs(
+				bb.left() - margin,
+				bb.bottom() - margin,
+				bb.right() + margin,
+				bb.top() + margin,
+			)
+			.as_unparticular()
+			.transform(self.local_transform());
+		}
 
-		let width = size_of(&font, &self.text, self.font_size);
+		let width = size_of(
+			self.font.as_ref(),
+			self.font_properties(),
+			&self.text,
+			self.font_size,
+			self.letter_spacing,
+			self.word_spacing,
+		);
+		let left = match self.align {
+			TextAlign::Left | TextAlign::Justify => 0.,
+			TextAlign::Center => -width / 2.,
+			TextAlign::Right => -width,
+		};
 
-		BoundingBox::centered([width, self.font_size])
+		// From the lowest to the highest glyphs of the font, or lines, around the baseline
+		let metrics = self.metrics();
+		let baseline = metrics.baseline(self.vertical_align);
+		let lines = self.decoration.lines(
+			&font::face(self.font.as_ref(), self.font_properties()).decorations(self.font_size),
+		);
+		let bottom = lines
+			.iter()
+			.map(|line| line.offset - line.thickness / 2.)
+			.fold(metrics.descent, f32::min);
+		let top = lines
+			.iter()
+			.map(|line| line.offset + line.thickness / 2.)
+			.fold(metrics.ascent, f32::max);
+		BoundingBox::mins_maxs(left, baseline + bottom, left + width, baseline + top)
 			.as_unparticular()
 			.transform(self.local_transform())
 	}
@@ -200,7 +656,7 @@ mod tests {
 		export::{Export, Exporter},
 		prelude::*,
 	};
-	use nalgebra::{Point2, Rotation2};
+	use nalgebra::{Point2, Rotation2, Transform2};
 	use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
 
 	#[test]
@@ -211,19 +667,19 @@ mod tests {
 				Text(
 					text = "1",
 					font_size = 30.,
-					vertical_align = TextVerticalAlign::Center,
+					vertical_align = TextVerticalAlign::Baseline,
 					translate = [0., 25.],
 				),
 				Text(
 					text = "2",
 					font_size = 40.,
-					vertical_align = TextVerticalAlign::Center,
+					vertical_align = TextVerticalAlign::Baseline,
 					translate = [0., 0.]
 				),
 				Text(
 					text = "3",
 					font_size = 15.,
-					vertical_align = TextVerticalAlign::Center,
+					vertical_align = TextVerticalAlign::Baseline,
 					translate = [0., -30.]
 				),
 			] > (rotate = Rotation2::new(FRAC_PI_4))
@@ -245,7 +701,11 @@ mod tests {
 				Ok(())
 			}
 
-			fn export_ellipse(&mut self, ellipse: EllipsePosition) -> Result<(), Self::Error> {
+			fn export_ellipse(
+				&mut self,
+				ellipse: EllipsePosition,
+				_: StylePosition,
+			) -> Result<(), Self::Error> {
 				let expected_position = Point2::new(-25. * FRAC_1_SQRT_2, 25. * FRAC_1_SQRT_2);
 				assert!(
 					(ellipse.center - expected_position).magnitude() < 10e-6,
@@ -260,12 +720,16 @@ mod tests {
 			fn export_curve(
 				&mut self,
 				_curve: CurvePosition,
-				StylePosition { fill, stroke }: StylePosition,
+				_: StylePosition,
 			) -> Result<(), Self::Error> {
 				Ok(())
 			}
 
-			fn export_text(&mut self, text: TextPosition) -> Result<(), Self::Error> {
+			fn export_text(
+				&mut self,
+				text: TextPosition,
+				_: StylePosition,
+			) -> Result<(), Self::Error> {
 				match text.text {
 					"1" => {
 						let expected_position =
@@ -314,4 +778,121 @@ mod tests {
 			)
 			.unwrap();
 	}
+
+	#[test]
+	fn aligned_bounding_box() {
+		let left = dessin!(Text(text = "Hello", align = TextAlign::Left)).local_bounding_box();
+		let center = dessin!(Text(text = "Hello", align = TextAlign::Center)).local_bounding_box();
+		let right = dessin!(Text(text = "Hello", align = TextAlign::Right)).local_bounding_box();
+
+		let height = dessin!(Text(text = "Hello")).metrics().height();
+
+		assert!(left.width() > 0.);
+		assert!((left.top_left() - Point2::new(0., height)).magnitude() < 10e-6);
+		assert!((center.center() - Point2::new(0., height / 2.)).magnitude() < 10e-6);
+		assert!((right.top_right() - Point2::new(0., height)).magnitude() < 10e-6);
+	}
+
+	#[test]
+	fn vertically_aligned_bounding_box() {
+		let text = |vertical_align| {
+			let text = dessin!(Text(text = "Hello", font_size = 10., { vertical_align }));
+			let metrics = text.metrics();
+			let bb = text.local_bounding_box().straigthen();
+			let start = text.position(&Transform2::default()).reference_start;
+			(metrics, bb, start)
+		};
+
+		let (metrics, bottom, start) = text(TextVerticalAlign::Bottom);
+		assert!(bottom.bottom().abs() < 10e-6);
+		assert!((bottom.height() - metrics.height()).abs() < 10e-6);
+		assert!((start.y + metrics.descent).abs() < 10e-6);
+
+		let (_, top, _) = text(TextVerticalAlign::Top);
+		assert!(top.top().abs() < 10e-6);
+
+		let (_, center, _) = text(TextVerticalAlign::Center);
+		assert!(center.center().y.abs() < 10e-6);
+
+		let (metrics, baseline, start) = text(TextVerticalAlign::Baseline);
+		assert_eq!(start, Point2::new(0., 0.));
+		assert!((baseline.top() - metrics.ascent).abs() < 10e-6);
+		assert!((baseline.bottom() - metrics.descent).abs() < 10e-6);
+	}
+
+	#[test]
+	fn glyph_texts() {
+		let helvetica = font::add_font(
+			"Helvetica ligatures",
+			font::FontGroup::new(font::Font::OTF(include_bytes!("../Helvetica.otf").to_vec())),
+		);
+		let text = dessin!(Text(text = "fit", font = helvetica));
+		let position = text.position(&Transform2::default());
+		let runs = position.runs();
+		let texts = runs[0].glyphs.iter().map(|v| v.text).collect::<Vec<_>>();
+		assert_eq!(texts, ["fi", "t"]);
+		assert_eq!(runs[0].glyphs[0].character, 'f');
+	}
+
+	#[test]
+	fn spaced_and_decorated() {
+		let width = |text: Text| text.local_bounding_box().straigthen().width();
+		let plain = dessin!(Text(text = "Hi there", font_size = 10.));
+
+		// Eight characters, one space
+		let spaced = dessin!(Text(
+			text = "Hi there",
+			font_size = 10.,
+			letter_spacing = 1.,
+			word_spacing = 2.,
+		));
+		assert!((width(spaced.clone()) - width(plain.clone()) - 10.).abs() < 10e-4);
+
+		let position = spaced.position(&Transform2::default());
+		let runs = position.runs();
+		let glyphs = &runs[0].glyphs;
+		let plain_position = plain.position(&Transform2::default());
+		let plain_glyphs = &plain_position.runs()[0].glyphs;
+		assert!((glyphs[1].position.x - plain_glyphs[1].position.x - 1.).abs() < 10e-4);
+		assert!((glyphs[3].position.x - plain_glyphs[3].position.x - 5.).abs() < 10e-4);
+
+		let decorated = dessin!(Text(
+			text = "Hi there",
+			font_size = 10.,
+			vertical_align = TextVerticalAlign::Baseline,
+			decoration = TextDecoration::UNDERLINE | TextDecoration::STRIKE_THROUGH,
+		));
+		let lines = decorated.position(&Transform2::default()).decorations();
+		assert_eq!(lines.len(), 2);
+		let corners = |line: &CurvePosition| {
+			let KeypointPosition::Point(start) = line.keypoints[0] else {
+				panic!("Point expected");
+			};
+			let KeypointPosition::Point(end) = line.keypoints[2] else {
+				panic!("Point expected");
+			};
+			(start, end)
+		};
+		let (start, end) = corners(&lines[0]);
+		assert!(start.y < 0. && end.y < 0., "underline below the baseline");
+		assert!((end.x - start.x - width(plain.clone())).abs() < 10e-4);
+		let (start, _) = corners(&lines[1]);
+		assert!(start.y > 0., "strike through above the baseline");
+
+		let bb = decorated.local_bounding_box().straigthen();
+		assert!(bb.bottom() <= decorated.metrics().descent);
+		assert!(plain
+			.position(&Transform2::default())
+			.decorations()
+			.is_empty());
+
+		// Outlines draw the lines too
+		let Shape::Group(Group { shapes, .. }) = decorated.to_outlines() else {
+			panic!("Group expected");
+		};
+		let Shape::Group(Group { shapes: plain, .. }) = plain.to_outlines() else {
+			panic!("Group expected");
+		};
+		assert_eq!(shapes.len(), plain.len() + 2);
+	}
 }
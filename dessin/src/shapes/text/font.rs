@@ -1,10 +1,16 @@
-use super::FontWeight;
+use super::{
+	shaping, DecorationMetrics, FontMetrics, FontProperties, FontStretch, FontStyle, FontWeight,
+	ShapedText,
+};
 use ecow::EcoString;
+use rustybuzz::Face;
 use std::{
-	collections::HashMap,
-	fmt,
+	borrow::Cow,
+	collections::{BTreeMap, HashMap},
+	fmt, fs, io,
 	ops::Deref,
-	sync::{LazyLock, OnceLock, RwLock},
+	path::Path,
+	sync::{Arc, LazyLock, OnceLock, RwLock},
 };
 
 static FONT_HOLDER: OnceLock<RwLock<FontHolder>> = OnceLock::new();
@@ -35,6 +41,255 @@ pub fn get_or_default(idx: Option<&FontRef>) -> FontGroup<Font> {
 	idx.map(get).unwrap_or_else(|| get(&*DEFAULT_FONT))
 }
 
+/// Parsed face of a font, or of the default font if `idx` is `None`
+///
+/// Faces are parsed once and kept until the font is replaced by [`add_font`].
+/// Measuring text through them is much cheaper than through a [`Font`].
+///
+/// A font that can't be found or read has no glyph, see [`try_face`] to know why.
+pub fn face(idx: Option<&FontRef>, properties: FontProperties) -> Arc<FontFace> {
+	try_face(idx, properties).unwrap_or_else(|_| Arc::new(FontFace::missing()))
+}
+
+/// Parsed face of a font, or of the default font if `idx` is `None`, see [`face`]
+///
+/// The face of the font closest to `properties` is used, see [`FontGroup::closest`].
+/// Fonts [by name][Font::ByName] are looked up in the [font database][load_fonts_dir].
+pub fn try_face(
+	idx: Option<&FontRef>,
+	properties: FontProperties,
+) -> Result<Arc<FontFace>, FontError> {
+	let font = idx.cloned().unwrap_or_default();
+	let name = font.0.clone();
+	let unregistered = || FontError::Unregistered(font.clone());
+	let empty = || FontError::Empty(font.clone());
+
+	let cached = font_holder(|f| {
+		let properties = f
+			.fonts
+			.get(&name)
+			.ok_or_else(unregistered)?
+			.available(properties)
+			.ok_or_else(empty)?;
+		Ok(f.faces.get(&(name.clone(), properties)).cloned())
+	})?;
+	if let Some(face) = cached {
+		return Ok(face);
+	}
+
+	font_holder_mut(|f| {
+		let group = f.fonts.get(&name).ok_or_else(unregistered)?;
+		let properties = group.available(properties).ok_or_else(empty)?;
+		let (bytes, index) = group.get(properties).data(&mut f.database)?;
+		let face = FontFace::new(bytes.into_owned(), index)
+			.ok_or_else(|| FontError::Invalid(FontRef(name.clone()), properties))?;
+
+		Ok(f.faces
+			.entry((name, properties))
+			.or_insert(Arc::new(face))
+			.clone())
+	})
+}
+
+/// Weight, style and stretch of the face drawn for `properties`, see [`FontGroup::closest`]
+///
+/// Exporters embedding fonts key them by these properties, so that a face is embedded once.
+pub fn properties(
+	idx: Option<&FontRef>,
+	properties: FontProperties,
+) -> Result<FontProperties, FontError> {
+	let font = idx.cloned().unwrap_or_default();
+	font_holder(|f| match f.fonts.get(&font.0) {
+		Some(group) => group.available(properties).ok_or(FontError::Empty(font)),
+		None => Err(FontError::Unregistered(font)),
+	})
+}
+
+/// Add the fonts of `dir` and its subdirectories to the font database
+///
+/// Fonts [by name][Font::ByName] are looked up in this database,
+/// which also holds the fonts installed on the system.
+pub fn load_fonts_dir<P: AsRef<Path>>(dir: P) {
+	font_holder_mut(|f| {
+		f.database
+			.get_or_insert_with(system_fonts)
+			.load_fonts_dir(dir);
+		// Fonts by name may now be found elsewhere
+		let fonts = &f.fonts;
+		f.faces.retain(|(name, properties), _| {
+			!matches!(
+				fonts.get(name).map(|group| group.get(*properties)),
+				Some(Font::ByName(_))
+			)
+		});
+	})
+}
+
+/// Register the fonts of a TTF, OTF, TTC or OTC file, see [`add_font_data`]
+pub fn add_font_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<FontRef>> {
+	add_font_data(fs::read(path)?)
+}
+
+/// Register the fonts of the font files of `dir` and its subdirectories, see [`add_font_data`]
+///
+/// Families spread over several files are grouped together.
+pub fn add_font_dir<P: AsRef<Path>>(dir: P) -> io::Result<Vec<FontRef>> {
+	fn read_dir(dir: &Path, files: &mut Vec<Vec<u8>>) -> io::Result<()> {
+		let mut entries = fs::read_dir(dir)?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<io::Result<Vec<_>>>()?;
+		entries.sort();
+
+		for path in entries {
+			if path.is_dir() {
+				read_dir(&path, files)?;
+				continue;
+			}
+
+			let extension = path
+				.extension()
+				.and_then(|extension| extension.to_str())
+				.map(str::to_ascii_lowercase);
+			if let Some("ttf" | "otf" | "ttc" | "otc") = extension.as_deref() {
+				files.push(fs::read(path)?);
+			}
+		}
+		Ok(())
+	}
+
+	let mut files = vec![];
+	read_dir(dir.as_ref(), &mut files)?;
+	Ok(add_fonts(files))
+}
+
+/// Register the fonts of a font file, one [`FontGroup`] per family
+///
+/// Fonts are named after their family, as read in the font.
+/// Each face of a family joins its group with the weight, style and stretch read in the font,
+/// replacing the face the group may already have for them.
+/// Fonts of collections are registered [by name][Font::ByName].
+///
+/// Returns the registered fonts, by name, or an [`InvalidData`][io::ErrorKind::InvalidData] error
+/// if `data` holds no font.
+pub fn add_font_data(data: Vec<u8>) -> io::Result<Vec<FontRef>> {
+	let fonts = add_fonts(vec![data]);
+	if fonts.is_empty() {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"no font can be read in the data",
+		));
+	}
+	Ok(fonts)
+}
+
+fn add_fonts(files: Vec<Vec<u8>>) -> Vec<FontRef> {
+	let mut database = fontdb::Database::new();
+	for data in files {
+		if rustybuzz::ttf_parser::fonts_in_collection(&data).is_some() {
+			font_holder_mut(|f| {
+				f.database
+					.get_or_insert_with(system_fonts)
+					.load_font_data(data.clone())
+			});
+		}
+		database.load_font_data(data);
+	}
+
+	let mut families = BTreeMap::<String, Vec<(FontProperties, Font)>>::new();
+	for face in database.faces() {
+		let Some((family, _)) = face.families.first() else {
+			continue;
+		};
+		let Some(font) = database.with_face_data(face.id, |data, _| {
+			if rustybuzz::ttf_parser::fonts_in_collection(data).is_some() {
+				Font::ByName(face.post_script_name.clone())
+			} else if data.starts_with(b"OTTO") {
+				Font::OTF(data.to_vec())
+			} else {
+				Font::TTF(data.to_vec())
+			}
+		}) else {
+			continue;
+		};
+
+		let properties = FontProperties {
+			weight: FontWeight(face.weight.0),
+			style: match face.style {
+				fontdb::Style::Normal => FontStyle::Normal,
+				fontdb::Style::Italic => FontStyle::Italic,
+				fontdb::Style::Oblique => FontStyle::Oblique,
+			},
+			stretch: match face.stretch {
+				fontdb::Stretch::UltraCondensed => FontStretch::UltraCondensed,
+				fontdb::Stretch::ExtraCondensed => FontStretch::ExtraCondensed,
+				fontdb::Stretch::Condensed => FontStretch::Condensed,
+				fontdb::Stretch::SemiCondensed => FontStretch::SemiCondensed,
+				fontdb::Stretch::Normal => FontStretch::Normal,
+				fontdb::Stretch::SemiExpanded => FontStretch::SemiExpanded,
+				fontdb::Stretch::Expanded => FontStretch::Expanded,
+				fontdb::Stretch::ExtraExpanded => FontStretch::ExtraExpanded,
+				fontdb::Stretch::UltraExpanded => FontStretch::UltraExpanded,
+			},
+		};
+		families
+			.entry(family.clone())
+			.or_default()
+			.push((properties, font));
+	}
+
+	// Families already registered keep their other faces
+	families
+		.into_iter()
+		.map(|(family, faces)| {
+			let mut group = font_holder(|f| f.fonts.get(family.as_str()).cloned())
+				.unwrap_or(FontGroup { faces: vec![] });
+			for (properties, font) in faces {
+				group.faces.retain(|(existing, _)| *existing != properties);
+				group.faces.push((properties, font));
+			}
+			add_font(family, group)
+		})
+		.collect()
+}
+
+/// Calls `f` with the parsed `font`, or `None` if it can't be found or read
+pub(crate) fn with_face<T, F: FnOnce(Option<&Face>) -> T>(font: &Font, f: F) -> T {
+	let data = match font {
+		Font::ByName(_) => font_holder_mut(|holder| {
+			font.data(&mut holder.database)
+				.map(|(bytes, index)| (Cow::Owned(bytes.into_owned()), index))
+		}),
+		Font::OTF(_) | Font::TTF(_) => font.data(&mut None),
+	};
+	match data {
+		Ok((bytes, index)) => f(Face::from_slice(&bytes, index).as_ref()),
+		Err(_) => f(None),
+	}
+}
+
+fn system_fonts() -> fontdb::Database {
+	let mut database = fontdb::Database::new();
+	database.load_system_fonts();
+	database
+}
+
+/// Fonts drawing the characters missing from `font`, in order of preference
+///
+/// Text is split into runs, each drawn with the first font of the chain having its characters.
+/// Fallbacks of the fallbacks are not used.
+pub fn set_fallbacks<F: Into<FontRef>, I: IntoIterator<Item = FontRef>>(font: F, fallbacks: I) {
+	let font = font.into();
+	let fallbacks = fallbacks.into_iter().collect();
+	font_holder_mut(move |f| {
+		f.fallbacks.insert(font.0, fallbacks);
+	})
+}
+
+/// Fallbacks of `font`, see [`set_fallbacks`]
+pub fn fallbacks(font: &FontRef) -> Vec<FontRef> {
+	font_holder(|f| f.fallbacks.get(&font.0).cloned().unwrap_or_default())
+}
+
 #[inline]
 ///
 pub fn fonts() -> HashMap<EcoString, FontGroup<Font>> {
@@ -52,6 +307,7 @@ pub fn font_names() -> Vec<EcoString> {
 pub fn add_font<S: Into<EcoString>>(font_name: S, font: FontGroup<Font>) -> FontRef {
 	font_holder_mut(move |f| {
 		let font_name = font_name.into();
+		f.faces.retain(|(name, _), _| *name != font_name);
 		f.fonts.insert(font_name.clone(), font);
 		FontRef(font_name)
 	})
@@ -61,6 +317,7 @@ pub fn add_font<S: Into<EcoString>>(font_name: S, font: FontGroup<Font>) -> Font
 pub const DEFAULT_FONT: LazyLock<FontRef> = LazyLock::new(|| FontRef("Hyperlegible".into()));
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 ///
 pub struct FontRef(EcoString);
@@ -89,6 +346,32 @@ impl<S: Into<EcoString>> From<S> for FontRef {
 	}
 }
 
+/// Font that can't be drawn
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontError {
+	/// No font was added with this name, see [`add_font`]
+	Unregistered(FontRef),
+	/// The font was added with a group of no face
+	Empty(FontRef),
+	/// No font of the font database has this name
+	NotFound(String),
+	/// The font file can't be read
+	Invalid(FontRef, FontProperties),
+}
+impl fmt::Display for FontError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			FontError::Unregistered(font) => write!(f, "no font `{font}` was added"),
+			FontError::Empty(font) => write!(f, "font `{font}` has no face"),
+			FontError::NotFound(name) => write!(f, "no font named `{name}` is installed"),
+			FontError::Invalid(font, font_weight) => {
+				write!(f, "font `{font} {font_weight:?}` can't be read")
+			}
+		}
+	}
+}
+impl std::error::Error for FontError {}
+
 #[derive(Clone)]
 ///
 pub enum Font {
@@ -96,74 +379,241 @@ pub enum Font {
 	OTF(Vec<u8>),
 	/// TTF font
 	TTF(Vec<u8>),
+	/// Font installed on the system, or in a directory given to [`load_fonts_dir`]
+	///
+	/// Matches the PostScript name of the font, like `DejaVuSans-Bold`, or else its family name.
+	/// The font is looked up when first used.
+	ByName(String),
 }
 
 impl Font {
-	///
+	/// Content of the font file, empty for a font [by name][Font::ByName]
 	pub fn as_bytes(&self) -> &[u8] {
 		match self {
 			Font::OTF(b) | Font::TTF(b) => b.as_slice(),
+			Font::ByName(_) => &[],
 		}
 	}
+
+	/// Content of the font file and index of the font in it
+	fn data(
+		&self,
+		database: &mut Option<fontdb::Database>,
+	) -> Result<(Cow<'_, [u8]>, u32), FontError> {
+		let name = match self {
+			Font::OTF(b) | Font::TTF(b) => return Ok((Cow::Borrowed(b), 0)),
+			Font::ByName(name) => name,
+		};
+
+		let database = database.get_or_insert_with(system_fonts);
+		let id = database
+			.faces()
+			.find(|face| face.post_script_name == *name)
+			.map(|face| face.id)
+			.or_else(|| {
+				database.query(&fontdb::Query {
+					families: &[fontdb::Family::Name(name)],
+					..Default::default()
+				})
+			})
+			.ok_or_else(|| FontError::NotFound(name.clone()))?;
+
+		database
+			.with_face_data(id, |bytes, index| (Cow::Owned(bytes.to_vec()), index))
+			.ok_or_else(|| FontError::NotFound(name.clone()))
+	}
 }
 
-#[derive(Clone)]
+/// [`Font`] parsed once, shared by bounding boxes, layouts and exporters
 ///
-pub struct FontGroup<T> {
-	///
-	pub regular: T,
+/// Get it from [`face`].
+pub struct FontFace {
+	/// `None` if the font can't be found or read
 	///
-	pub bold: Option<T>,
-	///
-	pub italic: Option<T>,
+	/// Borrows `bytes`, so it is declared first to be dropped before them.
+	/// Its `'static` lifetime never leaves the face, see [`FontFace::face`].
+	face: Option<Face<'static>>,
+	bytes: Arc<[u8]>,
+	index: u32,
+}
+impl FontFace {
+	fn new(bytes: Vec<u8>, index: u32) -> Option<Self> {
+		let bytes = Arc::<[u8]>::from(bytes);
+		// SAFETY: the content of an `Arc` neither moves nor changes, and `bytes` outlives `face`
+		let data = unsafe { &*Arc::as_ptr(&bytes) };
+		Some(FontFace {
+			face: Some(Face::from_slice(data, index)?),
+			bytes,
+			index,
+		})
+	}
+
+	fn missing() -> Self {
+		FontFace {
+			face: None,
+			bytes: Arc::from([]),
+			index: 0,
+		}
+	}
+
+	/// Content of the font file
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Index of the font in its file, for font collections
+	#[inline]
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	/// Whether the font can draw `c`
+	#[inline]
+	pub fn has_glyph(&self, c: char) -> bool {
+		self.face
+			.as_ref()
+			.is_some_and(|face| face.glyph_index(c).is_some())
+	}
+
+	/// Index of the glyph drawing `c`, `None` when the font can't draw it
+	#[inline]
+	pub fn glyph_id(&self, c: char) -> Option<u16> {
+		self.face.as_ref()?.glyph_index(c).map(|id| id.0)
+	}
+
+	#[inline]
+	pub(crate) fn face(&self) -> Option<&Face<'_>> {
+		self.face.as_ref()
+	}
+
+	/// Vertical metrics for text of `font_size`, see [`FontMetrics::of`]
+	#[inline]
+	pub fn metrics(&self, font_size: f32) -> FontMetrics {
+		self.face
+			.as_ref()
+			.map(|face| shaping::metrics(face, font_size))
+			.unwrap_or_default()
+	}
+
+	/// Decoration lines for text of `font_size`, see [`DecorationMetrics::of`]
+	#[inline]
+	pub fn decorations(&self, font_size: f32) -> DecorationMetrics {
+		self.face
+			.as_ref()
+			.map(|face| shaping::decoration_metrics(face, font_size))
+			.unwrap_or_default()
+	}
+
+	/// Glyphs of `text`, see [`shape_text`][super::shape_text]
+	#[inline]
+	pub fn shape(&self, text: &str, font_size: f32) -> ShapedText {
+		self.face
+			.as_ref()
+			.map(|face| shaping::shape(face, text, font_size))
+			.unwrap_or_default()
+	}
+
+	/// Length of `text` along its baseline
+	#[inline]
+	pub fn width(&self, text: &str, font_size: f32) -> f32 {
+		self.shape(text, font_size).width
+	}
+}
+
+#[derive(Clone)]
+/// Faces of a font family
+pub struct FontGroup<T> {
+	/// Faces, with their weight, style and stretch
+	pub faces: Vec<(FontProperties, T)>,
+}
+impl<T> FontGroup<T> {
+	/// Group of a regular face
+	pub fn new(regular: T) -> Self {
+		FontGroup {
+			faces: vec![(FontProperties::REGULAR, regular)],
+		}
+	}
+
+	/// Add a face to the group
+	pub fn with_face(mut self, properties: FontProperties, face: T) -> Self {
+		self.faces.push((properties, face));
+		self
+	}
+
+	/// Face closest to `properties`, as CSS matches fonts
 	///
-	pub bold_italic: Option<T>,
+	/// Faces of the closest stretch are preferred, then of the closest style, then of the closest weight.
+	/// Without an exact weight, weights up to 500 are tried first for 400 and 500,
+	/// lighter ones below 400 and heavier ones above 500.
+	pub fn closest(&self, properties: FontProperties) -> Option<&(FontProperties, T)> {
+		self.faces
+			.iter()
+			.min_by_key(|(face, _)| properties.distance(face))
+	}
 }
 impl FontGroup<Font> {
+	/// Font of the face closest to `properties`, see [`FontGroup::closest`]
 	///
-	pub fn get(&self, font_weight: FontWeight) -> &Font {
-		match font_weight {
-			FontWeight::Regular => &self.regular,
-			FontWeight::Bold => self.bold.as_ref().unwrap_or_else(|| &self.regular),
-			FontWeight::BoldItalic => self.bold_italic.as_ref().unwrap_or_else(|| &self.regular),
-			FontWeight::Italic => self.italic.as_ref().unwrap_or_else(|| &self.regular),
-		}
+	/// Panics if the group has no face.
+	pub fn get(&self, properties: FontProperties) -> &Font {
+		&self
+			.closest(properties)
+			.expect("a font group has at least one face")
+			.1
+	}
+
+	/// Properties of the face actually drawn for `properties`, `None` if the group has no face
+	fn available(&self, properties: FontProperties) -> Option<FontProperties> {
+		self.closest(properties).map(|(properties, _)| *properties)
 	}
 
 	#[cfg(feature = "default-font")]
 	///
 	pub fn hyperlegible() -> FontGroup<Font> {
-		FontGroup {
-			regular: Font::OTF(
-				include_bytes!("../../../Atkinson-Hyperlegible-Regular-102.otf").to_vec(),
-			),
-			bold: Some(Font::OTF(
-				include_bytes!("../../../Atkinson-Hyperlegible-Bold-102.otf").to_vec(),
-			)),
-			italic: Some(Font::OTF(
-				include_bytes!("../../../Atkinson-Hyperlegible-Italic-102.otf").to_vec(),
-			)),
-			bold_italic: Some(Font::OTF(
-				include_bytes!("../../../Atkinson-Hyperlegible-BoldItalic-102.otf").to_vec(),
-			)),
-		}
+		FontGroup::new(Font::OTF(
+			include_bytes!("../../../Atkinson-Hyperlegible-Regular-102.otf").to_vec(),
+		))
+		.with_face(
+			FontProperties::BOLD,
+			Font::OTF(include_bytes!("../../../Atkinson-Hyperlegible-Bold-102.otf").to_vec()),
+		)
+		.with_face(
+			FontProperties::ITALIC,
+			Font::OTF(include_bytes!("../../../Atkinson-Hyperlegible-Italic-102.otf").to_vec()),
+		)
+		.with_face(
+			FontProperties::BOLD_ITALIC,
+			Font::OTF(include_bytes!("../../../Atkinson-Hyperlegible-BoldItalic-102.otf").to_vec()),
+		)
 	}
 
 	#[cfg(not(feature = "default-font"))]
-	///
+	/// Atkinson Hyperlegible, as installed on the system or loaded with [`load_fonts_dir`]
 	pub fn hyperlegible() -> FontGroup<Font> {
-		FontGroup {
-			regular: Font::ByName("HyperlegibleRegular".to_string()),
-			bold: Some(Font::ByName("HyperlegibleBold".to_string())),
-			italic: Some(Font::ByName("HyperlegibleItalic".to_string())),
-			bold_italic: Some(Font::ByName("HyperlegibleBoldItalic".to_string())),
-		}
+		FontGroup::new(Font::ByName("AtkinsonHyperlegible-Regular".to_string()))
+			.with_face(
+				FontProperties::BOLD,
+				Font::ByName("AtkinsonHyperlegible-Bold".to_string()),
+			)
+			.with_face(
+				FontProperties::ITALIC,
+				Font::ByName("AtkinsonHyperlegible-Italic".to_string()),
+			)
+			.with_face(
+				FontProperties::BOLD_ITALIC,
+				Font::ByName("AtkinsonHyperlegible-BoldItalic".to_string()),
+			)
 	}
 }
 
 ///
 pub struct FontHolder {
 	fonts: HashMap<EcoString, FontGroup<Font>>,
+	faces: HashMap<(EcoString, FontProperties), Arc<FontFace>>,
+	fallbacks: HashMap<EcoString, Vec<FontRef>>,
+	/// Fonts by name, loaded when first needed
+	database: Option<fontdb::Database>,
 }
 impl FontHolder {
 	fn new() -> Self {
@@ -171,6 +621,192 @@ impl FontHolder {
 
 		fonts.insert("Hyperlegible".into(), FontGroup::hyperlegible());
 
-		FontHolder { fonts }
+		FontHolder {
+			fonts,
+			faces: HashMap::new(),
+			fallbacks: HashMap::new(),
+			database: None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn faces_are_cached() {
+		let regular = face(None, FontProperties::REGULAR);
+		assert!(Arc::ptr_eq(&regular, &face(None, FontProperties::REGULAR)));
+		assert!(!Arc::ptr_eq(&regular, &face(None, FontProperties::BOLD)));
+
+		let font = get_or_default(None).get(FontProperties::REGULAR).clone();
+		assert_eq!(regular.as_bytes(), font.as_bytes());
+		assert_eq!(
+			regular.shape("Hello", 10.),
+			super::super::shape_text(&font, "Hello", 10.)
+		);
+		assert_eq!(regular.metrics(10.), FontMetrics::of(&font, 10.));
+
+		// Replacing a font forgets its faces
+		let name = "Cached faces";
+		add_font(name, get_or_default(None));
+		let first = face(Some(&name.into()), FontProperties::REGULAR);
+		assert!(Arc::ptr_eq(
+			&first,
+			&face(Some(&name.into()), FontProperties::REGULAR)
+		));
+		add_font(name, get_or_default(None));
+		assert!(!Arc::ptr_eq(
+			&first,
+			&face(Some(&name.into()), FontProperties::REGULAR)
+		));
+
+		// Forgotten faces free their font file
+		let bytes = Arc::downgrade(&first.bytes);
+		drop(first);
+		assert!(bytes.upgrade().is_none());
+	}
+
+	#[test]
+	fn by_name() {
+		let bold = add_font(
+			"Bold by name",
+			FontGroup::new(Font::ByName("AtkinsonHyperlegible-Bold".to_string())),
+		);
+		load_fonts_dir(env!("CARGO_MANIFEST_DIR"));
+
+		let found = try_face(Some(&bold), FontProperties::REGULAR).unwrap();
+		assert_eq!(
+			found.as_bytes(),
+			get_or_default(None).get(FontProperties::BOLD).as_bytes()
+		);
+
+		let missing = add_font(
+			"Missing by name",
+			FontGroup::new(Font::ByName("Not a font".to_string())),
+		);
+		assert_eq!(
+			try_face(Some(&missing), FontProperties::REGULAR).err(),
+			Some(FontError::NotFound("Not a font".to_string()))
+		);
+		// Missing fonts measure as nothing
+		let width = face(Some(&missing), FontProperties::REGULAR).width("Hello", 10.);
+		assert_eq!(width, 0.);
+	}
+
+	#[test]
+	fn unregistered() {
+		let font = FontRef::from("Never added");
+		let error = Some(FontError::Unregistered(font.clone()));
+		assert_eq!(try_face(Some(&font), FontProperties::REGULAR).err(), error);
+		assert_eq!(
+			properties(Some(&font), FontProperties::REGULAR).err(),
+			error
+		);
+
+		// Texts with such a font measure as nothing
+		let width = face(Some(&font), FontProperties::REGULAR).width("Hello", 10.);
+		assert_eq!(width, 0.);
+	}
+
+	#[test]
+	fn empty_group() {
+		let font = add_font("No face", FontGroup { faces: vec![] });
+		let error = Some(FontError::Empty(font.clone()));
+		assert_eq!(try_face(Some(&font), FontProperties::BOLD).err(), error);
+		assert_eq!(properties(Some(&font), FontProperties::BOLD).err(), error);
+
+		let text = crate::prelude::Text::default()
+			.with_text("Hello")
+			.with_font(font);
+		assert_eq!(text.metrics(), FontMetrics::default());
+	}
+
+	#[test]
+	fn from_files() {
+		let fonts = add_font_dir(env!("CARGO_MANIFEST_DIR")).unwrap();
+		let hyperlegible = FontRef::from("Atkinson Hyperlegible");
+		assert!(fonts.contains(&hyperlegible));
+
+		let bundled = get_or_default(None);
+		let loaded = get(&hyperlegible);
+		assert_eq!(loaded.faces.len(), 4);
+		for properties in [
+			FontProperties::REGULAR,
+			FontProperties::BOLD,
+			FontProperties::ITALIC,
+			FontProperties::BOLD_ITALIC,
+		] {
+			assert_eq!(
+				loaded.get(properties).as_bytes(),
+				bundled.get(properties).as_bytes()
+			);
+		}
+
+		let path = concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/Atkinson-Hyperlegible-Bold-102.otf"
+		);
+		let bold = add_font_file(path).unwrap();
+		assert_eq!(bold.len(), 1);
+		assert_eq!(bold[0], hyperlegible);
+
+		// Adding a face again keeps the others of the family
+		let reloaded = get(&hyperlegible);
+		assert_eq!(reloaded.faces.len(), 4);
+		assert_eq!(
+			reloaded.get(FontProperties::REGULAR).as_bytes(),
+			bundled.get(FontProperties::REGULAR).as_bytes()
+		);
+		assert_eq!(
+			reloaded.get(FontProperties::BOLD).as_bytes(),
+			bundled.get(FontProperties::BOLD).as_bytes()
+		);
+
+		assert!(add_font_file("not a font file").is_err());
+		let invalid = add_font_data(b"not a font".to_vec()).unwrap_err();
+		assert_eq!(invalid.kind(), io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn closest_face() {
+		let group = FontGroup::new("regular")
+			.with_face(FontProperties::BOLD, "bold")
+			.with_face(FontProperties::ITALIC, "italic")
+			.with_face(
+				FontProperties {
+					stretch: FontStretch::Condensed,
+					..FontProperties::REGULAR
+				},
+				"condensed",
+			);
+		let closest = |properties| group.closest(properties).unwrap().1;
+
+		let weight = |weight| FontProperties {
+			weight,
+			..FontProperties::REGULAR
+		};
+		assert_eq!(closest(weight(FontWeight::LIGHT)), "regular");
+		assert_eq!(closest(weight(FontWeight::MEDIUM)), "regular");
+		assert_eq!(closest(weight(FontWeight::SEMI_BOLD)), "bold");
+		assert_eq!(closest(weight(FontWeight::BLACK)), "bold");
+
+		// Italic and oblique stand in for each other before upright faces
+		assert_eq!(closest(FontProperties::BOLD_ITALIC), "italic");
+		let oblique = FontProperties {
+			style: FontStyle::Oblique,
+			..FontProperties::REGULAR
+		};
+		assert_eq!(closest(oblique), "italic");
+
+		// Narrower faces are preferred for condensed text
+		let stretch = |stretch| FontProperties {
+			stretch,
+			..FontProperties::REGULAR
+		};
+		assert_eq!(closest(stretch(FontStretch::UltraCondensed)), "condensed");
+		assert_eq!(closest(stretch(FontStretch::SemiCondensed)), "condensed");
+		assert_eq!(closest(stretch(FontStretch::Expanded)), "regular");
 	}
 }
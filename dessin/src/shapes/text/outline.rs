@@ -0,0 +1,302 @@
+use super::{font, shape_runs, space_runs, Text, TextAlign};
+use crate::{
+	prelude::*,
+	visit::{ShapeVisitorMut, VisitContext, VisitFlow},
+};
+use nalgebra::{Point2, Rotation2, Scale2, Transform2, Translation2};
+use rustybuzz::ttf_parser;
+
+/// Contours of a glyph, in font units
+#[derive(Default)]
+struct GlyphOutline {
+	contours: Vec<Curve>,
+	current: Curve,
+}
+impl GlyphOutline {
+	fn into_curve(mut self) -> Option<Curve> {
+		self.finish_contour();
+
+		let mut contours = self.contours.into_iter();
+		let mut curve = contours.next()?;
+		curve.subpaths.extend(contours);
+		Some(curve)
+	}
+
+	fn finish_contour(&mut self) {
+		let contour = std::mem::take(&mut self.current);
+		if !contour.keypoints.is_empty() {
+			self.contours.push(contour);
+		}
+	}
+}
+impl ttf_parser::OutlineBuilder for GlyphOutline {
+	fn move_to(&mut self, x: f32, y: f32) {
+		self.finish_contour();
+		self.current.then(Point2::new(x, y));
+	}
+
+	fn line_to(&mut self, x: f32, y: f32) {
+		self.current.then(Point2::new(x, y));
+	}
+
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+		self.current
+			.then(QuadraticBezier::new(Point2::new(x1, y1), Point2::new(x, y)));
+	}
+
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+		self.current.then(Bezier::new(
+			Point2::new(x1, y1),
+			Point2::new(x2, y2),
+			Point2::new(x, y),
+		));
+	}
+
+	fn close(&mut self) {
+		self.current.closed();
+		self.finish_contour();
+	}
+}
+
+impl Text {
+	/// Outlines of the glyphs of the text, one [`Curve`] per glyph, instead of the text itself
+	///
+	/// Glyphs are placed as exporters draw the text: aligned, along [`Text::on_curve`] if any,
+	/// and moved by [`Text::local_transform`].
+	/// Along a curve, the text starts at the start of the curve, its middle or its end depending on [`Text::align`],
+	/// and glyphs falling outside of the curve are left out.
+	///
+	/// Lines of [`Text::decoration`] follow the glyphs as closed curves, except along a curve.
+	pub fn to_outlines(&self) -> Shape {
+		let mut runs = shape_runs(
+			self.font.as_ref(),
+			self.font_properties(),
+			&self.text,
+			self.font_size,
+		);
+		space_runs(
+			&mut runs,
+			&self.text,
+			self.letter_spacing,
+			self.word_spacing,
+		);
+		let width = runs.iter().map(|run| run.width).sum::<f32>();
+		let length = self.on_curve.as_ref().map(Curve::length);
+
+		let start = match (self.align, length) {
+			(TextAlign::Left | TextAlign::Justify, _) => 0.,
+			(TextAlign::Center, None) => -width / 2.,
+			(TextAlign::Right, None) => -width,
+			(TextAlign::Center, Some(length)) => (length - width) / 2.,
+			(TextAlign::Right, Some(length)) => length - width,
+		};
+		// Same baseline as exporters
+		let baseline = self.metrics().baseline(self.vertical_align);
+
+		// Each glyph is drawn by the font of its run
+		let glyphs = runs.into_iter().flat_map(|run| {
+			let font = font::face(Some(&run.font), self.font_properties());
+			run.glyphs
+				.into_iter()
+				.map(move |glyph| (font.clone(), glyph))
+		});
+		let glyphs = glyphs.filter_map(|(font, glyph)| {
+			let face = font.face()?;
+			let scale = self.font_size / face.units_per_em() as f32;
+
+			let mut outline = GlyphOutline::default();
+			face.outline_glyph(ttf_parser::GlyphId(glyph.id), &mut outline)?;
+			let mut curve = outline.into_curve()?;
+
+			let placement: Transform2<f32> = match (&self.on_curve, length) {
+				(Some(on_curve), Some(length)) if length > 0. => {
+					// Glyphs are turned around their middle, sitting on the curve
+					let middle = start + glyph.offset.x + glyph.advance / 2.;
+					if !(0. ..=length).contains(&middle) {
+						return None;
+					}
+
+					let t = middle / length;
+					let point = on_curve.point_at(t)?;
+					let tangent = on_curve.tangent_at(t)?;
+
+					nalgebra::convert::<_, Transform2<f32>>(Translation2::new(point.x, point.y))
+						* nalgebra::convert::<_, Transform2<f32>>(Rotation2::new(
+							tangent.y.atan2(tangent.x),
+						)) * nalgebra::convert::<_, Transform2<f32>>(Translation2::new(
+						-glyph.advance / 2.,
+						glyph.offset.y,
+					))
+				}
+				_ => nalgebra::convert(Translation2::new(
+					start + glyph.offset.x,
+					baseline + glyph.offset.y,
+				)),
+			};
+
+			curve.transform(
+				placement * nalgebra::convert::<_, Transform2<f32>>(Scale2::new(scale, scale)),
+			);
+			Some(Shape::Curve(curve))
+		});
+
+		let lines = match length {
+			Some(_) => vec![],
+			None => self.decoration.lines(
+				&font::face(self.font.as_ref(), self.font_properties()).decorations(self.font_size),
+			),
+		};
+		let lines = lines.into_iter().map(|line| {
+			let bottom = baseline + line.offset - line.thickness / 2.;
+			let top = bottom + line.thickness;
+			let mut curve = Curve::default();
+			curve
+				.then(Point2::new(start, bottom))
+				.then(Point2::new(start + width, bottom))
+				.then(Point2::new(start + width, top))
+				.then(Point2::new(start, top))
+				.closed();
+			Shape::Curve(curve)
+		});
+
+		Shape::Group(Group {
+			local_transform: self.local_transform,
+			shapes: glyphs.chain(lines).collect(),
+			metadata: vec![],
+		})
+	}
+}
+
+impl Shape {
+	/// Replace every [`Text`] of the dessin, masks included, by its [outlines][Text::to_outlines]
+	///
+	/// Outlines keep the string of their text in a `text` metadata.
+	/// [`Shape::Dynamic`] are replaced by a group of the shape they generate now, to outline its texts too.
+	pub fn outline_texts(&mut self) {
+		struct OutlineTexts;
+		impl ShapeVisitorMut for OutlineTexts {
+			fn enter(&mut self, shape: &mut Shape, _: &VisitContext) -> VisitFlow {
+				match shape {
+					Shape::Text(text) => {
+						let mut outlines = text.to_outlines();
+						if let Shape::Group(group) = &mut outlines {
+							group.metadata.push(("text".to_string(), text.text.clone()));
+						}
+						*shape = outlines;
+						return VisitFlow::SkipChildren;
+					}
+					Shape::Dynamic {
+						local_transform,
+						shaper,
+					} => {
+						*shape = Shape::Group(Group {
+							local_transform: *local_transform,
+							shapes: vec![shaper()],
+							metadata: vec![],
+						});
+					}
+					Shape::Mask { mask, .. } => mask.outline_texts(),
+					_ => {}
+				}
+				VisitFlow::Continue
+			}
+		}
+
+		self.visit_mut(&mut OutlineTexts);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn glyphs(shape: &Shape) -> &[Shape] {
+		let Shape::Group(Group { shapes, .. }) = shape else {
+			panic!("Group expected");
+		};
+		shapes
+	}
+
+	#[test]
+	fn one_curve_per_glyph() {
+		let text = dessin!(Text(text = "Hi o", font_size = 10., translate = [5., 0.]));
+		let outlines = text.to_outlines();
+
+		// The space has no outline
+		let glyphs = glyphs(&outlines);
+		assert_eq!(glyphs.len(), 3);
+		let Shape::Curve(o) = &glyphs[2] else {
+			panic!("Curve expected");
+		};
+		assert_eq!(o.subpaths.len(), 1, "outer and inner contours of `o`");
+
+		let text_bb = Shape::from(text).local_bounding_box().straigthen();
+		let outlines_bb = outlines.local_bounding_box().straigthen();
+		assert!(outlines_bb.left() >= text_bb.left() - 1.);
+		assert!(outlines_bb.right() <= text_bb.right() + 1.);
+	}
+
+	#[test]
+	fn aligned_and_on_curve() {
+		let left = dessin!(Text(text = "H", font_size = 10.)).to_outlines();
+		let right =
+			dessin!(Text(text = "H", font_size = 10., align = TextAlign::Right)).to_outlines();
+		let left_bb = left.local_bounding_box().straigthen();
+		let right_bb = right.local_bounding_box().straigthen();
+		assert!(left_bb.left() >= 0.);
+		assert!(right_bb.right() <= 0.);
+
+		let on_curve = dessin!(Text(
+			text = "HHHH",
+			font_size = 10.,
+			on_curve = dessin!(Line(from = [0., 0.], to = [0., 100.])),
+		))
+		.to_outlines();
+		let bb = on_curve.local_bounding_box().straigthen();
+		// Going up, the text is turned a quarter to the left
+		assert!(bb.height() > bb.width());
+		assert!(bb.bottom() >= -1.);
+
+		let mut shape = dessin!([Text(text = "H"), Circle()]);
+		shape.outline_texts();
+		assert!(matches!(glyphs(&glyphs(&shape)[0])[0], Shape::Curve(_)));
+	}
+
+	#[test]
+	fn outline_dynamic_and_masked_texts() {
+		let text = dessin!(Text(text = "H", font_size = 10.));
+		let dynamic = Shape::Dynamic {
+			local_transform: nalgebra::convert(Translation2::new(5., 0.)),
+			shaper: std::sync::Arc::new({
+				let text = text.clone();
+				move || text.clone().into()
+			}),
+		};
+		let mut shape = dessin!([
+			{ dynamic },
+			Mask<Shape>(mask = text.clone(), shape = dessin!(Circle())),
+		]);
+		shape.outline_texts();
+
+		// The dynamic shape is frozen as a group of outlines, keeping the text as metadata
+		let Shape::Group(Group {
+			local_transform,
+			shapes,
+			..
+		}) = &glyphs(&shape)[0]
+		else {
+			panic!("Group expected");
+		};
+		assert_eq!(local_transform * Point2::origin(), Point2::new(5., 0.));
+		let Shape::Group(outlines) = &shapes[0] else {
+			panic!("Group expected");
+		};
+		assert!(matches!(outlines.shapes[0], Shape::Curve(_)));
+		assert_eq!(outlines.metadata, [("text".to_string(), "H".to_string())]);
+
+		let Shape::Mask { mask, .. } = &glyphs(&shape)[1] else {
+			panic!("Mask expected");
+		};
+		assert!(matches!(glyphs(mask)[0], Shape::Curve(_)));
+	}
+}
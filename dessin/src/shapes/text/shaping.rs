@@ -0,0 +1,404 @@
+use super::{
+	font::{self, Font, FontRef},
+	FontProperties, TextVerticalAlign,
+};
+use nalgebra::Vector2;
+use rustybuzz::{Face, UnicodeBuffer};
+use std::ops::Range;
+
+/// Glyph of a [`ShapedText`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapedGlyph {
+	/// Index of the glyph in the font
+	pub id: u16,
+	/// Byte index, in the text, of the first character drawn by this glyph
+	pub cluster: usize,
+	/// Origin of the glyph, from the start of the baseline. `x` goes along the text, `y` goes up.
+	pub offset: Vector2<f32>,
+	/// Distance to the origin of the next glyph, along the text
+	pub advance: f32,
+}
+
+/// Text turned into glyphs by [`shape_text`]
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ShapedText {
+	/// Glyphs, from left to right
+	pub glyphs: Vec<ShapedGlyph>,
+	/// Length of the text along the baseline
+	pub width: f32,
+}
+
+/// Part of a text drawn with a single font, by [`shape_runs`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapedRun {
+	/// Font of the text, or the fallback drawing characters it lacks
+	pub font: FontRef,
+	/// Bytes of the text drawn by the run
+	pub range: Range<usize>,
+	/// Glyphs of the run, placed from the start of the text, with clusters in the whole text
+	pub glyphs: Vec<ShapedGlyph>,
+	/// Length of the run along the baseline
+	pub width: f32,
+}
+
+/// Vertical metrics of a font at a given size
+///
+/// Distances are taken from the baseline, going up.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+	/// Height of the highest glyphs above the baseline
+	pub ascent: f32,
+	/// Depth of the lowest glyphs below the baseline, negative
+	pub descent: f32,
+	/// Extra space the font recommends between two lines
+	pub line_gap: f32,
+}
+impl FontMetrics {
+	/// Metrics of `font` for text of `font_size`
+	///
+	/// The font is parsed on each call, prefer [`FontFace::metrics`][super::font::FontFace::metrics].
+	pub fn of(font: &Font, font_size: f32) -> Self {
+		font::with_face(font, |face| {
+			face.map(|face| metrics(face, font_size))
+				.unwrap_or_default()
+		})
+	}
+
+	/// Distance from the lowest to the highest glyphs
+	#[inline]
+	pub fn height(&self) -> f32 {
+		self.ascent - self.descent
+	}
+
+	/// Distance from the lowest glyphs of a line to the lowest glyphs of the next one
+	#[inline]
+	pub fn line_height(&self) -> f32 {
+		self.height() + self.line_gap
+	}
+
+	/// Position of the baseline, relative to the reference point of a text aligned with `vertical_align`
+	pub fn baseline(&self, vertical_align: TextVerticalAlign) -> f32 {
+		match vertical_align {
+			TextVerticalAlign::Bottom => -self.descent,
+			TextVerticalAlign::Center => -(self.ascent + self.descent) / 2.,
+			TextVerticalAlign::Top => -self.ascent,
+			TextVerticalAlign::Baseline => 0.,
+		}
+	}
+}
+
+/// Line of a [`TextDecoration`][super::TextDecoration]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct DecorationLine {
+	/// Distance from the baseline to the middle of the line, going up
+	pub offset: f32,
+	/// Thickness of the line
+	pub thickness: f32,
+}
+
+/// Lines a font draws along its text at a given size
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct DecorationMetrics {
+	/// Line below the baseline
+	pub underline: DecorationLine,
+	/// Line through lowercase letters
+	pub strike_through: DecorationLine,
+	/// Line inside the top of the ascent
+	pub overline: DecorationLine,
+}
+impl DecorationMetrics {
+	/// Decoration lines of `font` for text of `font_size`
+	///
+	/// The font is parsed on each call, prefer [`FontFace::decorations`][super::font::FontFace::decorations].
+	pub fn of(font: &Font, font_size: f32) -> Self {
+		font::with_face(font, |face| {
+			face.map(|face| decoration_metrics(face, font_size))
+				.unwrap_or_default()
+		})
+	}
+}
+
+pub(super) fn metrics(face: &Face, font_size: f32) -> FontMetrics {
+	let scale = font_size / face.units_per_em() as f32;
+
+	FontMetrics {
+		ascent: face.ascender() as f32 * scale,
+		descent: face.descender() as f32 * scale,
+		line_gap: face.line_gap() as f32 * scale,
+	}
+}
+
+pub(super) fn decoration_metrics(face: &Face, font_size: f32) -> DecorationMetrics {
+	let units_per_em = face.units_per_em() as f32;
+	let scale = font_size / units_per_em;
+
+	// Fonts without these lines get them where most fonts put them
+	let underline = face.underline_metrics();
+	let thickness = underline
+		.map(|line| line.thickness as f32)
+		.filter(|thickness| *thickness > 0.)
+		.unwrap_or(units_per_em / 20.);
+	let underline_offset = underline
+		.map(|line| line.position as f32)
+		.unwrap_or(-units_per_em / 10.);
+
+	// The strikeout position is the top of the line
+	let strikeout = face.strikeout_metrics();
+	let strikeout_thickness = strikeout
+		.map(|line| line.thickness as f32)
+		.filter(|thickness| *thickness > 0.)
+		.unwrap_or(thickness);
+	let strikeout_offset = strikeout
+		.map(|line| line.position as f32 - strikeout_thickness / 2.)
+		.unwrap_or_else(|| {
+			face.x_height()
+				.map(|x_height| x_height as f32 / 2.)
+				.unwrap_or(face.ascender() as f32 / 3.)
+		});
+
+	DecorationMetrics {
+		underline: DecorationLine {
+			offset: underline_offset * scale,
+			thickness: thickness * scale,
+		},
+		strike_through: DecorationLine {
+			offset: strikeout_offset * scale,
+			thickness: strikeout_thickness * scale,
+		},
+		overline: DecorationLine {
+			offset: (face.ascender() as f32 - thickness / 2.) * scale,
+			thickness: thickness * scale,
+		},
+	}
+}
+
+/// Turn `text` into glyphs of `font`, with ligatures, kerning and the rules of its script
+///
+/// Bounding boxes, layouts and exporters all rely on it, so that text is measured as it is drawn.
+/// Right to left scripts are detected and their glyphs are given from left to right.
+///
+/// The font is parsed on each call, prefer [`FontFace::shape`][super::font::FontFace::shape].
+pub fn shape_text(font: &Font, text: &str, font_size: f32) -> ShapedText {
+	font::with_face(font, |face| {
+		face.map(|face| shape(face, text, font_size))
+			.unwrap_or_default()
+	})
+}
+
+pub(super) fn shape(face: &Face, text: &str, font_size: f32) -> ShapedText {
+	let scale = font_size / face.units_per_em() as f32;
+
+	let mut buffer = UnicodeBuffer::new();
+	buffer.push_str(text);
+	buffer.guess_segment_properties();
+	let buffer = rustybuzz::shape(face, &[], buffer);
+
+	let mut pen = Vector2::zeros();
+	let glyphs = buffer
+		.glyph_infos()
+		.iter()
+		.zip(buffer.glyph_positions())
+		.map(|(info, position)| {
+			let glyph = ShapedGlyph {
+				id: info.glyph_id as u16,
+				cluster: info.cluster as usize,
+				offset: pen + Vector2::new(position.x_offset, position.y_offset).cast() * scale,
+				advance: position.x_advance as f32 * scale,
+			};
+			pen += Vector2::new(position.x_advance, position.y_advance).cast() * scale;
+			glyph
+		})
+		.collect();
+
+	ShapedText {
+		glyphs,
+		width: pen.x,
+	}
+}
+
+/// Split `text` by [fallback][font::set_fallbacks] of `font`, and shape each part with its own font
+///
+/// Each character goes to the first font of the chain having it, or to `font` if none does.
+/// Spaces stay in the run they are in.
+pub fn shape_runs(
+	font: Option<&FontRef>,
+	properties: FontProperties,
+	text: &str,
+	font_size: f32,
+) -> Vec<ShapedRun> {
+	let font = font.cloned().unwrap_or_default();
+	let primary = font::face(Some(&font), properties);
+	let drawn = |c: char| !c.is_whitespace() && !c.is_control();
+	let missing = text.chars().any(|c| drawn(c) && !primary.has_glyph(c));
+
+	// Index of the font in the chain and bytes of each run
+	let mut chain = vec![(primary, font)];
+	let mut runs: Vec<(usize, Range<usize>)> = vec![];
+	if missing {
+		let fallbacks = font::fallbacks(&chain[0].1);
+		chain.extend(
+			fallbacks
+				.into_iter()
+				.map(|font| (font::face(Some(&font), properties), font)),
+		);
+
+		for (idx, c) in text.char_indices() {
+			let end = idx + c.len_utf8();
+			let chosen = drawn(c).then(|| {
+				chain
+					.iter()
+					.position(|(face, _)| face.has_glyph(c))
+					.unwrap_or_default()
+			});
+
+			match (runs.last_mut(), chosen) {
+				(Some((_, range)), None) => range.end = end,
+				(Some((font, range)), Some(chosen)) if *font == chosen => range.end = end,
+				(_, chosen) => runs.push((chosen.unwrap_or_default(), idx..end)),
+			}
+		}
+	} else {
+		runs.push((0, 0..text.len()));
+	}
+
+	let mut pen = 0.;
+	runs.into_iter()
+		.map(|(font, range)| {
+			let (face, font) = &chain[font];
+			let shaped = face.shape(&text[range.clone()], font_size);
+			let glyphs = shaped
+				.glyphs
+				.into_iter()
+				.map(|glyph| ShapedGlyph {
+					cluster: glyph.cluster + range.start,
+					offset: glyph.offset + Vector2::new(pen, 0.),
+					..glyph
+				})
+				.collect();
+			pen += shaped.width;
+
+			ShapedRun {
+				font: font.clone(),
+				range,
+				glyphs,
+				width: shaped.width,
+			}
+		})
+		.collect()
+}
+
+/// Add `letter_spacing` after each character of `runs`, and `word_spacing` after each space
+///
+/// Glyphs drawing several characters, as ligatures, are followed by a single letter spacing.
+/// Runs are those of `text`, as given by [`shape_runs`].
+pub fn space_runs(runs: &mut [ShapedRun], text: &str, letter_spacing: f32, word_spacing: f32) {
+	if letter_spacing == 0. && word_spacing == 0. {
+		return;
+	}
+
+	let clusters = runs
+		.iter()
+		.flat_map(|run| run.glyphs.iter().map(|glyph| glyph.cluster))
+		.collect::<Vec<_>>();
+	let mut next = clusters.iter().skip(1);
+	let mut shift = 0.;
+	for run in runs {
+		let start = shift;
+		for glyph in &mut run.glyphs {
+			glyph.offset.x += shift;
+
+			// Marks and other glyphs of the same character are spaced with it
+			if next.next() != Some(&glyph.cluster) {
+				shift += letter_spacing;
+				if text[glyph.cluster..].starts_with([' ', '\u{a0}']) {
+					shift += word_spacing;
+				}
+			}
+		}
+		run.width += shift - start;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::font::get_or_default;
+
+	#[test]
+	fn kerning_and_ligatures() {
+		let font = get_or_default(None).get(FontProperties::REGULAR).clone();
+
+		let shaped = shape_text(&font, "AV", 10.);
+		assert_eq!(shaped.glyphs.len(), 2);
+		assert_eq!(shaped.glyphs[1].cluster, 1);
+
+		// Kerning brings letters closer than their own advances
+		let alone = shape_text(&font, "A", 10.).width + shape_text(&font, "V", 10.).width;
+		assert!(shaped.width < alone);
+
+		// Helvetica draws "fi" with a single glyph, that isn't the one of "f"
+		let helvetica = Font::OTF(include_bytes!("../../Helvetica.otf").to_vec());
+		let ligature = shape_text(&helvetica, "fi", 10.);
+		assert_eq!(ligature.glyphs.len(), 1);
+		assert_eq!(ligature.glyphs[0].cluster, 0);
+		assert_ne!(
+			ligature.glyphs[0].id,
+			shape_text(&helvetica, "f", 10.).glyphs[0].id
+		);
+
+		// Right to left glyphs are given from left to right, the last letter first
+		let hebrew = shape_text(&font, "\u{5e9}\u{5dc}\u{5d5}\u{5dd}", 10.);
+		let clusters = hebrew.glyphs.iter().map(|v| v.cluster).collect::<Vec<_>>();
+		assert_eq!(clusters, [6, 4, 2, 0]);
+
+		let empty = shape_text(&font, "", 10.);
+		assert_eq!(empty, ShapedText::default());
+	}
+
+	#[test]
+	fn metrics() {
+		let font = get_or_default(None).get(FontProperties::REGULAR).clone();
+
+		let metrics = FontMetrics::of(&font, 10.);
+		assert!(metrics.ascent > 0.);
+		assert!(metrics.descent < 0.);
+		assert!(metrics.line_height() >= metrics.height());
+		assert_eq!(FontMetrics::of(&font, 20.).height(), 2. * metrics.height());
+
+		assert_eq!(metrics.baseline(TextVerticalAlign::Baseline), 0.);
+		assert_eq!(
+			metrics.baseline(TextVerticalAlign::Bottom) + metrics.descent,
+			0.
+		);
+		assert_eq!(
+			metrics.baseline(TextVerticalAlign::Top) + metrics.ascent,
+			0.
+		);
+		let center = metrics.baseline(TextVerticalAlign::Center);
+		assert!((center + metrics.ascent + center + metrics.descent).abs() < 10e-6);
+	}
+
+	#[test]
+	fn fallbacks() {
+		let helvetica = font::add_font(
+			"Helvetica fallback",
+			font::FontGroup::new(Font::OTF(include_bytes!("../../Helvetica.otf").to_vec())),
+		);
+		let font = font::add_font("Hyperlegible with fallback", get_or_default(None));
+		font::set_fallbacks(font.clone(), [helvetica.clone()]);
+
+		// Hyperlegible has no ohm sign
+		let text = "1 \u{2126} 2";
+		let runs = shape_runs(Some(&font), FontProperties::REGULAR, text, 10.);
+		let fonts = runs.iter().map(|run| &run.font).collect::<Vec<_>>();
+		assert_eq!(fonts, [&font, &helvetica, &font]);
+		assert_eq!(&text[runs[1].range.clone()], "\u{2126} ");
+		assert_eq!(runs[1].glyphs[0].cluster, 2);
+		assert_eq!(runs[1].glyphs[0].offset.x, runs[0].width);
+
+		// Without fallback, the font draws what it can
+		let alone = shape_runs(None, FontProperties::REGULAR, text, 10.);
+		assert_eq!(alone.len(), 1);
+		assert_eq!(alone[0].range, 0..text.len());
+	}
+}
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use nalgebra::{Rotation2, Scale2, Transform2, Translation2, Vector2};
+use nalgebra::{Point2, Rotation2, Scale2, Transform2, Translation2, Vector2};
 use palette::{IntoColor, Srgba};
 use std::{
 	f32::consts::FRAC_1_SQRT_2,
@@ -7,7 +7,7 @@ use std::{
 };
 
 /// Calculated result style
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StylePosition {
 	///
 	pub stroke: Option<Stroke>,
@@ -15,45 +15,110 @@ pub struct StylePosition {
 	pub fill: Option<Fill>,
 }
 
-/// `Stroke`
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Stroke {
-	/// Solid line
-	Solid {
-		///
-		color: Srgba,
-		///
-		width: f32,
-	},
+/// Shape at the end of open subpaths and dashes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineCap {
+	/// The stroke stops at the end of the line
+	#[default]
+	Butt,
+	/// The stroke ends with a half circle
+	Round,
+	/// The stroke extends past the end of the line by half its width
+	Square,
+}
 
-	/// Dashed line
-	Dashed {
-		///
-		color: Srgba,
-		///
-		width: f32,
-		///
-		on: f32,
-		///
-		off: f32,
-	},
+/// Shape of the corners between two segments
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineJoin {
+	/// Sharp corner, beveled when exceeding the miter limit
+	#[default]
+	Miter,
+	/// Rounded corner
+	Round,
+	/// Corner cut at the end of both segments
+	Bevel,
+}
+
+/// `Stroke`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+	/// Color of the line
+	pub color: Srgba,
+	/// Width of the line
+	pub width: f32,
+	/// Shape at the end of open subpaths and dashes
+	pub cap: LineCap,
+	/// Shape of the corners
+	pub join: LineJoin,
+	/// Limit of the ratio between the miter length and the width, over which miter joins are beveled
+	pub miter_limit: f32,
+	/// Alternating lengths of dashes and gaps. Empty for a solid line.
+	pub dash: Vec<f32>,
+	/// Distance into the dash pattern at which the line starts
+	pub dash_offset: f32,
 }
 impl Stroke {
+	/// Default miter limit, as in SVG
+	pub const DEFAULT_MITER_LIMIT: f32 = 4.;
+
 	/// Solid line
 	pub fn new_solid<F: IntoColor<Srgba>>(color: F, width: f32) -> Self {
-		let color = color.into_color();
-		Self::Solid { color, width }
+		Stroke {
+			color: color.into_color(),
+			width,
+			cap: LineCap::default(),
+			join: LineJoin::default(),
+			miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+			dash: vec![],
+			dash_offset: 0.,
+		}
 	}
 
 	/// Dashed line
 	pub fn new_dashed<F: IntoColor<Srgba>>(color: F, width: f32, on: f32, off: f32) -> Self {
-		let color = color.into_color();
-		Self::Dashed {
-			color,
-			width,
-			on,
-			off,
+		Stroke::new_solid(color, width).with_dash([on, off], 0.)
+	}
+
+	/// Whether the line is dashed
+	#[inline]
+	pub fn is_dashed(&self) -> bool {
+		self.dash.iter().any(|v| *v > 0.)
+	}
+
+	/// Change the cap
+	#[inline]
+	pub fn with_cap(mut self, cap: LineCap) -> Self {
+		self.cap = cap;
+		self
+	}
+
+	/// Change the join
+	#[inline]
+	pub fn with_join(mut self, join: LineJoin) -> Self {
+		self.join = join;
+		self
+	}
+
+	/// Change the miter limit
+	#[inline]
+	pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+		self.miter_limit = miter_limit;
+		self
+	}
+
+	/// Change the dash pattern and its offset
+	///
+	/// As in SVG, a pattern with an odd number of values is repeated to get an even number of values.
+	pub fn with_dash<I: IntoIterator<Item = f32>>(mut self, dash: I, dash_offset: f32) -> Self {
+		self.dash = dash.into_iter().map(|v| v.max(0.)).collect();
+		if self.dash.len() % 2 == 1 {
+			self.dash.extend_from_within(..);
 		}
+		self.dash_offset = dash_offset;
+		self
 	}
 }
 
@@ -67,40 +132,216 @@ impl Mul<Stroke> for Transform2<f32> {
 	type Output = Stroke;
 
 	fn mul(self, rhs: Stroke) -> Self::Output {
-		match rhs {
-			Stroke::Solid { color, width } => Stroke::Solid {
-				color,
-				width: (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude() * width,
-			},
-			Stroke::Dashed {
-				color,
-				width,
-				on,
-				off,
-			} => {
-				let factor = (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
-
-				Stroke::Dashed {
-					color,
-					width: width * factor,
-					on: on * factor,
-					off: off * factor,
-				}
-			}
+		let factor = (self * Vector2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)).magnitude();
+
+		Stroke {
+			width: rhs.width * factor,
+			dash: rhs.dash.into_iter().map(|v| v * factor).collect(),
+			dash_offset: rhs.dash_offset * factor,
+			..rhs
 		}
 	}
 }
 
-/// `Stroke`
+/// Color at a given offset of a gradient
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientStop {
+	/// Position of the stop along the gradient, between 0 and 1
+	pub offset: f32,
+	/// Color of the stop
+	pub color: Srgba,
+}
+impl GradientStop {
+	/// New stop
+	pub fn new<C: IntoColor<Srgba>>(offset: f32, color: C) -> Self {
+		GradientStop {
+			offset,
+			color: color.into_color(),
+		}
+	}
+}
+
+impl<C: IntoColor<Srgba>> From<(f32, C)> for GradientStop {
+	fn from((offset, color): (f32, C)) -> Self {
+		GradientStop::new(offset, color)
+	}
+}
+
+/// How a gradient behaves outside of its `[0, 1]` range
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradientSpread {
+	/// Extend the first and last stops
+	#[default]
+	Pad,
+	/// Mirror the gradient back and forth
+	Reflect,
+	/// Repeat the gradient from the start
+	Repeat,
+}
+
+/// `Fill`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Fill {
 	/// Solid fill
 	Solid {
 		///
 		color: Srgba,
 	},
+
+	/// Gradient along the line from `start` to `end`
+	///
+	/// When `start` and `end` are the same point, the last stop fills the whole area.
+	/// The PDF exporter ignores the alpha of the stops.
+	LinearGradient {
+		/// Point where the gradient is at offset 0, in gradient space
+		start: Point2<f32>,
+		/// Point where the gradient is at offset 1, in gradient space
+		end: Point2<f32>,
+		/// Colors of the gradient, sorted by offset
+		stops: Vec<GradientStop>,
+		/// How the gradient behaves outside of `[0, 1]`
+		spread: GradientSpread,
+		/// Transform from gradient space to user space
+		transform: Transform2<f32>,
+	},
+
+	/// Gradient radiating from `focal` to the circle of `center` and `radius`
+	///
+	/// When `radius` is 0, the last stop fills the whole area.
+	/// The PDF exporter ignores the alpha of the stops.
+	RadialGradient {
+		/// Center of the circle where the gradient is at offset 1, in gradient space
+		center: Point2<f32>,
+		/// Radius of the circle where the gradient is at offset 1, in gradient space
+		radius: f32,
+		/// Point where the gradient is at offset 0, in gradient space
+		focal: Point2<f32>,
+		/// Colors of the gradient, sorted by offset
+		stops: Vec<GradientStop>,
+		/// How the gradient behaves outside of `[0, 1]`
+		spread: GradientSpread,
+		/// Transform from gradient space to user space
+		transform: Transform2<f32>,
+	},
+}
+impl Fill {
+	/// Linear gradient from `start` to `end`
+	pub fn new_linear_gradient<P, S, I>(start: P, end: P, stops: I) -> Self
+	where
+		P: Into<Point2<f32>>,
+		S: Into<GradientStop>,
+		I: IntoIterator<Item = S>,
+	{
+		Fill::LinearGradient {
+			start: start.into(),
+			end: end.into(),
+			stops: sorted_stops(stops),
+			spread: GradientSpread::default(),
+			transform: Transform2::default(),
+		}
+	}
+
+	/// Radial gradient from `center` to the circle of `radius`
+	pub fn new_radial_gradient<P, S, I>(center: P, radius: f32, stops: I) -> Self
+	where
+		P: Into<Point2<f32>>,
+		S: Into<GradientStop>,
+		I: IntoIterator<Item = S>,
+	{
+		let center = center.into();
+		Fill::RadialGradient {
+			center,
+			radius,
+			focal: center,
+			stops: sorted_stops(stops),
+			spread: GradientSpread::default(),
+			transform: Transform2::default(),
+		}
+	}
+
+	/// Change the spread of a gradient. Does nothing on a solid fill.
+	pub fn with_spread(mut self, new_spread: GradientSpread) -> Self {
+		match &mut self {
+			Fill::Solid { .. } => {}
+			Fill::LinearGradient { spread, .. } | Fill::RadialGradient { spread, .. } => {
+				*spread = new_spread;
+			}
+		}
+		self
+	}
+
+	/// Move the starting point of a radial gradient. Does nothing on other fills.
+	pub fn with_focal<P: Into<Point2<f32>>>(mut self, new_focal: P) -> Self {
+		if let Fill::RadialGradient { focal, .. } = &mut self {
+			*focal = new_focal.into();
+		}
+		self
+	}
+
+	/// Color of the last stop of a gradient without extent, that fills the whole area
+	///
+	/// Gradients without extent are linear gradients whose `start` and `end` are the same point
+	/// and radial gradients of radius 0. Other fills return `None`.
+	pub fn degenerate_color(&self) -> Option<Srgba> {
+		let stops = match self {
+			Fill::LinearGradient {
+				start, end, stops, ..
+			} if start == end => stops,
+			Fill::RadialGradient { radius, stops, .. } if *radius <= 0. => stops,
+			_ => return None,
+		};
+
+		Some(stops.last().map(|s| s.color).unwrap_or_default())
+	}
+
+	/// Color of the fill at a given offset of the gradient.
+	///
+	/// Solid fills always return their color.
+	pub fn color_at(&self, offset: f32) -> Srgba {
+		let (stops, spread) = match self {
+			Fill::Solid { color } => return *color,
+			Fill::LinearGradient { stops, spread, .. }
+			| Fill::RadialGradient { stops, spread, .. } => (stops, spread),
+		};
+
+		let offset = match spread {
+			GradientSpread::Pad => offset.clamp(0., 1.),
+			GradientSpread::Repeat => offset.rem_euclid(1.),
+			GradientSpread::Reflect => {
+				let t = offset.rem_euclid(2.);
+				if t > 1. {
+					2. - t
+				} else {
+					t
+				}
+			}
+		};
+
+		match stops.iter().position(|s| s.offset >= offset) {
+			None => stops.last().map(|s| s.color).unwrap_or_default(),
+			Some(0) => stops[0].color,
+			Some(idx) => {
+				let (a, b) = (stops[idx - 1], stops[idx]);
+				let t = (offset - a.offset) / (b.offset - a.offset);
+				Srgba::new(
+					a.color.red + (b.color.red - a.color.red) * t,
+					a.color.green + (b.color.green - a.color.green) * t,
+					a.color.blue + (b.color.blue - a.color.blue) * t,
+					a.color.alpha + (b.color.alpha - a.color.alpha) * t,
+				)
+			}
+		}
+	}
+}
+
+fn sorted_stops<S: Into<GradientStop>, I: IntoIterator<Item = S>>(stops: I) -> Vec<GradientStop> {
+	let mut stops = stops.into_iter().map(Into::into).collect::<Vec<_>>();
+	stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+	stops
 }
-impl Stroke {}
 
 impl<C: IntoColor<Srgba>> From<C> for Fill {
 	fn from(color: C) -> Self {
@@ -116,6 +357,34 @@ impl Mul<Fill> for Transform2<f32> {
 	fn mul(self, rhs: Fill) -> Self::Output {
 		match rhs {
 			Fill::Solid { color } => Fill::Solid { color },
+			Fill::LinearGradient {
+				start,
+				end,
+				stops,
+				spread,
+				transform,
+			} => Fill::LinearGradient {
+				start,
+				end,
+				stops,
+				spread,
+				transform: self * transform,
+			},
+			Fill::RadialGradient {
+				center,
+				radius,
+				focal,
+				stops,
+				spread,
+				transform,
+			} => Fill::RadialGradient {
+				center,
+				radius,
+				focal,
+				stops,
+				spread,
+				transform: self * transform,
+			},
 		}
 	}
 }
@@ -225,3 +494,54 @@ impl<T: ShapeBoundingBox> ShapeBoundingBox for Style<T> {
 		self.shape.local_bounding_box()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_float_eq::*;
+	use palette::{named, Srgb};
+
+	#[test]
+	fn gradient_color_at() {
+		let black: Srgba = Srgb::<f32>::from_format(named::BLACK).into();
+		let white: Srgba = Srgb::<f32>::from_format(named::WHITE).into();
+		let fill = Fill::new_linear_gradient([0., 0.], [10., 0.], [(1., white), (0., black)]);
+
+		assert_f32_near!(fill.color_at(0.5).red, 0.5);
+		assert_f32_near!(fill.color_at(-1.).red, 0.);
+		assert_f32_near!(fill.color_at(2.).red, 1.);
+
+		let fill = fill.with_spread(GradientSpread::Repeat);
+		assert_f32_near!(fill.color_at(1.25).red, 0.25);
+
+		let fill = fill.with_spread(GradientSpread::Reflect);
+		assert_f32_near!(fill.color_at(1.25).red, 0.75);
+		assert_eq!(fill.degenerate_color(), None);
+
+		let point = Fill::new_linear_gradient([5., 5.], [5., 5.], [(0., black), (1., white)]);
+		assert_eq!(point.degenerate_color(), Some(white));
+		let point = Fill::new_radial_gradient([5., 5.], 0., [(0., white), (1., black)]);
+		assert_eq!(point.degenerate_color(), Some(black));
+	}
+
+	#[test]
+	fn stroke_scale() {
+		let stroke = Stroke::new_dashed(Srgba::new(0., 0., 0., 1.), 1., 2., 1.)
+			.with_cap(LineCap::Round)
+			.with_miter_limit(10.);
+		let stroke = nalgebra::convert::<_, Transform2<f32>>(Scale2::new(2., 2.)) * stroke;
+
+		assert_f32_near!(stroke.width, 2.);
+		assert_f32_near!(stroke.dash[0], 4.);
+		assert_f32_near!(stroke.dash[1], 2.);
+		assert_eq!(stroke.cap, LineCap::Round);
+		assert_f32_near!(stroke.miter_limit, 10.);
+	}
+
+	#[test]
+	fn odd_dash_pattern() {
+		let stroke = Stroke::new_solid(Srgba::new(0., 0., 0., 1.), 1.).with_dash([1., 2., 3.], 0.);
+
+		assert_eq!(stroke.dash, vec![1., 2., 3., 1., 2., 3.]);
+	}
+}
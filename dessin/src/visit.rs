@@ -0,0 +1,365 @@
+//! Walk a dessin, and rewrite it, without an [`Exporter`][crate::export::Exporter].
+//!
+//! Implement [`ShapeVisitor`] or [`ShapeVisitorMut`] and give it to [`Shape::visit`] or [`Shape::visit_mut`].
+//! For common needs, [`Shape::find_by_metadata`], [`Shape::map_texts`] and [`Shape::retain`] are already there.
+//!
+//! ## Example
+//! ```
+//! # use dessin::{palette::Srgba, prelude::*, visit::*};
+//! struct Recolour;
+//! impl ShapeVisitorMut for Recolour {
+//! 	fn enter(&mut self, shape: &mut Shape, _: &VisitContext) -> VisitFlow {
+//! 		if let Shape::Style { fill: Some(fill), .. } = shape {
+//! 			*fill = Srgba::new(0., 0., 1., 1.).into();
+//! 		}
+//! 		VisitFlow::Continue
+//! 	}
+//! }
+//!
+//! let mut shape = dessin!([*Circle(fill = Srgba::new(1., 0., 0., 1.))]);
+//! shape.visit_mut(&mut Recolour);
+//! ```
+use crate::prelude::*;
+use nalgebra::Transform2;
+
+/// Where a visited shape is in the dessin
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisitContext {
+	/// Transform of the parents of the shape, its own transform excluded
+	pub parent_transform: Transform2<f32>,
+	/// Style inherited from the parents, as given to an [`Exporter`][crate::export::Exporter]
+	pub style: StylePosition,
+	/// Metadata of each group containing the shape, from the root
+	pub metadata: Vec<Vec<(String, String)>>,
+	/// Index of the shape in each group containing it, from the root
+	pub path: Vec<usize>,
+}
+impl Default for VisitContext {
+	fn default() -> Self {
+		VisitContext {
+			parent_transform: Transform2::identity(),
+			style: StylePosition {
+				fill: None,
+				stroke: None,
+			},
+			metadata: vec![],
+			path: vec![],
+		}
+	}
+}
+impl VisitContext {
+	/// Context of the shape at `idx` in `group`
+	fn in_group(&self, group: &Group, idx: usize) -> VisitContext {
+		let mut context = self.clone();
+		context.parent_transform = self.parent_transform * group.local_transform;
+		context.metadata.push(group.metadata.clone());
+		context.path.push(idx);
+		context
+	}
+
+	/// Context of the shape styled by `fill` and `stroke`
+	fn in_style(&self, fill: &Option<Fill>, stroke: &Option<Stroke>) -> VisitContext {
+		VisitContext {
+			style: StylePosition {
+				fill: fill.clone().map(|v| self.parent_transform * v),
+				stroke: stroke.clone().map(|v| self.parent_transform * v),
+			},
+			..self.clone()
+		}
+	}
+}
+
+/// What to do after entering a shape
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VisitFlow {
+	/// Visit the children of the shape
+	#[default]
+	Continue,
+	/// Don't visit the children of the shape
+	SkipChildren,
+	/// Stop the whole visit
+	Stop,
+}
+
+/// Visitor of a dessin, see [`Shape::visit`]
+///
+/// Children are the shapes of a [`Shape::Group`] and the shape of a [`Shape::Style`], [`Shape::Clip`] or [`Shape::Mask`].
+/// Masks and shapes generated by a [`Shape::Dynamic`] are not visited.
+pub trait ShapeVisitor<'a> {
+	/// Called on a shape, before its children
+	fn enter(&mut self, shape: &'a Shape, context: &VisitContext) -> VisitFlow;
+
+	/// Called on a shape, after its children
+	fn leave(&mut self, _shape: &'a Shape, _context: &VisitContext) {}
+}
+
+/// Visitor allowed to rewrite a dessin, see [`Shape::visit_mut`]
+///
+/// Children are the shapes of a [`Shape::Group`] and the shape of a [`Shape::Style`], [`Shape::Clip`] or [`Shape::Mask`].
+/// Masks and shapes generated by a [`Shape::Dynamic`] are not visited.
+pub trait ShapeVisitorMut {
+	/// Called on a shape, before its children. The shape can be replaced here, the new one's children are then visited.
+	fn enter(&mut self, shape: &mut Shape, context: &VisitContext) -> VisitFlow;
+
+	/// Called on a shape, after its children
+	fn leave(&mut self, _shape: &mut Shape, _context: &VisitContext) {}
+}
+
+/// Returns false when the visit should stop
+fn walk<'a, V: ShapeVisitor<'a>>(
+	shape: &'a Shape,
+	context: &VisitContext,
+	visitor: &mut V,
+) -> bool {
+	match visitor.enter(shape, context) {
+		VisitFlow::Stop => return false,
+		VisitFlow::SkipChildren => {}
+		VisitFlow::Continue => {
+			let keep_going = match shape {
+				Shape::Group(group) => group
+					.shapes
+					.iter()
+					.enumerate()
+					.all(|(idx, child)| walk(child, &context.in_group(group, idx), visitor)),
+				Shape::Style {
+					fill,
+					stroke,
+					shape,
+				} => walk(shape, &context.in_style(fill, stroke), visitor),
+				Shape::Clip { shape, .. } | Shape::Mask { shape, .. } => {
+					walk(shape, context, visitor)
+				}
+				Shape::Ellipse(_)
+				| Shape::Image(_)
+				| Shape::Text(_)
+				| Shape::Curve(_)
+				| Shape::Dynamic { .. } => true,
+			};
+
+			if !keep_going {
+				return false;
+			}
+		}
+	}
+
+	visitor.leave(shape, context);
+	true
+}
+
+/// Returns false when the visit should stop
+fn walk_mut<V: ShapeVisitorMut>(
+	shape: &mut Shape,
+	context: &VisitContext,
+	visitor: &mut V,
+) -> bool {
+	match visitor.enter(shape, context) {
+		VisitFlow::Stop => return false,
+		VisitFlow::SkipChildren => {}
+		VisitFlow::Continue => {
+			let keep_going = match shape {
+				Shape::Group(group) => {
+					let mut keep_going = true;
+					for idx in 0..group.shapes.len() {
+						let context = context.in_group(group, idx);
+						if !walk_mut(&mut group.shapes[idx], &context, visitor) {
+							keep_going = false;
+							break;
+						}
+					}
+					keep_going
+				}
+				Shape::Style {
+					fill,
+					stroke,
+					shape,
+				} => walk_mut(shape, &context.in_style(fill, stroke), visitor),
+				Shape::Clip { shape, .. } | Shape::Mask { shape, .. } => {
+					walk_mut(shape, context, visitor)
+				}
+				Shape::Ellipse(_)
+				| Shape::Image(_)
+				| Shape::Text(_)
+				| Shape::Curve(_)
+				| Shape::Dynamic { .. } => true,
+			};
+
+			if !keep_going {
+				return false;
+			}
+		}
+	}
+
+	visitor.leave(shape, context);
+	true
+}
+
+impl Shape {
+	/// Walk the dessin with `visitor`
+	pub fn visit<'a, V: ShapeVisitor<'a>>(&'a self, visitor: &mut V) {
+		walk(self, &VisitContext::default(), visitor);
+	}
+
+	/// Walk the dessin with `visitor`, allowed to rewrite it
+	pub fn visit_mut<V: ShapeVisitorMut>(&mut self, visitor: &mut V) {
+		walk_mut(self, &VisitContext::default(), visitor);
+	}
+
+	/// All groups having some metadata under `key`
+	pub fn find_by_metadata(&self, key: &str) -> Vec<&Shape> {
+		struct Find<'a, 'k> {
+			key: &'k str,
+			found: Vec<&'a Shape>,
+		}
+		impl<'a> ShapeVisitor<'a> for Find<'a, '_> {
+			fn enter(&mut self, shape: &'a Shape, _: &VisitContext) -> VisitFlow {
+				if let Shape::Group(Group { metadata, .. }) = shape {
+					if metadata.iter().any(|(k, _)| k == self.key) {
+						self.found.push(shape);
+					}
+				}
+				VisitFlow::Continue
+			}
+		}
+
+		let mut find = Find { key, found: vec![] };
+		self.visit(&mut find);
+		find.found
+	}
+
+	/// Change every [`Text`] of the dessin with `f`
+	pub fn map_texts<F: FnMut(&mut Text, &VisitContext)>(&mut self, f: F) {
+		struct MapTexts<F>(F);
+		impl<F: FnMut(&mut Text, &VisitContext)> ShapeVisitorMut for MapTexts<F> {
+			fn enter(&mut self, shape: &mut Shape, context: &VisitContext) -> VisitFlow {
+				if let Shape::Text(text) = shape {
+					(self.0)(text, context);
+				}
+				VisitFlow::Continue
+			}
+		}
+
+		self.visit_mut(&mut MapTexts(f));
+	}
+
+	/// Remove every shape of the groups for which `f` returns false
+	pub fn retain<F: FnMut(&Shape, &VisitContext) -> bool>(&mut self, f: F) {
+		struct Retain<F>(F);
+		impl<F: FnMut(&Shape, &VisitContext) -> bool> ShapeVisitorMut for Retain<F> {
+			fn enter(&mut self, shape: &mut Shape, context: &VisitContext) -> VisitFlow {
+				if let Shape::Group(group) = shape {
+					let shapes = std::mem::take(&mut group.shapes);
+					for (idx, child) in shapes.into_iter().enumerate() {
+						// Index in the original group, before any removal
+						if (self.0)(&child, &context.in_group(group, idx)) {
+							group.shapes.push(child);
+						}
+					}
+				}
+				VisitFlow::Continue
+			}
+		}
+
+		self.visit_mut(&mut Retain(f));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nalgebra::{Point2, Translation2};
+	use palette::Srgba;
+
+	fn debug_layer() -> Shape {
+		let mut debug = dessin!([Circle(radius = 1.), Text(text = "debug")]);
+		debug.add_metadata(("layer", "debug"));
+		debug
+	}
+
+	#[test]
+	fn context() {
+		struct Collect(Vec<(Point2<f32>, bool, Vec<usize>)>);
+		impl<'a> ShapeVisitor<'a> for Collect {
+			fn enter(&mut self, shape: &'a Shape, context: &VisitContext) -> VisitFlow {
+				if let Shape::Text(text) = shape {
+					self.0.push((
+						text.position(&context.parent_transform).reference_start,
+						context.style.fill.is_some(),
+						context.path.clone(),
+					));
+				}
+				VisitFlow::Continue
+			}
+		}
+
+		let shape = dessin!([
+			Circle(),
+			*Text(
+				text = "a",
+				vertical_align = TextVerticalAlign::Baseline,
+				fill = Srgba::new(0., 0., 0., 1.)
+			),
+			[Text(
+				text = "b",
+				vertical_align = TextVerticalAlign::Baseline
+			)] > (translate = [1., 2.]),
+		]);
+
+		let mut collect = Collect(vec![]);
+		shape.visit(&mut collect);
+		assert_eq!(
+			collect.0,
+			vec![
+				(Point2::new(0., 0.), true, vec![1]),
+				(Point2::new(1., 2.), false, vec![2, 0]),
+			]
+		);
+	}
+
+	#[test]
+	fn stop_and_skip() {
+		struct Count(usize, VisitFlow);
+		impl<'a> ShapeVisitor<'a> for Count {
+			fn enter(&mut self, shape: &'a Shape, _: &VisitContext) -> VisitFlow {
+				self.0 += 1;
+				match shape {
+					Shape::Group(_) if self.0 > 1 => self.1,
+					_ => VisitFlow::Continue,
+				}
+			}
+		}
+
+		let shape = dessin!([{ debug_layer() }, Circle()]);
+
+		let mut count = Count(0, VisitFlow::SkipChildren);
+		shape.visit(&mut count);
+		assert_eq!(count.0, 3);
+
+		let mut count = Count(0, VisitFlow::Stop);
+		shape.visit(&mut count);
+		assert_eq!(count.0, 2);
+	}
+
+	#[test]
+	fn helpers() {
+		let mut shape = dessin!([{ debug_layer() }, [Text(text = "keep"), { debug_layer() }]]);
+
+		assert_eq!(shape.find_by_metadata("layer").len(), 2);
+
+		shape.retain(|shape, _| match shape {
+			Shape::Group(Group { metadata, .. }) => {
+				!metadata.contains(&("layer".into(), "debug".into()))
+			}
+			_ => true,
+		});
+		assert!(shape.find_by_metadata("layer").is_empty());
+
+		shape.map_texts(|text, context| {
+			text.text = text.text.to_uppercase();
+			text.local_transform = context.parent_transform * Translation2::new(1., 0.);
+		});
+
+		let mut texts = vec![];
+		shape.map_texts(|text, _| texts.push(text.text.clone()));
+		assert_eq!(texts, vec!["KEEP".to_string()]);
+	}
+}
@@ -202,7 +202,7 @@ impl From<Symbol432> for Shape {
 				fill = Srgba::new(0.0, 0.008, 0.376, 0.6),
 				text = "echnologies",
 				font_size = 2.5,
-				font_weight = FontWeight::Bold,
+				font_weight = FontWeight::BOLD,
 				translate = [0.5, -10.],
 				vertical_align = TextVerticalAlign::Center,
 				align = TextAlign::Left,
@@ -211,7 +211,7 @@ impl From<Symbol432> for Shape {
 				fill = Srgb::<f32>::from_format(named::BLACK).into_linear(),
 				text = "3",
 				font_size = 7.,
-				font_weight = FontWeight::Regular,
+				font_weight = FontWeight::REGULAR,
 				translate = [1., 1.],
 				vertical_align = TextVerticalAlign::Center,
 				align = TextAlign::Left,
@@ -220,7 +220,7 @@ impl From<Symbol432> for Shape {
 				fill = Srgb::<f32>::from_format(named::BLACK).into_linear(),
 				text = "2",
 				font_size = 7.,
-				font_weight = FontWeight::Regular,
+				font_weight = FontWeight::REGULAR,
 				translate = [1., -5.6],
 				vertical_align = TextVerticalAlign::Center,
 				align = TextAlign::Left,